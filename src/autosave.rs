@@ -0,0 +1,111 @@
+use std::env;
+use std::str::FromStr;
+use std::time::Duration;
+
+const AUTOSAVE_ENV_VAR: &str = "FINANCE_AUTOSAVE";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AutosavePolicy {
+    Disabled,
+    OnChange,
+    Interval(Duration),
+    OnQuit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AutosaveTrigger {
+    Change,
+    Tick,
+    Quit,
+}
+
+impl AutosavePolicy {
+    pub fn from_env() -> Result<Self, String> {
+        match env::var(AUTOSAVE_ENV_VAR) {
+            Ok(value) => value.parse(),
+            Err(_) => Ok(Self::Disabled),
+        }
+    }
+
+    /// Whether the app should save when `trigger` fires, given the time
+    /// elapsed since the last successful save. Every enabled policy saves on
+    /// quit, so a failed on-change or interval save gets one more chance.
+    pub fn should_save(&self, trigger: AutosaveTrigger, since_last_save: Duration) -> bool {
+        match (self, trigger) {
+            (Self::Disabled, _) => false,
+            (_, AutosaveTrigger::Quit) => true,
+            (Self::OnChange, AutosaveTrigger::Change) => true,
+            (Self::Interval(interval), AutosaveTrigger::Tick) => since_last_save >= *interval,
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for AutosavePolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid_value = || {
+            format!(
+                "Invalid {AUTOSAVE_ENV_VAR} value \"{value}\"! Expected off, change, quit or interval:<seconds>."
+            )
+        };
+
+        match value.trim() {
+            "" | "off" => Ok(Self::Disabled),
+            "change" => Ok(Self::OnChange),
+            "quit" => Ok(Self::OnQuit),
+            other => {
+                let seconds = other
+                    .strip_prefix("interval:")
+                    .and_then(|seconds| seconds.parse::<u64>().ok())
+                    .filter(|seconds| *seconds > 0)
+                    .ok_or_else(invalid_value)?;
+
+                Ok(Self::Interval(Duration::from_secs(seconds)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{AutosavePolicy, AutosaveTrigger};
+
+    #[test]
+    fn test_parse_policies() {
+        assert_eq!("off".parse(), Ok(AutosavePolicy::Disabled));
+        assert_eq!("change".parse(), Ok(AutosavePolicy::OnChange));
+        assert_eq!("quit".parse(), Ok(AutosavePolicy::OnQuit));
+        assert_eq!(
+            "interval:30".parse(),
+            Ok(AutosavePolicy::Interval(Duration::from_secs(30)))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_policies() {
+        assert!("sometimes".parse::<AutosavePolicy>().is_err());
+        assert!("interval:".parse::<AutosavePolicy>().is_err());
+        assert!("interval:0".parse::<AutosavePolicy>().is_err());
+    }
+
+    #[test]
+    fn test_should_save() {
+        let interval = AutosavePolicy::Interval(Duration::from_secs(10));
+
+        assert!(!interval.should_save(AutosaveTrigger::Tick, Duration::from_secs(5)));
+        assert!(interval.should_save(AutosaveTrigger::Tick, Duration::from_secs(10)));
+        assert!(interval.should_save(AutosaveTrigger::Quit, Duration::ZERO));
+        assert!(!interval.should_save(AutosaveTrigger::Change, Duration::from_secs(60)));
+
+        assert!(AutosavePolicy::OnChange.should_save(AutosaveTrigger::Change, Duration::ZERO));
+        assert!(!AutosavePolicy::OnChange.should_save(AutosaveTrigger::Tick, Duration::ZERO));
+        assert!(AutosavePolicy::OnChange.should_save(AutosaveTrigger::Quit, Duration::ZERO));
+
+        assert!(AutosavePolicy::OnQuit.should_save(AutosaveTrigger::Quit, Duration::ZERO));
+        assert!(!AutosavePolicy::Disabled.should_save(AutosaveTrigger::Quit, Duration::ZERO));
+    }
+}
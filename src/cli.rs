@@ -0,0 +1,140 @@
+use neo_budget::attachments::AttachmentStore;
+use neo_budget::merge::{merge, MergeSide};
+use neo_budget::repository::{
+    EncryptedFinanceRepository, EnvFinanceRepository, EnvJSONFinanceRepository, FinanceRepository,
+};
+
+/// Runs the command named by the first argument, or returns `None` if there is
+/// no command and the TUI should start.
+pub fn run_command(args: &[String]) -> Option<Result<(), String>> {
+    let (command, command_args) = args.split_first()?;
+
+    Some(match command.as_str() {
+        "merge" => merge_command(command_args),
+        "encrypt" => encrypt_command(command_args),
+        "decrypt" => decrypt_command(command_args),
+        "attachments" => attachments_command(command_args),
+        _ => Err(format!(
+            "Unknown command {command}! Expected merge, encrypt, decrypt or attachments."
+        )),
+    })
+}
+
+/// `merge <base> <ours> <theirs>`: three-way merges finance files into
+/// `<ours>`, so it can be used as a git merge driver with `merge %O %A %B`.
+/// The files are JSON or journals as `FINANCE_FILE_FORMAT` says; encrypted
+/// files can't be merged. Conflicts keep our version and make the command
+/// fail.
+fn merge_command(args: &[String]) -> Result<(), String> {
+    let [base_path, our_path, their_path] = args else {
+        return Err(
+            "Usage: neo-budget merge <base> <ours> <theirs> (JSON or journal files only)"
+                .to_owned(),
+        );
+    };
+
+    let load = |path: &str| -> Result<_, String> {
+        Ok(EnvFinanceRepository::unencrypted_from_env_at(path)?.load()?)
+    };
+    let our_repo = EnvFinanceRepository::unencrypted_from_env_at(our_path)?;
+
+    let result = merge(
+        &load(base_path)?,
+        &load(our_path)?,
+        &load(their_path)?,
+        |_| MergeSide::Ours,
+    );
+
+    our_repo.save(&result.finance)?;
+
+    if result.conflicts.is_empty() {
+        return Ok(());
+    }
+
+    for conflict in &result.conflicts {
+        eprintln!("Conflict: {conflict}");
+    }
+
+    Err(format!(
+        "{} conflict(s) were resolved with our version in {our_path}.",
+        result.conflicts.len()
+    ))
+}
+
+/// `encrypt <file>`: encrypts a plain JSON finance file in place.
+fn encrypt_command(args: &[String]) -> Result<(), String> {
+    let [path] = args else {
+        return Err("Usage: neo-budget encrypt <file>".to_owned());
+    };
+
+    let finance = EnvJSONFinanceRepository::new(path).load()?;
+
+    let passphrase = prompt_passphrase("New passphrase: ")?;
+    let confirmation = prompt_passphrase("Repeat passphrase: ")?;
+
+    EncryptedFinanceRepository::create(path, &passphrase, &confirmation)?.save(&finance)?;
+
+    let attachments = AttachmentStore::next_to(path);
+    if attachments.directory().exists() {
+        eprintln!(
+            "The attachments in {} are still unencrypted!",
+            attachments.directory().display()
+        );
+    }
+
+    Ok(())
+}
+
+/// `decrypt <file>`: decrypts an encrypted finance file in place.
+fn decrypt_command(args: &[String]) -> Result<(), String> {
+    let [path] = args else {
+        return Err("Usage: neo-budget decrypt <file>".to_owned());
+    };
+
+    let passphrase = prompt_passphrase("Passphrase: ")?;
+    let finance = EncryptedFinanceRepository::unlock(path, &passphrase)?.load()?;
+
+    EnvJSONFinanceRepository::new(path).save(&finance)
+}
+
+/// `attachments`: lists attachment files no log points at, and attachments
+/// whose file is missing, for the data file in `FINANCE_FILE_PATH`. Fails if
+/// it finds any.
+fn attachments_command(args: &[String]) -> Result<(), String> {
+    if !args.is_empty() {
+        return Err("Usage: neo-budget attachments".to_owned());
+    }
+
+    let passphrase = match EnvFinanceRepository::requires_passphrase_from_env() {
+        true => Some(prompt_passphrase("Passphrase: ")?),
+        false => None,
+    };
+    let finance = EnvFinanceRepository::from_env_with_passphrase(passphrase.as_deref())?.load()?;
+
+    let check = AttachmentStore::from_env()?.check(&finance);
+
+    for path in &check.orphaned {
+        println!("Orphaned: {}", path.display());
+    }
+
+    for (index, attachment) in &check.missing {
+        println!(
+            "Missing: {} ({}) of log {}",
+            attachment.name,
+            attachment.file_name,
+            index + 1
+        );
+    }
+
+    match check.orphaned.len() + check.missing.len() {
+        0 => {
+            println!("All attachments are in place.");
+            Ok(())
+        }
+        problems => Err(format!("Found {problems} attachment problem(s).")),
+    }
+}
+
+fn prompt_passphrase(prompt: &str) -> Result<String, String> {
+    rpassword::prompt_password(prompt).map_err(|_| "Couldn't read the passphrase!".to_owned())
+}
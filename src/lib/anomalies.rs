@@ -0,0 +1,138 @@
+use gregorian::YearMonth;
+use itertools::Itertools;
+
+use crate::finance::Price;
+
+/// How many times its trailing median an amount has to be to be flagged,
+/// unless its category sets its own threshold.
+pub const DEFAULT_ANOMALY_THRESHOLD: f64 = 1.5;
+
+/// How many earlier amounts the median is taken over: months for category
+/// spending, logs for product prices.
+pub const ANOMALY_TRAILING_WINDOW: usize = 6;
+
+/// How many earlier amounts there must be before anything is flagged.
+pub const MIN_ANOMALY_HISTORY: usize = 3;
+
+/// What an anomaly was found in.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnomalySubject {
+    /// A month's spending in a category.
+    Category(String),
+    /// The price of a product in the log at `index`.
+    Log { index: usize, product: String },
+}
+
+/// An amount unusually high compared to the ones before it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Anomaly {
+    pub subject: AnomalySubject,
+    pub year_month: YearMonth,
+    pub amount: Price,
+    /// Median of the amounts before it.
+    pub median: Price,
+    pub threshold: f64,
+}
+
+impl Anomaly {
+    /// Flags `amount` if it's over `threshold` times the median of the last
+    /// `ANOMALY_TRAILING_WINDOW` amounts of `history`, oldest first.
+    pub fn detect(
+        subject: AnomalySubject,
+        year_month: YearMonth,
+        amount: Price,
+        history: &[Price],
+        threshold: f64,
+    ) -> Option<Self> {
+        if history.len() < MIN_ANOMALY_HISTORY {
+            return None;
+        }
+
+        let trailing = &history[history.len().saturating_sub(ANOMALY_TRAILING_WINDOW)..];
+        let median = median(trailing)?;
+
+        (median > 0.0 && amount > median * threshold).then_some(Self {
+            subject,
+            year_month,
+            amount,
+            median,
+            threshold,
+        })
+    }
+
+    /// How many times the median the amount is.
+    pub fn ratio(&self) -> f64 {
+        self.amount / self.median
+    }
+
+    pub fn explanation(&self) -> String {
+        let what = match &self.subject {
+            AnomalySubject::Category(category) => format!("Spending on {category}"),
+            AnomalySubject::Log { product, .. } => format!("Price of {product}"),
+        };
+
+        format!(
+            "{what} in {} was {:.2}, {:.1} times the usual {:.2} (threshold {:.1})",
+            self.year_month,
+            self.amount,
+            self.ratio(),
+            self.median,
+            self.threshold
+        )
+    }
+}
+
+fn median(values: &[Price]) -> Option<Price> {
+    let sorted = values
+        .iter()
+        .copied()
+        .sorted_by(|left, right| left.total_cmp(right))
+        .collect::<Vec<Price>>();
+
+    let middle = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        length if length % 2 == 0 => Some((sorted[middle - 1] + sorted[middle]) / 2.0),
+        _ => Some(sorted[middle]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+
+    use super::{Anomaly, AnomalySubject};
+
+    #[test]
+    fn test_detect() {
+        let year_month = YearMonth::new(2024, Month::May);
+        let food = || AnomalySubject::Category("Food".to_owned());
+
+        assert_eq!(
+            Anomaly::detect(food(), year_month, 500.0, &[100.0, 120.0], 1.5),
+            None
+        );
+        assert_eq!(
+            Anomaly::detect(
+                food(),
+                year_month,
+                170.0,
+                &[100.0, 120.0, 110.0, 130.0],
+                1.5
+            ),
+            None
+        );
+
+        // Only the last months count, so the old 1000.00 doesn't raise the
+        // median.
+        let history = [
+            1000.0, 1000.0, 1000.0, 100.0, 120.0, 110.0, 130.0, 90.0, 100.0,
+        ];
+        let anomaly = Anomaly::detect(food(), year_month, 210.0, &history, 1.5).unwrap();
+        assert_eq!(anomaly.median, 105.0);
+        assert_eq!(
+            anomaly.explanation(),
+            "Spending on Food in 2024-05 was 210.00, 2.0 times the usual 105.00 (threshold 1.5)"
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use itertools::Itertools;
+use ring::digest::{digest, SHA256};
+
+use crate::finance::Finance;
+use crate::repository::EnvFinanceRepository;
+
+/// A file attached to a log, like the receipt of a purchase. The file is
+/// stored under `file_name` in an `AttachmentStore`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Attachment {
+    /// The name of the file that was attached.
+    pub name: String,
+    /// The SHA-256 of the contents, plus the original extension.
+    pub file_name: String,
+}
+
+impl Attachment {
+    /// Whether `file_name` is a hex SHA-256 with an optional alphanumeric
+    /// extension, as `AttachmentStore::store` names files. Anything else,
+    /// like "../secret", could point outside the store.
+    pub fn is_valid_file_name(file_name: &str) -> bool {
+        let (hash, extension) = match file_name.split_once('.') {
+            Some((hash, extension)) => (hash, Some(extension)),
+            None => (file_name, None),
+        };
+
+        hash.len() == 64
+            && hash
+                .chars()
+                .all(|char| char.is_ascii_digit() || ('a'..='f').contains(&char))
+            && extension.is_none_or(is_valid_extension)
+    }
+}
+
+fn is_valid_extension(extension: &str) -> bool {
+    !extension.is_empty() && extension.chars().all(|char| char.is_ascii_alphanumeric())
+}
+
+/// Attachment files with no log pointing at them, and attachments whose file
+/// is gone.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AttachmentCheck {
+    pub orphaned: Vec<PathBuf>,
+    /// Each missing attachment with the position of its log.
+    pub missing: Vec<(usize, Attachment)>,
+}
+
+/// A content-addressed directory of attachment files. Files are named after
+/// their contents, so attaching the same file twice stores it once.
+#[derive(Clone, Debug)]
+pub struct AttachmentStore {
+    directory: PathBuf,
+}
+
+impl AttachmentStore {
+    pub fn new(directory: &Path) -> Self {
+        Self {
+            directory: directory.to_owned(),
+        }
+    }
+
+    /// The store next to the data file at `data_path`: "finance.json" keeps
+    /// its attachments in "finance.attachments".
+    pub fn next_to(data_path: &str) -> Self {
+        Self::new(&Path::new(data_path).with_extension("attachments"))
+    }
+
+    /// The store next to the data file at `FINANCE_FILE_PATH`.
+    pub fn from_env() -> Result<Self, String> {
+        env::var("FINANCE_FILE_PATH")
+            .map(|data_path| Self::next_to(&data_path))
+            .map_err(|_| "Couldn't load FINANCE_FILE_PATH variable! Is is set?".to_owned())
+    }
+
+    /// The store to attach new files to, from the environment. Attachments
+    /// are stored as they are, so encrypted data files can't have them.
+    pub fn for_attaching_from_env() -> Result<Self, String> {
+        if EnvFinanceRepository::requires_passphrase_from_env() {
+            return Err(
+                "Attachments can't be encrypted, so encrypted Finance files can't have them!"
+                    .to_owned(),
+            );
+        }
+
+        Self::from_env()
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Where the file of `attachment` is, or `None` if its file name isn't
+    /// one the store gives.
+    pub fn path_of(&self, attachment: &Attachment) -> Option<PathBuf> {
+        Attachment::is_valid_file_name(&attachment.file_name)
+            .then(|| self.directory.join(&attachment.file_name))
+    }
+
+    /// Copies the file at `source` into the store.
+    pub fn store(&self, source: &Path) -> Result<Attachment, String> {
+        let source_name = source.display();
+        let contents =
+            fs::read(source).map_err(|_| format!("Couldn't read file {source_name}!"))?;
+
+        let hash = digest(&SHA256, &contents)
+            .as_ref()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        // Extensions that couldn't be part of a stored file name are dropped.
+        let file_name = match source.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if is_valid_extension(extension) => format!("{hash}.{extension}"),
+            _ => hash,
+        };
+
+        let attachment = Attachment {
+            name: source
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or(file_name.clone()),
+            file_name,
+        };
+
+        let target = self.directory.join(&attachment.file_name);
+        if !target.exists() {
+            let directory = self.directory.display();
+            fs::create_dir_all(&self.directory)
+                .map_err(|_| format!("Couldn't create attachment directory {directory}!"))?;
+            fs::write(&target, contents)
+                .map_err(|_| format!("Couldn't write attachment to {directory}!"))?;
+        }
+
+        Ok(attachment)
+    }
+
+    /// Compares the files in the store with the attachments of `finance`.
+    pub fn check(&self, finance: &Finance) -> AttachmentCheck {
+        let attachments = finance
+            .logs
+            .iter()
+            .enumerate()
+            .flat_map(|(index, log)| {
+                log.attachments
+                    .iter()
+                    .map(move |attachment| (index, attachment.clone()))
+            })
+            .collect::<Vec<(usize, Attachment)>>();
+
+        let referenced = attachments
+            .iter()
+            .filter_map(|(_, attachment)| self.path_of(attachment))
+            .collect::<BTreeSet<PathBuf>>();
+
+        let orphaned = fs::read_dir(&self.directory)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && !referenced.contains(path))
+            .sorted()
+            .collect();
+
+        let missing = attachments
+            .into_iter()
+            .filter(|(_, attachment)| !self.path_of(attachment).is_some_and(|path| path.is_file()))
+            .collect();
+
+        AttachmentCheck { orphaned, missing }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use gregorian::{Month, YearMonth};
+    use tempfile::tempdir;
+
+    use super::{Attachment, AttachmentStore};
+    use crate::finance::{Finance, FinanceLog};
+
+    #[test]
+    fn test_next_to() {
+        assert_eq!(
+            AttachmentStore::next_to("/data/finance.json").directory(),
+            Path::new("/data/finance.attachments")
+        );
+    }
+
+    #[test]
+    fn test_store_and_check() {
+        let dir = tempdir().unwrap();
+        let receipt_path = dir.path().join("receipt.pdf");
+        fs::write(&receipt_path, "receipt").unwrap();
+
+        let store = AttachmentStore::new(&dir.path().join("attachments"));
+        let attachment = store.store(&receipt_path).unwrap();
+
+        assert_eq!(attachment.name, "receipt.pdf");
+        assert_eq!(
+            attachment.file_name.split_once('.'),
+            Some((
+                "6f32860910ca0fb2a20c7fda143666b09dbf8db5238195c90a586fb542ff0cad",
+                "pdf"
+            ))
+        );
+        assert_eq!(
+            fs::read_to_string(store.path_of(&attachment).unwrap()).unwrap(),
+            "receipt"
+        );
+        assert_eq!(store.store(&receipt_path), Ok(attachment.clone()));
+
+        let missing = Attachment {
+            name: "gone.jpg".to_owned(),
+            file_name: format!("{}.jpg", "0".repeat(64)),
+        };
+        let year_month = YearMonth::new(2024, Month::May);
+        let finance = Finance::new()
+            .with_log(FinanceLog::new("tv", 500.0, year_month).with_attachment(missing.clone()));
+
+        let check = store.check(&finance);
+        assert_eq!(check.orphaned, vec![store.path_of(&attachment).unwrap()]);
+        assert_eq!(check.missing, vec![(0, missing)]);
+
+        let finance = finance
+            .with_log(FinanceLog::new("radio", 50.0, year_month).with_attachment(attachment));
+        assert!(store.check(&finance).orphaned.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_file_names() {
+        let hash = "6f32860910ca0fb2a20c7fda143666b09dbf8db5238195c90a586fb542ff0cad";
+        assert!(Attachment::is_valid_file_name(hash));
+        assert!(Attachment::is_valid_file_name(&format!("{hash}.pdf")));
+
+        for file_name in [
+            "../../etc/passwd".to_owned(),
+            "/etc/passwd".to_owned(),
+            format!("{hash}.pdf/../../secret"),
+            format!("{hash}."),
+            hash.to_uppercase(),
+            hash[1..].to_owned(),
+        ] {
+            assert!(!Attachment::is_valid_file_name(&file_name), "{file_name}");
+        }
+
+        let store = AttachmentStore::new(Path::new("attachments"));
+        let escaping = Attachment {
+            name: "passwd".to_owned(),
+            file_name: "../../etc/passwd".to_owned(),
+        };
+        assert_eq!(store.path_of(&escaping), None);
+    }
+}
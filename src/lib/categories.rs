@@ -0,0 +1,101 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::finance::Price;
+
+/// Separates the levels of a category path, as in "Food:Restaurants".
+pub const DEFAULT_CATEGORY_SEPARATOR: &str = ":";
+
+/// The first `depth` levels of `category`, or all of them if it has fewer.
+pub fn truncate_category(category: &str, separator: &str, depth: usize) -> String {
+    category
+        .split(separator)
+        .take(depth.max(1))
+        .collect::<Vec<&str>>()
+        .join(separator)
+}
+
+/// Whether `category` is `parent` or one of its subcategories.
+pub fn is_within_category(category: &str, parent: &str, separator: &str) -> bool {
+    category == parent
+        || category
+            .strip_prefix(parent)
+            .is_some_and(|rest| rest.starts_with(separator))
+}
+
+/// Totals of a category and, recursively, of its subcategories. A
+/// category's total includes all of its subcategories.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CategoryTree {
+    pub total: Price,
+    pub children: BTreeMap<String, CategoryTree>,
+}
+
+impl CategoryTree {
+    /// Builds the tree of the categories in `totals`, whose keys are full
+    /// category paths. The root holds the grand total.
+    pub fn from_totals(totals: &HashMap<String, Price>, separator: &str) -> Self {
+        let mut root = Self::default();
+
+        for (category, total) in totals {
+            root.total += total;
+
+            let mut node = &mut root;
+            for level in category.split(separator) {
+                node = node.children.entry(level.to_owned()).or_default();
+                node.total += total;
+            }
+        }
+
+        root
+    }
+
+    /// The node at `path`, given as its levels from the root.
+    pub fn get(&self, path: &[String]) -> Option<&CategoryTree> {
+        path.iter()
+            .try_fold(self, |node, level| node.children.get(level))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{truncate_category, CategoryTree};
+
+    #[test]
+    fn test_truncate_category() {
+        assert_eq!(
+            truncate_category("Food:Restaurants:Sushi", ":", 2),
+            "Food:Restaurants"
+        );
+        assert_eq!(truncate_category("Food", ":", 3), "Food");
+        assert_eq!(truncate_category("Food/Groceries", "/", 0), "Food");
+    }
+
+    #[test]
+    fn test_from_totals() {
+        let totals = HashMap::from([
+            ("Food:Restaurants".to_owned(), 30.0),
+            ("Food:Groceries".to_owned(), 50.0),
+            ("Food".to_owned(), 5.0),
+            ("Rent".to_owned(), 500.0),
+        ]);
+
+        let tree = CategoryTree::from_totals(&totals, ":");
+
+        assert_eq!(tree.total, 585.0);
+        assert_eq!(tree.children.len(), 2);
+
+        let food = tree.get(&["Food".to_owned()]).unwrap();
+        assert_eq!(food.total, 85.0);
+        assert_eq!(
+            food.children.keys().cloned().collect::<Vec<String>>(),
+            vec!["Groceries", "Restaurants"]
+        );
+        assert_eq!(
+            tree.get(&["Food".to_owned(), "Groceries".to_owned()])
+                .map(|groceries| groceries.total),
+            Some(50.0)
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+use gregorian::YearMonth;
+
+use crate::categories::is_within_category;
+use crate::finance::Price;
+
+/// Money set aside for a category, month by month, in zero-based
+/// budgeting. Spending on the category or its subcategories draws it down.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Envelope {
+    category: String,
+    /// Whether what's left at the end of a month stays in the envelope.
+    /// Otherwise it goes back to be budgeted again.
+    pub rollover: bool,
+    pub allocations: BTreeMap<YearMonth, Price>,
+}
+
+impl Envelope {
+    pub fn new(category: &str, rollover: bool) -> Self {
+        Self {
+            category: category.to_owned(),
+            rollover,
+            allocations: BTreeMap::new(),
+        }
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    /// Sets what is allocated to the envelope in `year_month`, replacing any
+    /// earlier allocation for that month.
+    pub fn with_allocation(self, year_month: YearMonth, amount: Price) -> Self {
+        Self {
+            allocations: self
+                .allocations
+                .into_iter()
+                .chain([(year_month, amount)])
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn allocated_in(&self, year_month: YearMonth) -> Price {
+        self.allocations.get(&year_month).copied().unwrap_or(0.0)
+    }
+
+    /// Whether spending on `category` draws from this envelope.
+    pub fn covers(&self, category: &str, separator: &str) -> bool {
+        is_within_category(category, &self.category, separator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+
+    use super::Envelope;
+
+    #[test]
+    fn test_with_allocation() {
+        let may = YearMonth::new(2024, Month::May);
+        let envelope = Envelope::new("Food", true)
+            .with_allocation(may, 100.0)
+            .with_allocation(may, 150.0);
+
+        assert_eq!(envelope.allocated_in(may), 150.0);
+        assert_eq!(envelope.allocated_in(may.next()), 0.0);
+    }
+
+    #[test]
+    fn test_covers() {
+        let envelope = Envelope::new("Food", false);
+
+        assert!(envelope.covers("Food", ":"));
+        assert!(envelope.covers("Food:Groceries", ":"));
+        assert!(!envelope.covers("Foodstuff", ":"));
+        assert!(!envelope.covers("Rent", ":"));
+    }
+}
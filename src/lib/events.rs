@@ -0,0 +1,283 @@
+use itertools::Itertools;
+
+use crate::envelopes::Envelope;
+use crate::finance::{Account, Finance, FinanceLog, Goal, Product, Transfer};
+use crate::loans::Loan;
+use crate::recurring::RecurringLog;
+use crate::shared::Settlement;
+
+/// A single change to a `Finance`. Replaying a sequence of events on an empty
+/// `Finance` rebuilds the state they describe.
+// Most events are logs being added, so boxing them would only add indirection.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum FinanceEvent {
+    LogAdded(FinanceLog),
+    LogRemoved(usize),
+    ProductAdded(Product),
+    ProductRecategorized { product: String, category: String },
+    AccountSet(Account),
+    TransferAdded(Transfer),
+    TransferRemoved(usize),
+    RecurringAdded(RecurringLog),
+    RecurringRemoved(usize),
+    GoalSet(Goal),
+    LoanSet(Loan),
+    EnvelopeSet(Envelope),
+    TaxBucketSet { category: String, bucket: String },
+    AnomalyThresholdSet { category: String, threshold: f64 },
+    PersonAdded(String),
+    SettlementAdded(Settlement),
+    SettlementRemoved(usize),
+    SubscriptionDismissed(String),
+}
+
+impl FinanceEvent {
+    /// Returns the events that turn `before` into `after`.
+    pub fn between(before: &Finance, after: &Finance) -> Vec<FinanceEvent> {
+        let before_products = before.products();
+        let product_events = after
+            .products()
+            .into_values()
+            .sorted_by(|left, right| left.id().cmp(right.id()))
+            .filter_map(|product| match before_products.get(product.id()) {
+                None => Some(FinanceEvent::ProductAdded(product)),
+
+                Some(old_product) if old_product.category != product.category => {
+                    Some(FinanceEvent::ProductRecategorized {
+                        product: product.id().to_owned(),
+                        category: product.category,
+                    })
+                }
+
+                Some(_) => None,
+            });
+
+        let before_accounts = before.accounts();
+        let account_events = after
+            .accounts()
+            .into_values()
+            .sorted_by(|left, right| left.id().cmp(right.id()))
+            .filter(|account| before_accounts.get(account.id()) != Some(account))
+            .map(FinanceEvent::AccountSet);
+
+        let before_goals = before.goals();
+        let goal_events = after
+            .goals()
+            .into_values()
+            .sorted_by(|left, right| left.id().cmp(right.id()))
+            .filter(|goal| before_goals.get(goal.id()) != Some(goal))
+            .map(FinanceEvent::GoalSet);
+
+        let before_loans = before.loans();
+        let loan_events = after
+            .loans()
+            .into_values()
+            .sorted_by(|left, right| left.id().cmp(right.id()))
+            .filter(|loan| before_loans.get(loan.id()) != Some(loan))
+            .map(FinanceEvent::LoanSet);
+
+        let before_envelopes = before.envelopes();
+        let envelope_events = after
+            .envelopes()
+            .into_values()
+            .sorted_by(|left, right| left.category().cmp(right.category()))
+            .filter(|envelope| before_envelopes.get(envelope.category()) != Some(envelope))
+            .map(FinanceEvent::EnvelopeSet);
+
+        let before_tax_buckets = before.tax_buckets();
+        let tax_bucket_events = after
+            .tax_buckets()
+            .into_iter()
+            .sorted()
+            .filter(|(category, bucket)| before_tax_buckets.get(category) != Some(bucket))
+            .map(|(category, bucket)| FinanceEvent::TaxBucketSet { category, bucket });
+
+        let before_thresholds = before.anomaly_thresholds();
+        let threshold_events = after
+            .anomaly_thresholds()
+            .into_iter()
+            .sorted_by(|left, right| left.0.cmp(&right.0))
+            .filter(|(category, threshold)| before_thresholds.get(category) != Some(threshold))
+            .map(|(category, threshold)| FinanceEvent::AnomalyThresholdSet {
+                category,
+                threshold,
+            });
+
+        let before_people = before.people();
+        let person_events = after
+            .people()
+            .into_iter()
+            .filter(|person| !before_people.contains(person))
+            .map(FinanceEvent::PersonAdded);
+
+        let before_dismissed = before.dismissed_subscriptions();
+        let dismissed_events = after
+            .dismissed_subscriptions()
+            .into_iter()
+            .filter(|name| !before_dismissed.contains(name))
+            .map(FinanceEvent::SubscriptionDismissed);
+
+        let log_events = list_events(
+            &before.logs,
+            &after.logs,
+            FinanceEvent::LogRemoved,
+            FinanceEvent::LogAdded,
+        );
+
+        let transfer_events = list_events(
+            &before.transfers,
+            &after.transfers,
+            FinanceEvent::TransferRemoved,
+            FinanceEvent::TransferAdded,
+        );
+
+        let recurring_events = list_events(
+            &before.recurring,
+            &after.recurring,
+            FinanceEvent::RecurringRemoved,
+            FinanceEvent::RecurringAdded,
+        );
+
+        let settlement_events = list_events(
+            &before.settlements,
+            &after.settlements,
+            FinanceEvent::SettlementRemoved,
+            FinanceEvent::SettlementAdded,
+        );
+
+        product_events
+            .chain(account_events)
+            .chain(goal_events)
+            .chain(loan_events)
+            .chain(envelope_events)
+            .chain(tax_bucket_events)
+            .chain(threshold_events)
+            .chain(person_events)
+            .chain(dismissed_events)
+            .chain(log_events)
+            .chain(transfer_events)
+            .chain(recurring_events)
+            .chain(settlement_events)
+            .collect()
+    }
+
+    pub fn apply(&self, finance: Finance) -> Finance {
+        match self {
+            FinanceEvent::LogAdded(log) => finance.with_log(log.clone()),
+            FinanceEvent::LogRemoved(index) => finance.without_log(*index),
+            FinanceEvent::ProductAdded(product) => finance.with_product(product),
+            FinanceEvent::ProductRecategorized { product, category } => {
+                finance.with_product(&Product::new(product, category))
+            }
+            FinanceEvent::AccountSet(account) => finance.with_account(account),
+            FinanceEvent::TransferAdded(transfer) => finance.with_transfer(transfer.clone()),
+            FinanceEvent::TransferRemoved(index) => finance.without_transfer(*index),
+            FinanceEvent::RecurringAdded(recurring) => finance.with_recurring(recurring.clone()),
+            FinanceEvent::RecurringRemoved(index) => finance.without_recurring(*index),
+            FinanceEvent::GoalSet(goal) => finance.with_goal(goal),
+            FinanceEvent::LoanSet(loan) => finance.with_loan(loan),
+            FinanceEvent::EnvelopeSet(envelope) => finance.with_envelope(envelope),
+            FinanceEvent::TaxBucketSet { category, bucket } => {
+                finance.with_tax_bucket(category, bucket)
+            }
+            FinanceEvent::AnomalyThresholdSet {
+                category,
+                threshold,
+            } => finance.with_anomaly_threshold(category, *threshold),
+            FinanceEvent::PersonAdded(person) => finance.with_person(person),
+            FinanceEvent::SettlementAdded(settlement) => {
+                finance.with_settlement(settlement.clone())
+            }
+            FinanceEvent::SettlementRemoved(index) => finance.without_settlement(*index),
+            FinanceEvent::SubscriptionDismissed(name) => finance.with_dismissed_subscription(name),
+        }
+    }
+}
+
+/// Turns `before` into `after` by removing the items past their common
+/// prefix, last first, and then adding the rest of `after`.
+fn list_events<T: Clone + PartialEq>(
+    before: &[T],
+    after: &[T],
+    removed: impl Fn(usize) -> FinanceEvent,
+    added: impl Fn(T) -> FinanceEvent,
+) -> Vec<FinanceEvent> {
+    let common_items = before
+        .iter()
+        .zip(after.iter())
+        .take_while(|(before_item, after_item)| before_item == after_item)
+        .count();
+
+    let removed_events = (common_items..before.len()).rev().map(removed);
+    let added_events = after[common_items..].iter().cloned().map(added);
+
+    removed_events.chain(added_events).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+
+    use crate::finance::{Account, AccountKind, Finance, FinanceLog, Product, Transfer};
+
+    use super::FinanceEvent;
+
+    #[test]
+    fn test_between_and_apply() {
+        let before = Finance::new()
+            .with_product(&Product::new("prod1", "cat1"))
+            .with_product(&Product::new("prod2", "cat2"))
+            .with_log(log("prod1", 10.0))
+            .with_log(log("prod2", 20.0));
+
+        let after = before
+            .clone()
+            .without_log(1)
+            .with_log(log("prod3", 30.0))
+            .with_product(&Product::new("prod2", "cat3"))
+            .with_product(&Product::new("prod3", "cat3"))
+            .with_account(&Account::new("cash", AccountKind::Cash, 50.0))
+            .with_transfer(transfer());
+
+        let events = FinanceEvent::between(&before, &after);
+
+        assert_eq!(
+            events,
+            vec![
+                FinanceEvent::ProductRecategorized {
+                    product: "prod2".to_owned(),
+                    category: "cat3".to_owned()
+                },
+                FinanceEvent::ProductAdded(Product::new("prod3", "cat3")),
+                FinanceEvent::AccountSet(Account::new("cash", AccountKind::Cash, 50.0)),
+                FinanceEvent::LogRemoved(1),
+                FinanceEvent::LogAdded(log("prod3", 30.0)),
+                FinanceEvent::TransferAdded(transfer()),
+            ]
+        );
+
+        let replayed = events
+            .iter()
+            .fold(before, |finance, event| event.apply(finance));
+
+        assert_eq!(replayed, after);
+    }
+
+    #[test]
+    fn test_between_equal_finances() {
+        let finance = Finance::new()
+            .with_product(&Product::new("prod1", "cat1"))
+            .with_log(log("prod1", 10.0));
+
+        assert!(FinanceEvent::between(&finance, &finance).is_empty());
+    }
+
+    fn log(product: &str, price: f64) -> FinanceLog {
+        FinanceLog::new(product, price, YearMonth::new(2023, Month::May))
+    }
+
+    fn transfer() -> Transfer {
+        Transfer::new("checking", "cash", 25.0, YearMonth::new(2023, Month::May))
+    }
+}
@@ -0,0 +1,27 @@
+/// Joins `fields` into a CSV line, quoting the fields that need it.
+pub fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            match field.contains([',', '"', '\n']) {
+                true => format!("\"{}\"", field.replace('"', "\"\"")),
+                false => field.to_owned(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::csv_line;
+
+    #[test]
+    fn test_csv_line() {
+        assert_eq!(
+            csv_line(&["plain", "with, comma", "say \"hi\""]),
+            "plain,\"with, comma\",\"say \"\"hi\"\"\""
+        );
+    }
+}
@@ -1,16 +1,43 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
-use gregorian::YearMonth;
+use gregorian::{Date, YearMonth};
+
+use crate::attachments::Attachment;
+use crate::envelopes::Envelope;
+use crate::loans::Loan;
+use crate::recurring::RecurringLog;
+use crate::reimbursements::Reimbursable;
+use crate::shared::{Settlement, SharedExpense};
 
 pub type Price = f64;
 type ProductName = String;
 type ProductId = String;
 type Category = String;
+type AccountId = String;
+type GoalId = String;
+type LoanId = String;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Finance {
     pub logs: Vec<FinanceLog>,
     products: HashMap<ProductId, Product>,
+    accounts: HashMap<AccountId, Account>,
+    pub transfers: Vec<Transfer>,
+    pub recurring: Vec<RecurringLog>,
+    goals: HashMap<GoalId, Goal>,
+    loans: HashMap<LoanId, Loan>,
+    /// Who shared logs can be split between.
+    people: BTreeSet<String>,
+    pub settlements: Vec<Settlement>,
+    envelopes: HashMap<Category, Envelope>,
+    /// Names of detected subscriptions that aren't subscriptions after all.
+    dismissed_subscriptions: BTreeSet<String>,
+    /// The tax bucket of each category, which also covers its
+    /// subcategories.
+    tax_buckets: HashMap<Category, String>,
+    /// Anomaly threshold of each category, which also covers its
+    /// subcategories.
+    anomaly_thresholds: HashMap<Category, f64>,
 }
 
 impl Finance {
@@ -18,6 +45,17 @@ impl Finance {
         Self {
             logs: Vec::new(),
             products: HashMap::new(),
+            accounts: HashMap::new(),
+            transfers: Vec::new(),
+            recurring: Vec::new(),
+            goals: HashMap::new(),
+            loans: HashMap::new(),
+            people: BTreeSet::new(),
+            settlements: Vec::new(),
+            envelopes: HashMap::new(),
+            dismissed_subscriptions: BTreeSet::new(),
+            tax_buckets: HashMap::new(),
+            anomaly_thresholds: HashMap::new(),
         }
     }
 
@@ -25,6 +63,150 @@ impl Finance {
         self.products.clone()
     }
 
+    pub fn accounts(&self) -> HashMap<AccountId, Account> {
+        self.accounts.clone()
+    }
+
+    pub fn with_account(self, account: &Account) -> Self {
+        Self {
+            accounts: self
+                .accounts
+                .into_iter()
+                .chain(vec![(account.id.to_owned(), account.clone())])
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn goals(&self) -> HashMap<GoalId, Goal> {
+        self.goals.clone()
+    }
+
+    pub fn with_goal(self, goal: &Goal) -> Self {
+        Self {
+            goals: self
+                .goals
+                .into_iter()
+                .chain(vec![(goal.id.to_owned(), goal.clone())])
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn loans(&self) -> HashMap<LoanId, Loan> {
+        self.loans.clone()
+    }
+
+    pub fn with_loan(self, loan: &Loan) -> Self {
+        Self {
+            loans: self
+                .loans
+                .into_iter()
+                .chain(vec![(loan.id().to_owned(), loan.clone())])
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn envelopes(&self) -> HashMap<Category, Envelope> {
+        self.envelopes.clone()
+    }
+
+    pub fn with_envelope(self, envelope: &Envelope) -> Self {
+        Self {
+            envelopes: self
+                .envelopes
+                .into_iter()
+                .chain(vec![(envelope.category().to_owned(), envelope.clone())])
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn people(&self) -> BTreeSet<String> {
+        self.people.clone()
+    }
+
+    pub fn with_person(self, person: &str) -> Self {
+        Self {
+            people: self
+                .people
+                .into_iter()
+                .chain(Some(person.to_owned()))
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn tax_buckets(&self) -> HashMap<Category, String> {
+        self.tax_buckets.clone()
+    }
+
+    pub fn with_tax_bucket(self, category: &str, bucket: &str) -> Self {
+        Self {
+            tax_buckets: self
+                .tax_buckets
+                .into_iter()
+                .chain(vec![(category.to_owned(), bucket.to_owned())])
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn anomaly_thresholds(&self) -> HashMap<Category, f64> {
+        self.anomaly_thresholds.clone()
+    }
+
+    pub fn with_anomaly_threshold(self, category: &str, threshold: f64) -> Self {
+        Self {
+            anomaly_thresholds: self
+                .anomaly_thresholds
+                .into_iter()
+                .chain(vec![(category.to_owned(), threshold)])
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn dismissed_subscriptions(&self) -> BTreeSet<String> {
+        self.dismissed_subscriptions.clone()
+    }
+
+    pub fn with_dismissed_subscription(self, name: &str) -> Self {
+        Self {
+            dismissed_subscriptions: self
+                .dismissed_subscriptions
+                .into_iter()
+                .chain(Some(name.to_owned()))
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn with_settlement(self, settlement: Settlement) -> Self {
+        Self {
+            settlements: self
+                .settlements
+                .into_iter()
+                .chain(Some(settlement))
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn without_settlement(self, index: usize) -> Self {
+        Self {
+            settlements: self
+                .settlements
+                .into_iter()
+                .enumerate()
+                .filter(|(settlement_index, _)| *settlement_index != index)
+                .map(|(_, settlement)| settlement)
+                .collect(),
+            ..self
+        }
+    }
+
     pub fn with_log(self, log: FinanceLog) -> Self {
         Self {
             logs: self.logs.into_iter().chain(Some(log)).collect(),
@@ -32,6 +214,96 @@ impl Finance {
         }
     }
 
+    pub fn with_replaced_log(self, index: usize, log: FinanceLog) -> Self {
+        Self {
+            logs: self
+                .logs
+                .into_iter()
+                .enumerate()
+                .map(|(log_index, old_log)| match log_index == index {
+                    true => log.clone(),
+                    false => old_log,
+                })
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn without_log(self, index: usize) -> Self {
+        Self {
+            logs: self
+                .logs
+                .into_iter()
+                .enumerate()
+                .filter(|(log_index, _)| *log_index != index)
+                .map(|(_, log)| log)
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn with_transfer(self, transfer: Transfer) -> Self {
+        Self {
+            transfers: self.transfers.into_iter().chain(Some(transfer)).collect(),
+            ..self
+        }
+    }
+
+    pub fn without_transfer(self, index: usize) -> Self {
+        Self {
+            transfers: self
+                .transfers
+                .into_iter()
+                .enumerate()
+                .filter(|(transfer_index, _)| *transfer_index != index)
+                .map(|(_, transfer)| transfer)
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn with_recurring(self, recurring: RecurringLog) -> Self {
+        Self {
+            recurring: self.recurring.into_iter().chain(Some(recurring)).collect(),
+            ..self
+        }
+    }
+
+    pub fn without_recurring(self, index: usize) -> Self {
+        Self {
+            recurring: self
+                .recurring
+                .into_iter()
+                .enumerate()
+                .filter(|(recurring_index, _)| *recurring_index != index)
+                .map(|(_, recurring)| recurring)
+                .collect(),
+            ..self
+        }
+    }
+
+    /// Adds a log for every recurring occurrence due up to and including
+    /// `today` that wasn't added yet.
+    pub fn materialize_due(self, today: Date) -> Self {
+        let mut logs = self.logs;
+        let recurring = self
+            .recurring
+            .iter()
+            .map(|recurring| {
+                let (recurring, due_logs) = recurring.materialize_until(today);
+                logs.extend(due_logs);
+
+                recurring
+            })
+            .collect();
+
+        Self {
+            logs,
+            recurring,
+            ..self
+        }
+    }
+
     pub fn with_product(self, product: &Product) -> Self {
         Self {
             products: self
@@ -43,6 +315,14 @@ impl Finance {
         }
     }
 
+    /// Every tag used by a log.
+    pub fn tags(&self) -> BTreeSet<String> {
+        self.logs
+            .iter()
+            .flat_map(|log| log.tags.iter().cloned())
+            .collect()
+    }
+
     pub fn get_category_for(&self, product_id: &str) -> Option<Category> {
         self.products.get(product_id).map(|p| p.category.clone())
     }
@@ -54,7 +334,7 @@ impl Default for Finance {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Product {
     // TODO Add name to product
     id: ProductId,
@@ -68,13 +348,69 @@ impl Product {
             category: category.to_owned(),
         }
     }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccountKind {
+    Checking,
+    CreditCard,
+    Cash,
+    Savings,
+}
+
+/// Where money is kept. Logs attached to an account draw its balance down
+/// from `opening_balance`; logs with a negative price add to it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Account {
+    id: AccountId,
+    pub kind: AccountKind,
+    pub opening_balance: Price,
+}
+
+impl Account {
+    pub fn new(id: &str, kind: AccountKind, opening_balance: Price) -> Self {
+        Self {
+            id: id.to_owned(),
+            kind,
+            opening_balance,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
 }
 
+/// A transaction. A split log covers several products at once, like a
+/// supermarket receipt: `product` then only names the transaction, and `price`
+/// is the sum of its splits.
 #[derive(Clone, Debug, PartialEq)]
 pub struct FinanceLog {
     pub product: ProductName,
     pub price: Price,
     pub year_month: YearMonth,
+    pub account: Option<AccountId>,
+    pub splits: Vec<LogSplit>,
+    pub tags: BTreeSet<String>,
+    pub payee: Option<String>,
+    pub note: Option<String>,
+    pub location: Option<String>,
+    /// How much of the product was bought, in `unit`. `price` is still the
+    /// total.
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+    pub shared: Option<SharedExpense>,
+    pub attachments: Vec<Attachment>,
+    pub reimbursable: Option<Reimbursable>,
+    /// How many monthly installments, from `year_month` on, `price` is paid
+    /// in. `None` if it's paid at once.
+    pub installments: Option<u32>,
+    /// Tax bucket of the whole log, over the one of its category.
+    pub tax_bucket: Option<String>,
 }
 
 impl FinanceLog {
@@ -83,6 +419,225 @@ impl FinanceLog {
             product: product.to_owned(),
             price,
             year_month,
+            account: None,
+            splits: Vec::new(),
+            tags: BTreeSet::new(),
+            payee: None,
+            note: None,
+            location: None,
+            quantity: None,
+            unit: None,
+            shared: None,
+            attachments: Vec::new(),
+            reimbursable: None,
+            installments: None,
+            tax_bucket: None,
+        }
+    }
+
+    pub fn split(name: &str, splits: Vec<LogSplit>, year_month: YearMonth) -> Self {
+        Self {
+            price: splits.iter().fold(0.0, |total, split| total + split.price),
+            splits,
+            ..Self::new(name, 0.0, year_month)
+        }
+    }
+
+    pub fn with_tag(self, tag: &str) -> Self {
+        Self {
+            tags: self.tags.into_iter().chain(Some(tag.to_owned())).collect(),
+            ..self
+        }
+    }
+
+    pub fn with_payee(self, payee: &str) -> Self {
+        Self {
+            payee: Some(payee.to_owned()),
+            ..self
+        }
+    }
+
+    pub fn with_quantity(self, quantity: f64, unit: &str) -> Self {
+        Self {
+            quantity: Some(quantity),
+            unit: Some(unit.to_owned()),
+            ..self
+        }
+    }
+
+    pub fn with_shared(self, shared: SharedExpense) -> Self {
+        Self {
+            shared: Some(shared),
+            ..self
+        }
+    }
+
+    pub fn with_attachment(self, attachment: Attachment) -> Self {
+        Self {
+            attachments: self
+                .attachments
+                .into_iter()
+                .chain(Some(attachment))
+                .collect(),
+            ..self
+        }
+    }
+
+    pub fn with_reimbursable(self, reimbursable: Reimbursable) -> Self {
+        Self {
+            reimbursable: Some(reimbursable),
+            ..self
+        }
+    }
+
+    pub fn with_tax_bucket(self, bucket: &str) -> Self {
+        Self {
+            tax_bucket: Some(bucket.to_owned()),
+            ..self
+        }
+    }
+
+    pub fn with_installments(self, installments: u32) -> Self {
+        Self {
+            installments: Some(installments),
+            ..self
+        }
+    }
+
+    /// The log as it's paid: one log per installment, a month apart, each
+    /// with its share of the price and splits. Installments are rounded to
+    /// cents and the last one takes what's left.
+    pub fn installment_logs(&self) -> Vec<FinanceLog> {
+        let count = match self.installments {
+            Some(count) if count > 1 => count,
+            _ => return vec![self.clone()],
+        };
+
+        let installment = (self.price / f64::from(count) * 100.0).round() / 100.0;
+        let last = self.price - installment * f64::from(count - 1);
+
+        (0..count)
+            .map(|index| {
+                let price = match index + 1 == count {
+                    true => last,
+                    false => installment,
+                };
+                let share = match self.price {
+                    0.0 => 0.0,
+                    total => price / total,
+                };
+
+                FinanceLog {
+                    price,
+                    year_month: self.year_month.add_months(index as i32),
+                    splits: self
+                        .splits
+                        .iter()
+                        .map(|split| LogSplit::new(&split.product, split.price * share))
+                        .collect(),
+                    installments: None,
+                    ..self.clone()
+                }
+            })
+            .collect()
+    }
+
+    pub fn is_split(&self) -> bool {
+        !self.splits.is_empty()
+    }
+
+    /// What the log spent on each product: its splits, or the whole price on
+    /// its product if it isn't split.
+    pub fn lines(&self) -> Vec<LogSplit> {
+        match self.is_split() {
+            true => self.splits.clone(),
+            false => vec![LogSplit::new(&self.product, self.price)],
+        }
+    }
+
+    pub fn with_account(self, account: &str) -> Self {
+        Self {
+            account: Some(account.to_owned()),
+            ..self
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogSplit {
+    pub product: ProductName,
+    pub price: Price,
+}
+
+impl LogSplit {
+    pub fn new(product: &str, price: Price) -> Self {
+        Self {
+            product: product.to_owned(),
+            price,
+        }
+    }
+}
+
+/// Something to save up for, like a car or an emergency fund.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Goal {
+    id: GoalId,
+    pub target: Price,
+    pub deadline: Option<YearMonth>,
+    pub contributions: Vec<GoalContribution>,
+}
+
+impl Goal {
+    pub fn new(id: &str, target: Price, deadline: Option<YearMonth>) -> Self {
+        Self {
+            id: id.to_owned(),
+            target,
+            deadline,
+            contributions: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn with_contribution(self, contribution: GoalContribution) -> Self {
+        Self {
+            contributions: self
+                .contributions
+                .into_iter()
+                .chain(Some(contribution))
+                .collect(),
+            ..self
+        }
+    }
+}
+
+/// Money put towards a goal: a log or a transfer, by position like
+/// everywhere else.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GoalContribution {
+    Log(usize),
+    Transfer(usize),
+}
+
+/// Money moved from one account to another. It changes both balances, but
+/// isn't income or spending.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transfer {
+    pub from: AccountId,
+    pub to: AccountId,
+    pub amount: Price,
+    pub year_month: YearMonth,
+}
+
+impl Transfer {
+    pub fn new(from: &str, to: &str, amount: Price, year_month: YearMonth) -> Self {
+        Self {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            amount,
+            year_month,
         }
     }
 }
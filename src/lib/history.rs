@@ -0,0 +1,131 @@
+use im::Vector;
+
+/// Undo/redo stacks of states of type `T`, each step labeled with a
+/// description of what it changed.
+#[derive(Clone, Debug)]
+pub struct History<T: Clone> {
+    undo_steps: Vector<HistoryStep<T>>,
+    redo_steps: Vector<HistoryStep<T>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryStep<T> {
+    pub description: String,
+    pub before: T,
+    pub after: T,
+}
+
+impl<T: Clone> History<T> {
+    pub fn new() -> Self {
+        Self {
+            undo_steps: Vector::new(),
+            redo_steps: Vector::new(),
+        }
+    }
+
+    /// Records a step from `before` to `after`. Any undone steps are
+    /// discarded, since they no longer follow from the current state.
+    pub fn with_step(self, description: &str, before: T, after: T) -> Self {
+        let mut undo_steps = self.undo_steps;
+        undo_steps.push_back(HistoryStep {
+            description: description.to_owned(),
+            before,
+            after,
+        });
+
+        Self {
+            undo_steps,
+            redo_steps: Vector::new(),
+        }
+    }
+
+    /// Returns the history with its last step undone, along with that step,
+    /// or `None` if there is nothing to undo.
+    pub fn undo(self) -> Option<(Self, HistoryStep<T>)> {
+        let mut undo_steps = self.undo_steps;
+        let step = undo_steps.pop_back()?;
+
+        let mut redo_steps = self.redo_steps;
+        redo_steps.push_back(step.clone());
+
+        Some((
+            Self {
+                undo_steps,
+                redo_steps,
+            },
+            step,
+        ))
+    }
+
+    /// Returns the history with its last undone step redone, along with that
+    /// step, or `None` if there is nothing to redo.
+    pub fn redo(self) -> Option<(Self, HistoryStep<T>)> {
+        let mut redo_steps = self.redo_steps;
+        let step = redo_steps.pop_back()?;
+
+        let mut undo_steps = self.undo_steps;
+        undo_steps.push_back(step.clone());
+
+        Some((
+            Self {
+                undo_steps,
+                redo_steps,
+            },
+            step,
+        ))
+    }
+
+    /// Steps that can be undone, oldest first.
+    pub fn undo_steps(&self) -> Vec<HistoryStep<T>> {
+        self.undo_steps.iter().cloned().collect()
+    }
+
+    /// Steps that can be redone, in the order they would be redone.
+    pub fn redo_steps(&self) -> Vec<HistoryStep<T>> {
+        self.redo_steps.iter().rev().cloned().collect()
+    }
+}
+
+impl<T: Clone> Default for History<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::History;
+
+    #[test]
+    fn test_undo_and_redo() {
+        let history = History::new().with_step("one", 0, 1).with_step("two", 1, 2);
+
+        let (history, step) = history.undo().expect("Expected a step to undo!");
+        assert_eq!(step.description, "two");
+        assert_eq!(step.before, 1);
+
+        let (history, step) = history.undo().expect("Expected a step to undo!");
+        assert_eq!(step.before, 0);
+        assert!(history.clone().undo().is_none());
+
+        let (history, step) = history.redo().expect("Expected a step to redo!");
+        assert_eq!(step.description, "one");
+        assert_eq!(step.after, 1);
+
+        assert_eq!(history.undo_steps().len(), 1);
+        assert_eq!(history.redo_steps()[0].description, "two");
+    }
+
+    #[test]
+    fn test_new_step_discards_redo_steps() {
+        let (history, _) = History::new()
+            .with_step("one", 0, 1)
+            .undo()
+            .expect("Expected a step to undo!");
+
+        let history = history.with_step("other", 0, 5);
+
+        assert!(history.redo_steps().is_empty());
+        assert!(history.redo().is_none());
+    }
+}
@@ -0,0 +1,143 @@
+use gregorian::YearMonth;
+
+use crate::finance::Price;
+
+/// Borrowed money paid back in equal monthly payments. Logs of
+/// `payment_product` are the payments made on it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Loan {
+    id: String,
+    pub principal: Price,
+    /// Yearly interest rate, in percent.
+    pub annual_rate: f64,
+    pub term_months: u32,
+    /// Month of the first payment.
+    pub start: YearMonth,
+    pub payment_product: String,
+}
+
+/// One payment of an amortization schedule. `balance` is what is still owed
+/// after it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AmortizationEntry {
+    pub year_month: YearMonth,
+    pub payment: Price,
+    pub principal: Price,
+    pub interest: Price,
+    pub balance: Price,
+}
+
+impl Loan {
+    pub fn new(
+        id: &str,
+        principal: Price,
+        annual_rate: f64,
+        term_months: u32,
+        start: YearMonth,
+        payment_product: &str,
+    ) -> Self {
+        Self {
+            id: id.to_owned(),
+            principal,
+            annual_rate,
+            term_months,
+            start,
+            payment_product: payment_product.to_owned(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn monthly_rate(&self) -> f64 {
+        self.annual_rate / 12.0 / 100.0
+    }
+
+    /// The fixed payment that pays the loan off in `term_months`.
+    pub fn monthly_payment(&self) -> Price {
+        let rate = self.monthly_rate();
+        let term = f64::from(self.term_months.max(1));
+
+        match rate {
+            0.0 => self.principal / term,
+            _ => self.principal * rate / (1.0 - (1.0 + rate).powf(-term)),
+        }
+    }
+
+    /// Splits `payment` into the interest `balance` accrued over `months`,
+    /// compounded monthly, and the principal it pays off. The principal is
+    /// negative when the payment doesn't cover the interest.
+    pub fn split_payment(&self, balance: Price, payment: Price, months: u32) -> (Price, Price) {
+        let interest = (0..months).fold(0.0, |interest, _| {
+            interest + (balance + interest) * self.monthly_rate()
+        });
+
+        (payment - interest, interest)
+    }
+
+    pub fn schedule(&self) -> Vec<AmortizationEntry> {
+        let payment = self.monthly_payment();
+        let mut balance = self.principal;
+
+        (0..self.term_months)
+            .map(|month| {
+                let (principal, interest) = self.split_payment(balance, payment, 1);
+                balance = (balance - principal).max(0.0);
+
+                AmortizationEntry {
+                    year_month: self.start.add_months(month as i32),
+                    payment,
+                    principal,
+                    interest,
+                    balance,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+
+    use super::Loan;
+
+    #[test]
+    fn test_schedule() {
+        let loan = Loan::new(
+            "car",
+            10_000.0,
+            6.0,
+            24,
+            YearMonth::new(2024, Month::January),
+            "car loan",
+        );
+
+        let schedule = loan.schedule();
+        let first = &schedule[0];
+        let last = schedule.last().unwrap();
+
+        assert_eq!(schedule.len(), 24);
+        assert!((loan.monthly_payment() - 443.21).abs() < 0.01);
+        assert!((first.interest - 50.0).abs() < 1e-9);
+        assert!((first.principal - 393.21).abs() < 0.01);
+        assert_eq!(last.year_month, YearMonth::new(2025, Month::December));
+        assert!(last.balance.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_interest_free_schedule() {
+        let loan = Loan::new(
+            "phone",
+            1_200.0,
+            0.0,
+            12,
+            YearMonth::new(2024, Month::March),
+            "phone",
+        );
+
+        assert_eq!(loan.monthly_payment(), 100.0);
+        assert!(loan.schedule().iter().all(|entry| entry.interest == 0.0));
+    }
+}
@@ -0,0 +1,974 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+use crate::envelopes::Envelope;
+use crate::finance::{Account, Finance, FinanceLog, Goal, GoalContribution, Product, Transfer};
+use crate::loans::Loan;
+use crate::recurring::RecurringLog;
+use crate::reimbursements::Reimbursable;
+use crate::shared::Settlement;
+
+/// Which version wins a conflict.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MergeSide {
+    Ours,
+    Theirs,
+}
+
+/// A change both sides made differently to the same entry. `None`
+/// means the side doesn't have it.
+// Conflicts are few and short-lived, so boxing the bigger variants isn't worth it.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum MergeConflict {
+    ProductCategory {
+        product: String,
+        base: Option<String>,
+        ours: Option<String>,
+        theirs: Option<String>,
+    },
+    Log {
+        index: usize,
+        base: FinanceLog,
+        ours: Option<FinanceLog>,
+        theirs: Option<FinanceLog>,
+    },
+    Account {
+        account: String,
+        base: Option<Account>,
+        ours: Option<Account>,
+        theirs: Option<Account>,
+    },
+    Goal {
+        goal: String,
+        base: Option<Goal>,
+        ours: Option<Goal>,
+        theirs: Option<Goal>,
+    },
+    Loan {
+        loan: String,
+        base: Option<Loan>,
+        ours: Option<Loan>,
+        theirs: Option<Loan>,
+    },
+    Envelope {
+        category: String,
+        base: Option<Envelope>,
+        ours: Option<Envelope>,
+        theirs: Option<Envelope>,
+    },
+    TaxBucket {
+        category: String,
+        base: Option<String>,
+        ours: Option<String>,
+        theirs: Option<String>,
+    },
+    AnomalyThreshold {
+        category: String,
+        base: Option<f64>,
+        ours: Option<f64>,
+        theirs: Option<f64>,
+    },
+    Transfer {
+        index: usize,
+        base: Transfer,
+        ours: Option<Transfer>,
+        theirs: Option<Transfer>,
+    },
+    Recurring {
+        index: usize,
+        base: RecurringLog,
+        ours: Option<RecurringLog>,
+        theirs: Option<RecurringLog>,
+    },
+    Settlement {
+        index: usize,
+        base: Settlement,
+        ours: Option<Settlement>,
+        theirs: Option<Settlement>,
+    },
+}
+
+pub struct MergeResult {
+    pub finance: Finance,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Merges the changes `ours` and `theirs` made to their common ancestor
+/// `base`. Changes made by only one side, and logs added by either side, are
+/// merged automatically; `resolve` picks the winner of every conflict.
+///
+/// Logs, transfers, recurring logs and settlements have no identity besides their position, so the ones
+/// present in `base` are matched by index. This fits how they are edited:
+/// they are only ever appended, or changed in place. Look-alike items added
+/// on both sides are all kept, since they may well be separate purchases,
+/// except for logs both sides materialized from the same recurring log.
+pub fn merge(
+    base: &Finance,
+    ours: &Finance,
+    theirs: &Finance,
+    resolve: impl Fn(&MergeConflict) -> MergeSide,
+) -> MergeResult {
+    let mut conflicts = Vec::new();
+    let mut finance = Finance::new();
+
+    let shared_logs = shared_materialized_logs(base, ours, theirs);
+
+    // The logs and transfers theirs added move behind ours, so theirs
+    // references to them, from goals and reimbursements, have to follow.
+    let theirs = &with_merged_positions(
+        theirs,
+        &merged_positions(&base.logs, &ours.logs, &theirs.logs, &shared_logs),
+        &merged_positions(&base.transfers, &ours.transfers, &theirs.transfers, &[]),
+    );
+
+    let categories = |finance: &Finance| {
+        finance
+            .products()
+            .into_iter()
+            .map(|(id, product)| (id, product.category))
+            .collect()
+    };
+
+    let merged_categories = merge_keyed(
+        categories(base),
+        categories(ours),
+        categories(theirs),
+        |product, base, ours, theirs| MergeConflict::ProductCategory {
+            product,
+            base,
+            ours,
+            theirs,
+        },
+        &resolve,
+        &mut conflicts,
+    );
+
+    for (product_id, category) in merged_categories {
+        finance = finance.with_product(&Product::new(&product_id, &category));
+    }
+
+    let merged_accounts = merge_keyed(
+        base.accounts(),
+        ours.accounts(),
+        theirs.accounts(),
+        |account, base, ours, theirs| MergeConflict::Account {
+            account,
+            base,
+            ours,
+            theirs,
+        },
+        &resolve,
+        &mut conflicts,
+    );
+
+    for account in merged_accounts.values() {
+        finance = finance.with_account(account);
+    }
+
+    let merged_goals = merge_keyed(
+        base.goals(),
+        ours.goals(),
+        theirs.goals(),
+        |goal, base, ours, theirs| MergeConflict::Goal {
+            goal,
+            base,
+            ours,
+            theirs,
+        },
+        &resolve,
+        &mut conflicts,
+    );
+
+    for goal in merged_goals.values() {
+        finance = finance.with_goal(goal);
+    }
+
+    let merged_loans = merge_keyed(
+        base.loans(),
+        ours.loans(),
+        theirs.loans(),
+        |loan, base, ours, theirs| MergeConflict::Loan {
+            loan,
+            base,
+            ours,
+            theirs,
+        },
+        &resolve,
+        &mut conflicts,
+    );
+
+    for loan in merged_loans.values() {
+        finance = finance.with_loan(loan);
+    }
+
+    let merged_envelopes = merge_keyed(
+        base.envelopes(),
+        ours.envelopes(),
+        theirs.envelopes(),
+        |category, base, ours, theirs| MergeConflict::Envelope {
+            category,
+            base,
+            ours,
+            theirs,
+        },
+        &resolve,
+        &mut conflicts,
+    );
+
+    for envelope in merged_envelopes.values() {
+        finance = finance.with_envelope(envelope);
+    }
+
+    let merged_tax_buckets = merge_keyed(
+        base.tax_buckets(),
+        ours.tax_buckets(),
+        theirs.tax_buckets(),
+        |category, base, ours, theirs| MergeConflict::TaxBucket {
+            category,
+            base,
+            ours,
+            theirs,
+        },
+        &resolve,
+        &mut conflicts,
+    );
+
+    for (category, bucket) in merged_tax_buckets {
+        finance = finance.with_tax_bucket(&category, &bucket);
+    }
+
+    let merged_thresholds = merge_keyed(
+        base.anomaly_thresholds(),
+        ours.anomaly_thresholds(),
+        theirs.anomaly_thresholds(),
+        |category, base, ours, theirs| MergeConflict::AnomalyThreshold {
+            category,
+            base,
+            ours,
+            theirs,
+        },
+        &resolve,
+        &mut conflicts,
+    );
+
+    for (category, threshold) in merged_thresholds {
+        finance = finance.with_anomaly_threshold(&category, threshold);
+    }
+
+    let merged_logs = merge_indexed(
+        &base.logs,
+        &ours.logs,
+        &theirs.logs,
+        &shared_logs,
+        |index, base, ours, theirs| MergeConflict::Log {
+            index,
+            base,
+            ours,
+            theirs,
+        },
+        &resolve,
+        &mut conflicts,
+    );
+
+    for log in merged_logs {
+        finance = finance.with_log(log);
+    }
+
+    let merged_transfers = merge_indexed(
+        &base.transfers,
+        &ours.transfers,
+        &theirs.transfers,
+        &[],
+        |index, base, ours, theirs| MergeConflict::Transfer {
+            index,
+            base,
+            ours,
+            theirs,
+        },
+        &resolve,
+        &mut conflicts,
+    );
+
+    for transfer in merged_transfers {
+        finance = finance.with_transfer(transfer);
+    }
+
+    let merged_recurring = merge_indexed(
+        &base.recurring,
+        &ours.recurring,
+        &theirs.recurring,
+        &[],
+        |index, base, ours, theirs| MergeConflict::Recurring {
+            index,
+            base,
+            ours,
+            theirs,
+        },
+        &resolve,
+        &mut conflicts,
+    );
+
+    for recurring in merged_recurring {
+        finance = finance.with_recurring(recurring);
+    }
+
+    // People are only ever added, so everyone either side knows is kept.
+    for person in ours.people().union(&theirs.people()) {
+        finance = finance.with_person(person);
+    }
+
+    // Likewise for dismissed subscriptions.
+    for name in ours
+        .dismissed_subscriptions()
+        .union(&theirs.dismissed_subscriptions())
+    {
+        finance = finance.with_dismissed_subscription(name);
+    }
+
+    let merged_settlements = merge_indexed(
+        &base.settlements,
+        &ours.settlements,
+        &theirs.settlements,
+        &[],
+        |index, base, ours, theirs| MergeConflict::Settlement {
+            index,
+            base,
+            ours,
+            theirs,
+        },
+        &resolve,
+        &mut conflicts,
+    );
+
+    for settlement in merged_settlements {
+        finance = finance.with_settlement(settlement);
+    }
+
+    MergeResult { finance, conflicts }
+}
+
+/// Merges maps entry by entry, in key order so conflicts come up in a stable
+/// order.
+fn merge_keyed<T: Clone + PartialEq>(
+    base: HashMap<String, T>,
+    ours: HashMap<String, T>,
+    theirs: HashMap<String, T>,
+    to_conflict: impl Fn(String, Option<T>, Option<T>, Option<T>) -> MergeConflict,
+    resolve: &impl Fn(&MergeConflict) -> MergeSide,
+    conflicts: &mut Vec<MergeConflict>,
+) -> HashMap<String, T> {
+    let keys = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .cloned()
+        .collect::<BTreeSet<String>>();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let merged_value = merge_values(
+                base.get(&key).cloned(),
+                ours.get(&key).cloned(),
+                theirs.get(&key).cloned(),
+                |base, ours, theirs| to_conflict(key.clone(), base, ours, theirs),
+                resolve,
+                conflicts,
+            );
+
+            merged_value.map(|value| (key, value))
+        })
+        .collect()
+}
+
+/// Merges lists item by item for the items in `base`, then appends the items
+/// each side added: ours first, then theirs. `shared` are items known to be
+/// added by both sides, which are kept once.
+fn merge_indexed<T: Clone + PartialEq>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+    shared: &[T],
+    to_conflict: impl Fn(usize, T, Option<T>, Option<T>) -> MergeConflict,
+    resolve: &impl Fn(&MergeConflict) -> MergeSide,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Vec<T> {
+    let mut merged = Vec::new();
+
+    for (index, base_item) in base.iter().enumerate() {
+        let merged_item = merge_values(
+            Some(base_item.clone()),
+            ours.get(index).cloned(),
+            theirs.get(index).cloned(),
+            |_, ours, theirs| to_conflict(index, base_item.clone(), ours, theirs),
+            resolve,
+            conflicts,
+        );
+
+        merged.extend(merged_item);
+    }
+
+    merged.extend(ours.iter().skip(base.len()).cloned());
+
+    let their_additions = theirs
+        .iter()
+        .zip(merged_positions(base, ours, theirs, shared))
+        .skip(base.len())
+        .filter(|(_, position)| *position >= ours.len().max(base.len()))
+        .map(|(item, _)| item.clone());
+
+    merged.extend(their_additions);
+
+    merged
+}
+
+/// Where each of `theirs` items ends up once merged by `merge_indexed`: the
+/// ones in `base` keep their position, and the ones theirs added go after
+/// ours' additions, or where ours added the same item if it's `shared`.
+fn merged_positions<T: PartialEq>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+    shared: &[T],
+) -> Vec<usize> {
+    let mut our_additions = ours.iter().skip(base.len()).map(Some).collect::<Vec<_>>();
+    let mut shared = shared.iter().map(Some).collect::<Vec<_>>();
+    let mut next_position = ours.len().max(base.len());
+
+    theirs
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            if index < base.len() {
+                return index;
+            }
+
+            let shared_position = shared.iter().position(|shared| *shared == Some(item));
+            let our_position = our_additions.iter().position(|ours| *ours == Some(item));
+
+            match shared_position.zip(our_position) {
+                Some((shared_position, our_position)) => {
+                    shared[shared_position] = None;
+                    our_additions[our_position] = None;
+                    base.len() + our_position
+                }
+                None => {
+                    next_position += 1;
+                    next_position - 1
+                }
+            }
+        })
+        .collect()
+}
+
+/// The logs both sides materialized from the same occurrences of the
+/// recurring logs in `base`. Only these are known to be one log added twice.
+fn shared_materialized_logs(base: &Finance, ours: &Finance, theirs: &Finance) -> Vec<FinanceLog> {
+    let template = |recurring: &RecurringLog| RecurringLog {
+        materialized: 0,
+        ..recurring.clone()
+    };
+
+    base.recurring
+        .iter()
+        .zip(&ours.recurring)
+        .zip(&theirs.recurring)
+        .filter(|((base, ours), theirs)| {
+            template(base) == template(ours) && template(ours) == template(theirs)
+        })
+        .flat_map(|((base, ours), theirs)| {
+            (base.materialized..ours.materialized.min(theirs.materialized))
+                .filter_map(|index| base.occurrence_log(index))
+        })
+        .collect()
+}
+
+/// `finance` with its references to logs and transfers moved to the
+/// positions given, as found by `merged_positions`.
+fn with_merged_positions(
+    finance: &Finance,
+    log_positions: &[usize],
+    transfer_positions: &[usize],
+) -> Finance {
+    let position =
+        |positions: &[usize], index: usize| positions.get(index).copied().unwrap_or(index);
+
+    let mut moved = finance.clone();
+    moved.logs = finance
+        .logs
+        .iter()
+        .cloned()
+        .map(|log| FinanceLog {
+            reimbursable: log.reimbursable.map(|reimbursable| Reimbursable {
+                reimbursed_by: reimbursable
+                    .reimbursed_by
+                    .map(|index| position(log_positions, index)),
+                ..reimbursable
+            }),
+            ..log
+        })
+        .collect();
+
+    finance
+        .goals()
+        .into_values()
+        .fold(moved, |finance, mut goal| {
+            goal.contributions = goal
+                .contributions
+                .iter()
+                .map(|contribution| match *contribution {
+                    GoalContribution::Log(index) => {
+                        GoalContribution::Log(position(log_positions, index))
+                    }
+                    GoalContribution::Transfer(index) => {
+                        GoalContribution::Transfer(position(transfer_positions, index))
+                    }
+                })
+                .collect();
+
+            finance.with_goal(&goal)
+        })
+}
+
+fn merge_values<T: Clone + PartialEq>(
+    base: Option<T>,
+    ours: Option<T>,
+    theirs: Option<T>,
+    to_conflict: impl Fn(Option<T>, Option<T>, Option<T>) -> MergeConflict,
+    resolve: &impl Fn(&MergeConflict) -> MergeSide,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Option<T> {
+    if ours == theirs || theirs == base {
+        return ours;
+    }
+
+    if ours == base {
+        return theirs;
+    }
+
+    let conflict = to_conflict(base, ours.clone(), theirs.clone());
+    let side = resolve(&conflict);
+    conflicts.push(conflict);
+
+    match side {
+        MergeSide::Ours => ours,
+        MergeSide::Theirs => theirs,
+    }
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_missing = |value: &Option<String>| value.clone().unwrap_or("(none)".to_owned());
+        let describe_threshold = |threshold: &Option<f64>| match threshold {
+            Some(threshold) => format!("{threshold}"),
+            None => "(none)".to_owned(),
+        };
+        let describe_log = |log: &Option<FinanceLog>| match log {
+            Some(log) => format!("{} {:.2} in {}", log.product, log.price, log.year_month),
+            None => "(removed)".to_owned(),
+        };
+        let describe_account = |account: &Option<Account>| match account {
+            Some(account) => format!(
+                "{:?} opening at {:.2}",
+                account.kind, account.opening_balance
+            ),
+            None => "(none)".to_owned(),
+        };
+        let describe_goal = |goal: &Option<Goal>| match goal {
+            Some(goal) => format!(
+                "target {:.2} with {} contributions",
+                goal.target,
+                goal.contributions.len()
+            ),
+            None => "(none)".to_owned(),
+        };
+        let describe_loan = |loan: &Option<Loan>| match loan {
+            Some(loan) => format!(
+                "{:.2} at {}% over {} months from {}",
+                loan.principal, loan.annual_rate, loan.term_months, loan.start
+            ),
+            None => "(none)".to_owned(),
+        };
+        let describe_envelope = |envelope: &Option<Envelope>| match envelope {
+            Some(envelope) => format!(
+                "{} allocations{}",
+                envelope.allocations.len(),
+                match envelope.rollover {
+                    true => ", rolling over",
+                    false => "",
+                }
+            ),
+            None => "(none)".to_owned(),
+        };
+        let describe_transfer = |transfer: &Option<Transfer>| match transfer {
+            Some(transfer) => format!(
+                "{:.2} from {} to {} in {}",
+                transfer.amount, transfer.from, transfer.to, transfer.year_month
+            ),
+            None => "(removed)".to_owned(),
+        };
+        let describe_recurring = |recurring: &Option<RecurringLog>| match recurring {
+            Some(recurring) => format!(
+                "{} {:.2} {:?} from {}, {} added",
+                recurring.product,
+                recurring.price,
+                recurring.interval,
+                recurring.start,
+                recurring.materialized
+            ),
+            None => "(removed)".to_owned(),
+        };
+        let describe_settlement = |settlement: &Option<Settlement>| match settlement {
+            Some(settlement) => format!(
+                "{:.2} from {} to {} in {}",
+                settlement.amount, settlement.from, settlement.to, settlement.year_month
+            ),
+            None => "(removed)".to_owned(),
+        };
+
+        match self {
+            MergeConflict::ProductCategory {
+                product,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Category of {product}: was {}, ours {}, theirs {}",
+                or_missing(base),
+                or_missing(ours),
+                or_missing(theirs)
+            ),
+
+            MergeConflict::Log {
+                index,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Log {}: was {}, ours {}, theirs {}",
+                index + 1,
+                describe_log(&Some(base.clone())),
+                describe_log(ours),
+                describe_log(theirs)
+            ),
+
+            MergeConflict::Account {
+                account,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Account {account}: was {}, ours {}, theirs {}",
+                describe_account(base),
+                describe_account(ours),
+                describe_account(theirs)
+            ),
+
+            MergeConflict::Goal {
+                goal,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Goal {goal}: was {}, ours {}, theirs {}",
+                describe_goal(base),
+                describe_goal(ours),
+                describe_goal(theirs)
+            ),
+
+            MergeConflict::Loan {
+                loan,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Loan {loan}: was {}, ours {}, theirs {}",
+                describe_loan(base),
+                describe_loan(ours),
+                describe_loan(theirs)
+            ),
+
+            MergeConflict::Envelope {
+                category,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Envelope {category}: was {}, ours {}, theirs {}",
+                describe_envelope(base),
+                describe_envelope(ours),
+                describe_envelope(theirs)
+            ),
+
+            MergeConflict::TaxBucket {
+                category,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Tax bucket of {category}: was {}, ours {}, theirs {}",
+                or_missing(base),
+                or_missing(ours),
+                or_missing(theirs)
+            ),
+
+            MergeConflict::AnomalyThreshold {
+                category,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Anomaly threshold of {category}: was {}, ours {}, theirs {}",
+                describe_threshold(base),
+                describe_threshold(ours),
+                describe_threshold(theirs)
+            ),
+
+            MergeConflict::Transfer {
+                index,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Transfer {}: was {}, ours {}, theirs {}",
+                index + 1,
+                describe_transfer(&Some(base.clone())),
+                describe_transfer(ours),
+                describe_transfer(theirs)
+            ),
+
+            MergeConflict::Recurring {
+                index,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Recurring log {}: was {}, ours {}, theirs {}",
+                index + 1,
+                describe_recurring(&Some(base.clone())),
+                describe_recurring(ours),
+                describe_recurring(theirs)
+            ),
+
+            MergeConflict::Settlement {
+                index,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "Settlement {}: was {}, ours {}, theirs {}",
+                index + 1,
+                describe_settlement(&Some(base.clone())),
+                describe_settlement(ours),
+                describe_settlement(theirs)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Date, Month, YearMonth};
+
+    use crate::finance::{
+        Account, AccountKind, Finance, FinanceLog, Goal, GoalContribution, Product, Transfer,
+    };
+    use crate::recurring::{RecurrenceInterval, RecurringLog};
+    use crate::reimbursements::{Reimbursable, ReimbursementStatus};
+
+    use super::{merge, MergeConflict, MergeSide};
+
+    #[test]
+    fn test_merge_non_conflicting_changes() {
+        let base = Finance::new()
+            .with_product(&Product::new("prod1", "cat1"))
+            .with_log(log("prod1", 10.0));
+
+        let ours = base
+            .clone()
+            .with_product(&Product::new("prod1", "cat2"))
+            .with_account(&Account::new("cash", AccountKind::Cash, 5.0))
+            .with_log(log("prod2", 20.0))
+            .with_log(log("prod4", 40.0));
+
+        let theirs = base
+            .clone()
+            .with_product(&Product::new("prod3", "cat3"))
+            .with_log(log("prod3", 30.0))
+            .with_log(log("prod4", 40.0))
+            .with_transfer(transfer());
+
+        let result = merge(&base, &ours, &theirs, |_| panic!("Expected no conflicts!"));
+
+        assert!(result.conflicts.is_empty());
+        assert_eq!(
+            result.finance.get_category_for("prod1"),
+            Some("cat2".into())
+        );
+        assert_eq!(
+            result.finance.get_category_for("prod3"),
+            Some("cat3".into())
+        );
+        assert_eq!(
+            result.finance.logs,
+            vec![
+                log("prod1", 10.0),
+                log("prod2", 20.0),
+                log("prod4", 40.0),
+                log("prod3", 30.0),
+                log("prod4", 40.0)
+            ]
+        );
+        assert_eq!(result.finance.transfers, vec![transfer()]);
+    }
+
+    #[test]
+    fn test_merge_keeps_shared_recurring_logs_once() {
+        let rent = RecurringLog::new(
+            "rent",
+            700.0,
+            RecurrenceInterval::Monthly,
+            Date::new(2023, Month::June, 1).unwrap(),
+        );
+        let base = Finance::new().with_recurring(rent);
+
+        let ours = base
+            .clone()
+            .materialize_due(Date::new(2023, Month::July, 1).unwrap())
+            .with_log(log("coffee", 3.0));
+        let theirs = base
+            .clone()
+            .materialize_due(Date::new(2023, Month::July, 1).unwrap())
+            .with_log(log("coffee", 3.0));
+
+        let result = merge(&base, &ours, &theirs, |_| panic!("Expected no conflicts!"));
+
+        assert_eq!(
+            result.finance.logs,
+            vec![
+                log("rent", 700.0),
+                FinanceLog::new("rent", 700.0, YearMonth::new(2023, Month::July)),
+                log("coffee", 3.0),
+                log("coffee", 3.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let base = Finance::new()
+            .with_product(&Product::new("prod1", "cat1"))
+            .with_log(log("prod1", 10.0));
+
+        let ours = Finance::new()
+            .with_product(&Product::new("prod1", "cat2"))
+            .with_log(log("prod1", 11.0));
+
+        let theirs = Finance::new()
+            .with_product(&Product::new("prod1", "cat3"))
+            .with_log(log("prod1", 12.0));
+
+        let result = merge(&base, &ours, &theirs, |conflict| match conflict {
+            MergeConflict::Log { .. } => MergeSide::Theirs,
+            _ => MergeSide::Ours,
+        });
+
+        assert_eq!(
+            result.conflicts,
+            vec![
+                MergeConflict::ProductCategory {
+                    product: "prod1".into(),
+                    base: Some("cat1".into()),
+                    ours: Some("cat2".into()),
+                    theirs: Some("cat3".into()),
+                },
+                MergeConflict::Log {
+                    index: 0,
+                    base: log("prod1", 10.0),
+                    ours: Some(log("prod1", 11.0)),
+                    theirs: Some(log("prod1", 12.0)),
+                }
+            ]
+        );
+        assert_eq!(
+            result.finance.get_category_for("prod1"),
+            Some("cat2".into())
+        );
+        assert_eq!(result.finance.logs, vec![log("prod1", 12.0)]);
+    }
+
+    #[test]
+    fn test_merge_moves_reimbursements_with_their_logs() {
+        let base = Finance::new().with_log(log("prod1", 10.0));
+        let ours = base.clone().with_log(log("prod2", 20.0));
+
+        let reimbursable = Reimbursable::new(ReimbursementStatus::Reimbursed);
+        let expense = |reimbursed_by| FinanceLog {
+            reimbursable: Some(reimbursable.clone().with_reimbursed_by(reimbursed_by)),
+            ..log("hotel", 80.0)
+        };
+        let theirs = base
+            .clone()
+            .with_log(log("refund", -80.0))
+            .with_log(expense(1));
+
+        let result = merge(&base, &ours, &theirs, |_| panic!("Expected no conflicts!"));
+
+        assert_eq!(result.finance.logs[2], log("refund", -80.0));
+        assert_eq!(result.finance.logs[3], expense(2));
+    }
+
+    #[test]
+    fn test_merge_moves_goal_contributions_with_their_logs() {
+        let base = Finance::new().with_log(log("prod1", 10.0));
+
+        let ours = base
+            .clone()
+            .with_log(log("prod2", 20.0))
+            .with_transfer(transfer());
+
+        let their_transfer = Transfer::new("checking", "savings", 50.0, transfer().year_month);
+        let theirs = base
+            .clone()
+            .with_log(log("prod3", 30.0))
+            .with_transfer(their_transfer.clone())
+            .with_goal(
+                &Goal::new("trip", 100.0, None)
+                    .with_contribution(GoalContribution::Log(0))
+                    .with_contribution(GoalContribution::Log(1))
+                    .with_contribution(GoalContribution::Transfer(0)),
+            );
+
+        let result = merge(&base, &ours, &theirs, |_| panic!("Expected no conflicts!"));
+
+        assert_eq!(result.finance.logs[2], log("prod3", 30.0));
+        assert_eq!(result.finance.transfers[1], their_transfer);
+        assert_eq!(
+            result.finance.goals()["trip"].contributions,
+            vec![
+                GoalContribution::Log(0),
+                GoalContribution::Log(2),
+                GoalContribution::Transfer(1)
+            ]
+        );
+    }
+
+    fn log(product: &str, price: f64) -> FinanceLog {
+        FinanceLog::new(product, price, YearMonth::new(2023, Month::June))
+    }
+
+    fn transfer() -> Transfer {
+        Transfer::new("checking", "cash", 25.0, YearMonth::new(2023, Month::June))
+    }
+}
@@ -1,3 +1,18 @@
+pub mod anomalies;
+pub mod attachments;
+pub mod categories;
+pub mod envelopes;
+pub mod events;
+pub mod export;
 pub mod finance;
+pub mod history;
+pub mod loans;
+pub mod merge;
+pub mod periods;
+pub mod recurring;
+pub mod reimbursements;
 pub mod repository;
+pub mod shared;
 pub mod stats;
+pub mod subscriptions;
+pub mod taxes;
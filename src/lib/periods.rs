@@ -0,0 +1,141 @@
+use std::fmt;
+
+use gregorian::{Month, Year, YearMonth};
+
+/// A span of whole months that totals can be computed over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Period {
+    Month(YearMonth),
+    /// Quarter 1 to 4 of `year`.
+    Quarter {
+        year: Year,
+        quarter: u8,
+    },
+    Year(Year),
+    /// The twelve months from `start`.
+    FiscalYear {
+        start: YearMonth,
+    },
+    /// From `from` to `to`, both included.
+    Range {
+        from: YearMonth,
+        to: YearMonth,
+    },
+}
+
+impl Period {
+    pub fn first_month(&self) -> YearMonth {
+        match *self {
+            Period::Month(year_month) => year_month,
+            Period::Quarter { year, quarter } => year
+                .with_month(Month::January)
+                .add_months(3 * (i32::from(quarter) - 1)),
+            Period::Year(year) => year.with_month(Month::January),
+            Period::FiscalYear { start } => start,
+            Period::Range { from, .. } => from,
+        }
+    }
+
+    pub fn last_month(&self) -> YearMonth {
+        match *self {
+            Period::Month(year_month) => year_month,
+            Period::Quarter { .. } => self.first_month().add_months(2),
+            Period::Year(year) => year.with_month(Month::December),
+            Period::FiscalYear { start } => start.add_months(11),
+            Period::Range { to, .. } => to,
+        }
+    }
+
+    pub fn contains(&self, year_month: YearMonth) -> bool {
+        (self.first_month()..=self.last_month()).contains(&year_month)
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Period::Month(year_month) => write!(f, "{year_month}"),
+            Period::Quarter { year, quarter } => write!(f, "{year} Q{quarter}"),
+            Period::Year(year) => write!(f, "{year}"),
+            Period::FiscalYear { start } if start.month() == Month::January => {
+                write!(f, "FY {}", start.year())
+            }
+            Period::FiscalYear { start } => write!(
+                f,
+                "FY {}/{:02}",
+                start.year(),
+                (start.year().to_number() + 1) % 100
+            ),
+            Period::Range { from, to } => write!(f, "{from} to {to}"),
+        }
+    }
+}
+
+/// How months are grouped into periods.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PeriodGranularity {
+    Month,
+    Quarter,
+    Year,
+    /// Years starting on `start_month`.
+    FiscalYear {
+        start_month: Month,
+    },
+}
+
+impl PeriodGranularity {
+    /// The period `year_month` falls in.
+    pub fn period_of(&self, year_month: YearMonth) -> Period {
+        match *self {
+            PeriodGranularity::Month => Period::Month(year_month),
+            PeriodGranularity::Quarter => Period::Quarter {
+                year: year_month.year(),
+                quarter: (year_month.month().to_number() - 1) / 3 + 1,
+            },
+            PeriodGranularity::Year => Period::Year(year_month.year()),
+            PeriodGranularity::FiscalYear { start_month } => {
+                let start = year_month.year().with_month(start_month);
+
+                Period::FiscalYear {
+                    start: match start > year_month {
+                        true => start.add_months(-12),
+                        false => start,
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, Year, YearMonth};
+
+    use super::{Period, PeriodGranularity};
+
+    #[test]
+    fn test_period_of() {
+        let february = YearMonth::new(2024, Month::February);
+        let fiscal = PeriodGranularity::FiscalYear {
+            start_month: Month::April,
+        };
+
+        let quarter = PeriodGranularity::Quarter.period_of(february);
+        assert_eq!(quarter.to_string(), "2024 Q1");
+        assert_eq!(quarter.last_month(), YearMonth::new(2024, Month::March));
+
+        let fiscal_year = fiscal.period_of(february);
+        assert_eq!(fiscal_year.to_string(), "FY 2023/24");
+        assert_eq!(
+            fiscal_year.first_month(),
+            YearMonth::new(2023, Month::April)
+        );
+        assert!(fiscal_year.contains(YearMonth::new(2024, Month::March)));
+        assert!(!fiscal_year.contains(YearMonth::new(2024, Month::April)));
+
+        assert_eq!(
+            PeriodGranularity::Year.period_of(february),
+            Period::Year(Year::new(2024))
+        );
+    }
+}
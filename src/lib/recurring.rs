@@ -0,0 +1,177 @@
+use gregorian::{Date, DateResultExt};
+
+use crate::finance::{FinanceLog, Price};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RecurrenceInterval {
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A log that repeats every `interval` from `start` until `end`, like rent or
+/// a salary. Occurrences are turned into real logs as they come due;
+/// `materialized` counts how many already were.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecurringLog {
+    pub product: String,
+    pub price: Price,
+    pub account: Option<String>,
+    pub interval: RecurrenceInterval,
+    pub start: Date,
+    pub end: Option<Date>,
+    pub materialized: usize,
+}
+
+impl RecurringLog {
+    pub fn new(product: &str, price: Price, interval: RecurrenceInterval, start: Date) -> Self {
+        Self {
+            product: product.to_owned(),
+            price,
+            account: None,
+            interval,
+            start,
+            end: None,
+            materialized: 0,
+        }
+    }
+
+    pub fn with_end(self, end: Date) -> Self {
+        Self {
+            end: Some(end),
+            ..self
+        }
+    }
+
+    pub fn with_account(self, account: &str) -> Self {
+        Self {
+            account: Some(account.to_owned()),
+            ..self
+        }
+    }
+
+    /// Date of the occurrence number `index`, counting from 0, or `None` if it
+    /// falls after the end date. Occurrences past the end of a shorter month
+    /// fall on its last day.
+    pub fn occurrence(&self, index: usize) -> Option<Date> {
+        let index = i32::try_from(index).ok()?;
+        let date = match self.interval {
+            RecurrenceInterval::Weekly => self.start.add_days(7 * index),
+            RecurrenceInterval::Monthly => self.start.add_months(index).or_prev_valid(),
+            RecurrenceInterval::Yearly => self
+                .start
+                .add_years(i16::try_from(index).ok()?)
+                .or_prev_valid(),
+        };
+
+        match self.end {
+            Some(end) if date > end => None,
+            _ => Some(date),
+        }
+    }
+
+    /// The first occurrence that wasn't materialized yet.
+    pub fn next_due(&self) -> Option<Date> {
+        self.occurrence(self.materialized)
+    }
+
+    /// Dates of the occurrences not materialized yet, up to and including
+    /// `date`.
+    pub fn due_until(&self, date: Date) -> Vec<Date> {
+        (self.materialized..)
+            .map_while(|index| self.occurrence(index))
+            .take_while(|occurrence| *occurrence <= date)
+            .collect()
+    }
+
+    /// The log the occurrence number `index` is materialized as.
+    pub fn occurrence_log(&self, index: usize) -> Option<FinanceLog> {
+        self.occurrence(index).map(|date| self.log_on(date))
+    }
+
+    /// Turns the occurrences due up to `date` into logs, returning them along
+    /// with the template marked as materialized up to there.
+    pub fn materialize_until(&self, date: Date) -> (Self, Vec<FinanceLog>) {
+        let due_dates = self.due_until(date);
+        let logs = due_dates
+            .iter()
+            .map(|due_date| self.log_on(*due_date))
+            .collect();
+
+        (
+            Self {
+                materialized: self.materialized + due_dates.len(),
+                ..self.clone()
+            },
+            logs,
+        )
+    }
+
+    fn log_on(&self, date: Date) -> FinanceLog {
+        FinanceLog {
+            account: self.account.clone(),
+            ..FinanceLog::new(&self.product, self.price, date.year_month())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::Date;
+
+    use super::{RecurrenceInterval, RecurringLog};
+
+    #[test]
+    fn test_monthly_occurrences_clamp_to_month_end() {
+        let rent = RecurringLog::new(
+            "rent",
+            500.0,
+            RecurrenceInterval::Monthly,
+            date(2024, 1, 31),
+        )
+        .with_end(date(2024, 4, 30));
+
+        let occurrences = (0..5)
+            .map(|index| rent.occurrence(index))
+            .collect::<Vec<Option<Date>>>();
+
+        assert_eq!(
+            occurrences,
+            vec![
+                Some(date(2024, 1, 31)),
+                Some(date(2024, 2, 29)),
+                Some(date(2024, 3, 31)),
+                Some(date(2024, 4, 30)),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_materialize_until() {
+        let groceries = RecurringLog::new(
+            "groceries",
+            30.0,
+            RecurrenceInterval::Weekly,
+            date(2024, 1, 1),
+        )
+        .with_account("cash");
+
+        let (groceries, logs) = groceries.materialize_until(date(2024, 1, 20));
+
+        assert_eq!(logs.len(), 3);
+        assert!(logs
+            .iter()
+            .all(|log| log.account.as_deref() == Some("cash")));
+        assert_eq!(groceries.materialized, 3);
+        assert_eq!(groceries.next_due(), Some(date(2024, 1, 22)));
+
+        let (groceries, logs) = groceries.materialize_until(date(2024, 1, 20));
+        assert!(logs.is_empty());
+        assert_eq!(groceries.materialized, 3);
+    }
+
+    fn date(year: i16, month: u8, day: u8) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use crate::export::csv_line;
+use crate::finance::{FinanceLog, Price};
+
+/// Where a reimbursable expense is in getting paid back.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReimbursementStatus {
+    Pending,
+    Submitted,
+    Reimbursed,
+}
+
+/// Marks a log as an expense someone else will pay back.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reimbursable {
+    pub status: ReimbursementStatus,
+    /// Position of the income log that paid it back. Merges move it along
+    /// with the log.
+    pub reimbursed_by: Option<usize>,
+}
+
+impl Reimbursable {
+    pub fn new(status: ReimbursementStatus) -> Self {
+        Self {
+            status,
+            reimbursed_by: None,
+        }
+    }
+
+    pub fn with_reimbursed_by(self, income_index: usize) -> Self {
+        Self {
+            reimbursed_by: Some(income_index),
+            ..self
+        }
+    }
+}
+
+/// How much of each log is paid back, by position. A reimbursed expense is
+/// covered by its linked income, in the order of the expenses linked to it,
+/// or fully if it isn't linked. The income gets the negative of what it
+/// covers, so both sides cancel out.
+pub fn reimbursed_amounts(logs: &[FinanceLog]) -> HashMap<usize, Price> {
+    let mut amounts = HashMap::<usize, Price>::new();
+    let mut income_left = HashMap::<usize, Price>::new();
+
+    for (index, log) in logs.iter().enumerate() {
+        let Some(reimbursable) = &log.reimbursable else {
+            continue;
+        };
+
+        if reimbursable.status != ReimbursementStatus::Reimbursed {
+            continue;
+        }
+
+        let covered = match reimbursable.reimbursed_by {
+            Some(income_index) => {
+                let Some(income) = logs.get(income_index).filter(|income| income.price < 0.0)
+                else {
+                    continue;
+                };
+
+                let left = income_left.entry(income_index).or_insert(-income.price);
+                let covered = log.price.min(*left);
+                *left -= covered;
+                *amounts.entry(income_index).or_insert(0.0) -= covered;
+
+                covered
+            }
+            None => log.price,
+        };
+
+        *amounts.entry(index).or_insert(0.0) += covered;
+    }
+
+    amounts
+}
+
+const REPORT_COLUMNS: [&str; 6] = ["Month", "Product", "Payee", "Amount", "Status", "Note"];
+
+/// The expenses as CSV, with a header row.
+pub fn expense_report_csv(logs: &[FinanceLog]) -> String {
+    let rows = logs.iter().map(|log| csv_line(&report_row(log)));
+
+    Some(csv_line(&REPORT_COLUMNS))
+        .into_iter()
+        .chain(rows)
+        .map(|row| row + "\n")
+        .collect()
+}
+
+/// The expenses as a Markdown table, followed by their total.
+pub fn expense_report_markdown(logs: &[FinanceLog]) -> String {
+    let rows = logs.iter().map(|log| {
+        let fields = report_row(log)
+            .iter()
+            .map(|field| field.replace('|', "\\|"))
+            .collect::<Vec<String>>();
+
+        format!("| {} |", fields.join(" | "))
+    });
+
+    let total = logs.iter().fold(0.0, |total, log| total + log.price);
+
+    [
+        format!("| {} |", REPORT_COLUMNS.join(" | ")),
+        format!("|{}", "---|".repeat(REPORT_COLUMNS.len())),
+    ]
+    .into_iter()
+    .chain(rows)
+    .chain(["".to_owned(), format!("**Total: {total:.2}**")])
+    .map(|line| line + "\n")
+    .collect()
+}
+
+fn report_row(log: &FinanceLog) -> [String; 6] {
+    let status = match log
+        .reimbursable
+        .as_ref()
+        .map(|reimbursable| reimbursable.status)
+    {
+        Some(ReimbursementStatus::Pending) => "pending",
+        Some(ReimbursementStatus::Submitted) => "submitted",
+        Some(ReimbursementStatus::Reimbursed) => "reimbursed",
+        None => "",
+    };
+
+    [
+        log.year_month.to_string(),
+        log.product.to_owned(),
+        log.payee.to_owned().unwrap_or_default(),
+        format!("{:.2}", log.price),
+        status.to_owned(),
+        log.note.to_owned().unwrap_or_default(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use gregorian::{Month, YearMonth};
+
+    use super::{
+        expense_report_csv, expense_report_markdown, reimbursed_amounts, Reimbursable,
+        ReimbursementStatus,
+    };
+    use crate::finance::FinanceLog;
+
+    fn expense(product: &str, price: f64, reimbursable: Reimbursable) -> FinanceLog {
+        FinanceLog::new(product, price, YearMonth::new(2024, Month::May))
+            .with_reimbursable(reimbursable)
+    }
+
+    #[test]
+    fn test_reimbursed_amounts() {
+        let reimbursed = Reimbursable::new(ReimbursementStatus::Reimbursed);
+        let logs = vec![
+            FinanceLog::new("refund", -100.0, YearMonth::new(2024, Month::June)),
+            expense("hotel", 80.0, reimbursed.clone().with_reimbursed_by(0)),
+            expense("train", 30.0, reimbursed.clone().with_reimbursed_by(0)),
+            expense("taxi", 15.0, reimbursed),
+            expense(
+                "lunch",
+                12.0,
+                Reimbursable::new(ReimbursementStatus::Pending),
+            ),
+        ];
+
+        assert_eq!(
+            reimbursed_amounts(&logs),
+            HashMap::from([(0, -100.0), (1, 80.0), (2, 20.0), (3, 15.0)])
+        );
+    }
+
+    #[test]
+    fn test_expense_reports() {
+        let mut hotel = expense(
+            "hotel",
+            80.0,
+            Reimbursable::new(ReimbursementStatus::Pending),
+        )
+        .with_payee("Inn, Ltd");
+        hotel.note = Some("2 nights".to_owned());
+        let logs = vec![hotel];
+
+        assert_eq!(
+            expense_report_csv(&logs),
+            "Month,Product,Payee,Amount,Status,Note\n\
+             2024-05,hotel,\"Inn, Ltd\",80.00,pending,2 nights\n"
+        );
+        assert_eq!(
+            expense_report_markdown(&logs),
+            "| Month | Product | Payee | Amount | Status | Note |\n\
+             |---|---|---|---|---|---|\n\
+             | 2024-05 | hotel | Inn, Ltd | 80.00 | pending | 2 nights |\n\
+             \n\
+             **Total: 80.00**\n"
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use std::fmt;
+use std::fs;
+use std::num::NonZeroU32;
+use std::ops::RangeInclusive;
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+
+use super::json::JSONFinance;
+use super::{FileFingerprint, FinanceRepository, FinanceRepositoryLoadError};
+use crate::finance::Finance;
+
+const MAGIC: &[u8] = b"NEOBUDGET-ENCRYPTED-1\n";
+const PBKDF2_ITERATIONS: u32 = 600_000;
+/// Iterations accepted from a file header. The header is only authenticated
+/// after the key is derived, so a tampered count must not make that take
+/// forever.
+const PBKDF2_ITERATIONS_RANGE: RangeInclusive<u32> = 1_000..=10_000_000;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+const HEADER_LEN: usize = MAGIC.len() + 4 + SALT_LEN + NONCE_LEN;
+
+/// Stores a `Finance` as JSON encrypted with ChaCha20-Poly1305, under a key
+/// derived from a passphrase with PBKDF2-HMAC-SHA256.
+///
+/// The file starts with a header holding the KDF iterations, the salt and the
+/// nonce, which is authenticated along with the ciphertext.
+#[derive(Clone)]
+pub struct EncryptedFinanceRepository {
+    path: String,
+    key: [u8; KEY_LEN],
+    salt: [u8; SALT_LEN],
+    iterations: u32,
+}
+
+struct Header {
+    iterations: u32,
+    salt: [u8; SALT_LEN],
+    nonce: [u8; NONCE_LEN],
+}
+
+impl EncryptedFinanceRepository {
+    /// Opens the encrypted file at `path`, checking that `passphrase` decrypts
+    /// it. New files are made with `create` instead.
+    pub fn unlock(path: &str, passphrase: &str) -> Result<Self, String> {
+        let contents = read_file(path).map_err(String::from)?;
+        let header = Header::parse(&contents)
+            .ok_or_else(|| format!("{path} is not an encrypted Finance file!"))?;
+
+        let repo = Self::with_key(path, passphrase, header.salt, header.iterations);
+        repo.decrypt(&contents)?;
+
+        Ok(repo)
+    }
+
+    /// Prepares a new encrypted file at `path` with a fresh salt, replacing
+    /// whatever is there on the next save. The passphrase has to be entered
+    /// twice, as `confirmation` too, since a typo would lock the file for
+    /// good.
+    pub fn create(path: &str, passphrase: &str, confirmation: &str) -> Result<Self, String> {
+        if passphrase != confirmation {
+            return Err("The passphrases don't match!".to_owned());
+        }
+
+        Self::create_with_iterations(path, passphrase, PBKDF2_ITERATIONS)
+    }
+
+    fn create_with_iterations(
+        path: &str,
+        passphrase: &str,
+        iterations: u32,
+    ) -> Result<Self, String> {
+        let mut salt = [0; SALT_LEN];
+        SystemRandom::new()
+            .fill(&mut salt)
+            .map_err(|_| "Couldn't generate a random salt!")?;
+
+        Ok(Self::with_key(path, passphrase, salt, iterations))
+    }
+
+    fn with_key(path: &str, passphrase: &str, salt: [u8; SALT_LEN], iterations: u32) -> Self {
+        let mut key = [0; KEY_LEN];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(iterations).unwrap_or(NonZeroU32::MIN),
+            &salt,
+            passphrase.as_bytes(),
+            &mut key,
+        );
+
+        Self {
+            path: path.to_owned(),
+            key,
+            salt,
+            iterations,
+        }
+    }
+
+    fn sealing_key(&self) -> LessSafeKey {
+        LessSafeKey::new(
+            UnboundKey::new(&CHACHA20_POLY1305, &self.key).expect("Key has the right length."),
+        )
+    }
+
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| "Couldn't generate a random nonce!")?;
+
+        let header = Header {
+            iterations: self.iterations,
+            salt: self.salt,
+            nonce,
+        }
+        .to_bytes();
+
+        let mut ciphertext = plaintext.to_vec();
+        self.sealing_key()
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(&header),
+                &mut ciphertext,
+            )
+            .map_err(|_| "Couldn't encrypt Finance!")?;
+
+        Ok([header, ciphertext].concat())
+    }
+
+    fn decrypt(&self, contents: &[u8]) -> Result<Vec<u8>, String> {
+        let wrong_passphrase =
+            || "Couldn't decrypt Finance file. Is the passphrase right?".to_owned();
+
+        let header = Header::parse(contents).ok_or_else(wrong_passphrase)?;
+        let mut ciphertext = contents[HEADER_LEN..].to_vec();
+
+        let plaintext = self
+            .sealing_key()
+            .open_in_place(
+                Nonce::assume_unique_for_key(header.nonce),
+                Aad::from(&contents[..HEADER_LEN]),
+                &mut ciphertext,
+            )
+            .map_err(|_| wrong_passphrase())?;
+
+        Ok(plaintext.to_vec())
+    }
+}
+
+impl FinanceRepository for EncryptedFinanceRepository {
+    fn load(&self) -> Result<Finance, FinanceRepositoryLoadError> {
+        let plaintext = self
+            .decrypt(&read_file(&self.path)?)
+            .map_err(FinanceRepositoryLoadError::Corrupted)?;
+
+        let json_finance: JSONFinance = serde_json::from_slice(&plaintext).map_err(|_| {
+            FinanceRepositoryLoadError::Corrupted(
+                "Error parsing Finance from JSON content. Does it have the correct structure?"
+                    .to_owned(),
+            )
+        })?;
+
+        json_finance
+            .to_finance()
+            .map_err(FinanceRepositoryLoadError::Corrupted)
+    }
+
+    fn save(&self, finance: &Finance) -> Result<(), String> {
+        let path = &self.path;
+        let json_finance = JSONFinance::from_finance(finance);
+        let plaintext = serde_json::to_vec(&json_finance).map_err(|_| "Unknown error.")?;
+
+        // Write next to the file first, so a failed write can't destroy it.
+        let saving_path = format!("{path}.saving");
+        fs::write(&saving_path, self.encrypt(&plaintext)?)
+            .map_err(|_| format!("Couldn't write to file {path}! Does the directory exist?"))?;
+
+        fs::rename(&saving_path, path).map_err(|_| format!("Couldn't replace file {path}!"))
+    }
+
+    fn fingerprint(&self) -> Option<FileFingerprint> {
+        FileFingerprint::of_file(&self.path)
+    }
+}
+
+impl fmt::Debug for EncryptedFinanceRepository {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptedFinanceRepository")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Header {
+    fn parse(contents: &[u8]) -> Option<Self> {
+        let rest = contents.strip_prefix(MAGIC)?;
+        if rest.len() < HEADER_LEN - MAGIC.len() {
+            return None;
+        }
+
+        let (iterations, rest) = rest.split_at(4);
+        let (salt, rest) = rest.split_at(SALT_LEN);
+        let nonce = &rest[..NONCE_LEN];
+
+        let iterations = u32::from_be_bytes(iterations.try_into().ok()?);
+        if !PBKDF2_ITERATIONS_RANGE.contains(&iterations) {
+            return None;
+        }
+
+        Some(Self {
+            iterations,
+            salt: salt.try_into().ok()?,
+            nonce: nonce.try_into().ok()?,
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        [
+            MAGIC,
+            &self.iterations.to_be_bytes(),
+            &self.salt,
+            &self.nonce,
+        ]
+        .concat()
+    }
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, FinanceRepositoryLoadError> {
+    fs::read(path).map_err(|_| {
+        FinanceRepositoryLoadError::DoesNotExist(format!(
+            "Couldn't read Finance file {path}. Does it exist?"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use tempfile::TempDir;
+
+    use crate::finance::{FinanceLog, Product};
+
+    use super::*;
+
+    // Keeps the tests fast; real files use PBKDF2_ITERATIONS.
+    const TEST_ITERATIONS: u32 = 1_000;
+
+    #[test]
+    fn test_save_and_unlock() {
+        with_temp_path(|path| {
+            let finance = Finance::new()
+                .with_product(&Product::new("prod1", "cat1"))
+                .with_log(FinanceLog::new(
+                    "prod1",
+                    10.0,
+                    YearMonth::new(2024, Month::March),
+                ));
+
+            let repo =
+                EncryptedFinanceRepository::create_with_iterations(path, "secret", TEST_ITERATIONS)
+                    .unwrap();
+            repo.save(&finance).expect("Didn't expect save to fail!");
+
+            let contents = fs::read(path).unwrap();
+            assert!(contents.starts_with(MAGIC));
+            assert!(!String::from_utf8_lossy(&contents).contains("prod1"));
+
+            let unlocked = EncryptedFinanceRepository::unlock(path, "secret")
+                .expect("Didn't expect unlock to fail!");
+            assert_eq!(unlocked.load(), Ok(finance));
+        });
+    }
+
+    #[test]
+    fn test_unlock_wrong_passphrase() {
+        with_temp_path(|path| {
+            EncryptedFinanceRepository::create_with_iterations(path, "secret", TEST_ITERATIONS)
+                .unwrap()
+                .save(&Finance::new())
+                .unwrap();
+
+            assert!(EncryptedFinanceRepository::unlock(path, "wrong").is_err());
+        });
+    }
+
+    #[test]
+    fn test_load_tampered_file() {
+        with_temp_path(|path| {
+            let repo =
+                EncryptedFinanceRepository::create_with_iterations(path, "secret", TEST_ITERATIONS)
+                    .unwrap();
+            repo.save(&Finance::new()).unwrap();
+
+            let mut contents = fs::read(path).unwrap();
+            let last = contents.len() - 1;
+            contents[last] ^= 1;
+            fs::write(path, contents).unwrap();
+
+            assert!(matches!(
+                repo.load(),
+                Err(FinanceRepositoryLoadError::Corrupted(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_unlock_out_of_range_iterations() {
+        with_temp_path(|path| {
+            EncryptedFinanceRepository::create_with_iterations(path, "secret", TEST_ITERATIONS)
+                .unwrap()
+                .save(&Finance::new())
+                .unwrap();
+
+            let mut contents = fs::read(path).unwrap();
+            contents[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+            fs::write(path, contents).unwrap();
+
+            assert!(EncryptedFinanceRepository::unlock(path, "secret").is_err());
+        });
+    }
+
+    #[test]
+    fn test_create_needs_matching_passphrases() {
+        with_temp_path(|path| {
+            assert!(EncryptedFinanceRepository::create(path, "secret", "secert").is_err());
+            assert!(EncryptedFinanceRepository::unlock(path, "secret").is_err());
+        });
+    }
+
+    #[test]
+    fn test_unlock_plain_file() {
+        with_temp_path(|path| {
+            fs::write(path, r#"{ "products": [], "logs": [] }"#).unwrap();
+
+            assert!(EncryptedFinanceRepository::unlock(path, "secret").is_err());
+        });
+    }
+
+    fn with_temp_path<F>(func: F)
+    where
+        F: Fn(&str),
+    {
+        let dir = TempDir::new().expect("Error creating temp dir!");
+        let path = dir.path().join("finance.json.enc");
+
+        func(path.to_str().unwrap())
+    }
+}
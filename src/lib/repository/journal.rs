@@ -0,0 +1,541 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+use gregorian::Date;
+use serde::{Deserialize, Serialize};
+
+use super::json::{
+    JSONAccount, JSONEnvelope, JSONFinance, JSONGoal, JSONLoan, JSONLog, JSONProduct,
+    JSONRecurringLog, JSONSettlement, JSONTransfer,
+};
+use super::{FileFingerprint, FinanceRepository, FinanceRepositoryLoadError};
+use crate::events::FinanceEvent;
+use crate::finance::Finance;
+
+/// Stores a `Finance` as an append-only journal of dated events, one JSON
+/// entry per line. A journal may start with a snapshot, written on the first
+/// save or by compaction. Saves that events can't describe, like a removed
+/// product, append a snapshot too.
+#[derive(Clone, Debug)]
+pub struct JournalFinanceRepository {
+    journal_path: String,
+}
+
+enum JournalChange {
+    Snapshot(Finance),
+    Event(FinanceEvent),
+}
+
+struct JournalEntry {
+    date: Date,
+    change: JournalChange,
+}
+
+impl JournalFinanceRepository {
+    pub fn new(journal_path: &str) -> Self {
+        Self {
+            journal_path: journal_path.to_owned(),
+        }
+    }
+
+    /// Rebuilds the books as they were at the end of `date`.
+    pub fn load_as_of(&self, date: Date) -> Result<Finance, String> {
+        let entries = self.read_entries()?;
+
+        if let Some(first_entry) = entries.first() {
+            if first_entry.date > date {
+                return Err(format!(
+                    "The journal has no history before {}.",
+                    first_entry.date
+                ));
+            }
+        }
+
+        Ok(replay(
+            entries.into_iter().take_while(|entry| entry.date <= date),
+        ))
+    }
+
+    /// Folds every entry up to and including `date` into a single snapshot,
+    /// keeping later entries as they are. Fails without touching the journal
+    /// if there is nothing to fold.
+    pub fn compact_until(&self, date: Date) -> Result<(), String> {
+        let entries = self.read_entries()?;
+        let (compacted, kept): (Vec<JournalEntry>, Vec<JournalEntry>) =
+            entries.into_iter().partition(|entry| entry.date <= date);
+
+        if compacted.is_empty() {
+            return Err(format!("The journal has no history until {date}."));
+        }
+
+        let snapshot = JournalEntry {
+            date,
+            change: JournalChange::Snapshot(replay(compacted.into_iter())),
+        };
+
+        let journal_path = &self.journal_path;
+        let compacted_path = format!("{journal_path}.compacting");
+        let contents = Some(snapshot)
+            .into_iter()
+            .chain(kept)
+            .map(|entry| serialize_entry(&entry))
+            .collect::<Result<Vec<String>, String>>()?;
+
+        fs::write(&compacted_path, contents.join("\n") + "\n").map_err(|_| {
+            format!("Couldn't write to file {compacted_path}! Does the directory exist?")
+        })?;
+
+        fs::rename(&compacted_path, journal_path)
+            .map_err(|_| format!("Couldn't replace journal {journal_path}!"))
+    }
+
+    fn save_on(&self, finance: &Finance, date: Date) -> Result<(), String> {
+        let entries = match self.read_entries() {
+            Ok(entries) => entries,
+            Err(FinanceRepositoryLoadError::DoesNotExist(_)) => Vec::new(),
+            Err(error) => return Err(error.into()),
+        };
+
+        let snapshot = || {
+            vec![JournalEntry {
+                date,
+                change: JournalChange::Snapshot(finance.clone()),
+            }]
+        };
+
+        let new_entries = match entries.is_empty() {
+            true => snapshot(),
+
+            false => {
+                let stored = replay(entries.into_iter());
+                let events = FinanceEvent::between(&stored, finance);
+
+                // Events can't remove products, accounts and the like, as
+                // undo or a merge may do, so such changes need a snapshot.
+                let replayed = events
+                    .iter()
+                    .fold(stored, |finance, event| event.apply(finance));
+                match replayed == *finance {
+                    true => events
+                        .into_iter()
+                        .map(|event| JournalEntry {
+                            date,
+                            change: JournalChange::Event(event),
+                        })
+                        .collect(),
+                    false => snapshot(),
+                }
+            }
+        };
+
+        self.append_entries(&new_entries)
+    }
+
+    fn read_entries(&self) -> Result<Vec<JournalEntry>, FinanceRepositoryLoadError> {
+        let journal_path = &self.journal_path;
+        let journal_content = fs::read_to_string(journal_path).map_err(|_| {
+            FinanceRepositoryLoadError::DoesNotExist(format!(
+                "Couldn't read Finance journal {journal_path}. Does it exist?"
+            ))
+        })?;
+
+        journal_content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(line_index, line)| {
+                deserialize_entry(line).ok_or_else(|| {
+                    FinanceRepositoryLoadError::Corrupted(format!(
+                        "Error parsing entry {} of Finance journal. Does it have the correct structure?",
+                        line_index + 1
+                    ))
+                })
+            })
+            .collect()
+    }
+
+    fn append_entries(&self, entries: &[JournalEntry]) -> Result<(), String> {
+        let journal_path = &self.journal_path;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(journal_path)
+            .map_err(|_| {
+                format!("Couldn't write to file {journal_path}! Does the directory exist?")
+            })?;
+
+        for entry in entries {
+            writeln!(file, "{}", serialize_entry(entry)?).map_err(|_| "Unknown error.")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FinanceRepository for JournalFinanceRepository {
+    fn load(&self) -> Result<Finance, FinanceRepositoryLoadError> {
+        Ok(replay(self.read_entries()?.into_iter()))
+    }
+
+    fn save(&self, finance: &Finance) -> Result<(), String> {
+        self.save_on(finance, Date::today())
+    }
+
+    fn fingerprint(&self) -> Option<FileFingerprint> {
+        FileFingerprint::of_file(&self.journal_path)
+    }
+}
+
+fn replay(entries: impl Iterator<Item = JournalEntry>) -> Finance {
+    entries.fold(Finance::new(), |finance, entry| match entry.change {
+        JournalChange::Snapshot(snapshot) => snapshot,
+        JournalChange::Event(event) => event.apply(finance),
+    })
+}
+
+#[derive(Deserialize, Serialize)]
+struct JSONJournalEntry {
+    date: String,
+    #[serde(flatten)]
+    change: JSONJournalChange,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JSONJournalChange {
+    Snapshot { finance: JSONFinance },
+    LogAdded { log: JSONLog },
+    LogRemoved { index: usize },
+    ProductAdded { product: JSONProduct },
+    ProductRecategorized { product: String, category: String },
+    AccountSet { account: JSONAccount },
+    TransferAdded { transfer: JSONTransfer },
+    TransferRemoved { index: usize },
+    RecurringAdded { recurring: JSONRecurringLog },
+    RecurringRemoved { index: usize },
+    GoalSet { goal: JSONGoal },
+    LoanSet { loan: JSONLoan },
+    EnvelopeSet { envelope: JSONEnvelope },
+    TaxBucketSet { category: String, bucket: String },
+    AnomalyThresholdSet { category: String, threshold: f64 },
+    PersonAdded { person: String },
+    SettlementAdded { settlement: JSONSettlement },
+    SettlementRemoved { index: usize },
+    SubscriptionDismissed { name: String },
+}
+
+fn serialize_entry(entry: &JournalEntry) -> Result<String, String> {
+    let change = match &entry.change {
+        JournalChange::Snapshot(finance) => JSONJournalChange::Snapshot {
+            finance: JSONFinance::from_finance(finance),
+        },
+
+        JournalChange::Event(FinanceEvent::LogAdded(log)) => JSONJournalChange::LogAdded {
+            log: JSONLog::from_log(log),
+        },
+
+        JournalChange::Event(FinanceEvent::LogRemoved(index)) => {
+            JSONJournalChange::LogRemoved { index: *index }
+        }
+
+        JournalChange::Event(FinanceEvent::ProductAdded(product)) => {
+            JSONJournalChange::ProductAdded {
+                product: JSONProduct::from_product(product),
+            }
+        }
+
+        JournalChange::Event(FinanceEvent::ProductRecategorized { product, category }) => {
+            JSONJournalChange::ProductRecategorized {
+                product: product.to_owned(),
+                category: category.to_owned(),
+            }
+        }
+
+        JournalChange::Event(FinanceEvent::AccountSet(account)) => JSONJournalChange::AccountSet {
+            account: JSONAccount::from_account(account),
+        },
+
+        JournalChange::Event(FinanceEvent::TransferAdded(transfer)) => {
+            JSONJournalChange::TransferAdded {
+                transfer: JSONTransfer::from_transfer(transfer),
+            }
+        }
+
+        JournalChange::Event(FinanceEvent::TransferRemoved(index)) => {
+            JSONJournalChange::TransferRemoved { index: *index }
+        }
+
+        JournalChange::Event(FinanceEvent::RecurringAdded(recurring)) => {
+            JSONJournalChange::RecurringAdded {
+                recurring: JSONRecurringLog::from_recurring(recurring),
+            }
+        }
+
+        JournalChange::Event(FinanceEvent::RecurringRemoved(index)) => {
+            JSONJournalChange::RecurringRemoved { index: *index }
+        }
+
+        JournalChange::Event(FinanceEvent::GoalSet(goal)) => JSONJournalChange::GoalSet {
+            goal: JSONGoal::from_goal(goal),
+        },
+
+        JournalChange::Event(FinanceEvent::LoanSet(loan)) => JSONJournalChange::LoanSet {
+            loan: JSONLoan::from_loan(loan),
+        },
+
+        JournalChange::Event(FinanceEvent::EnvelopeSet(envelope)) => {
+            JSONJournalChange::EnvelopeSet {
+                envelope: JSONEnvelope::from_envelope(envelope),
+            }
+        }
+
+        JournalChange::Event(FinanceEvent::TaxBucketSet { category, bucket }) => {
+            JSONJournalChange::TaxBucketSet {
+                category: category.to_owned(),
+                bucket: bucket.to_owned(),
+            }
+        }
+
+        JournalChange::Event(FinanceEvent::AnomalyThresholdSet {
+            category,
+            threshold,
+        }) => JSONJournalChange::AnomalyThresholdSet {
+            category: category.to_owned(),
+            threshold: *threshold,
+        },
+
+        JournalChange::Event(FinanceEvent::PersonAdded(person)) => JSONJournalChange::PersonAdded {
+            person: person.to_owned(),
+        },
+
+        JournalChange::Event(FinanceEvent::SettlementAdded(settlement)) => {
+            JSONJournalChange::SettlementAdded {
+                settlement: JSONSettlement::from_settlement(settlement),
+            }
+        }
+
+        JournalChange::Event(FinanceEvent::SettlementRemoved(index)) => {
+            JSONJournalChange::SettlementRemoved { index: *index }
+        }
+
+        JournalChange::Event(FinanceEvent::SubscriptionDismissed(name)) => {
+            JSONJournalChange::SubscriptionDismissed {
+                name: name.to_owned(),
+            }
+        }
+    };
+
+    serde_json::to_string(&JSONJournalEntry {
+        date: entry.date.to_string(),
+        change,
+    })
+    .map_err(|_| "Unknown error.".to_owned())
+}
+
+fn deserialize_entry(line: &str) -> Option<JournalEntry> {
+    let json_entry: JSONJournalEntry = serde_json::from_str(line).ok()?;
+
+    let change = match json_entry.change {
+        JSONJournalChange::Snapshot { finance } => {
+            JournalChange::Snapshot(finance.to_finance().ok()?)
+        }
+        JSONJournalChange::LogAdded { log } => {
+            JournalChange::Event(FinanceEvent::LogAdded(log.to_log().ok()?))
+        }
+        JSONJournalChange::LogRemoved { index } => {
+            JournalChange::Event(FinanceEvent::LogRemoved(index))
+        }
+        JSONJournalChange::ProductAdded { product } => {
+            JournalChange::Event(FinanceEvent::ProductAdded(product.to_product()))
+        }
+        JSONJournalChange::ProductRecategorized { product, category } => {
+            JournalChange::Event(FinanceEvent::ProductRecategorized { product, category })
+        }
+        JSONJournalChange::AccountSet { account } => {
+            JournalChange::Event(FinanceEvent::AccountSet(account.to_account()))
+        }
+        JSONJournalChange::TransferAdded { transfer } => {
+            JournalChange::Event(FinanceEvent::TransferAdded(transfer.to_transfer().ok()?))
+        }
+        JSONJournalChange::TransferRemoved { index } => {
+            JournalChange::Event(FinanceEvent::TransferRemoved(index))
+        }
+        JSONJournalChange::RecurringAdded { recurring } => {
+            JournalChange::Event(FinanceEvent::RecurringAdded(recurring.to_recurring().ok()?))
+        }
+        JSONJournalChange::RecurringRemoved { index } => {
+            JournalChange::Event(FinanceEvent::RecurringRemoved(index))
+        }
+        JSONJournalChange::GoalSet { goal } => {
+            JournalChange::Event(FinanceEvent::GoalSet(goal.to_goal().ok()?))
+        }
+        JSONJournalChange::LoanSet { loan } => {
+            JournalChange::Event(FinanceEvent::LoanSet(loan.to_loan().ok()?))
+        }
+        JSONJournalChange::EnvelopeSet { envelope } => {
+            JournalChange::Event(FinanceEvent::EnvelopeSet(envelope.to_envelope().ok()?))
+        }
+        JSONJournalChange::TaxBucketSet { category, bucket } => {
+            JournalChange::Event(FinanceEvent::TaxBucketSet { category, bucket })
+        }
+        JSONJournalChange::AnomalyThresholdSet {
+            category,
+            threshold,
+        } => JournalChange::Event(FinanceEvent::AnomalyThresholdSet {
+            category,
+            threshold,
+        }),
+        JSONJournalChange::PersonAdded { person } => {
+            JournalChange::Event(FinanceEvent::PersonAdded(person))
+        }
+        JSONJournalChange::SettlementAdded { settlement } => JournalChange::Event(
+            FinanceEvent::SettlementAdded(settlement.to_settlement().ok()?),
+        ),
+        JSONJournalChange::SettlementRemoved { index } => {
+            JournalChange::Event(FinanceEvent::SettlementRemoved(index))
+        }
+        JSONJournalChange::SubscriptionDismissed { name } => {
+            JournalChange::Event(FinanceEvent::SubscriptionDismissed(name))
+        }
+    };
+
+    Some(JournalEntry {
+        date: json_entry.date.parse().ok()?,
+        change,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Date, Month, YearMonth};
+    use tempfile::TempDir;
+
+    use crate::finance::{Finance, FinanceLog, Product};
+
+    use super::*;
+
+    #[test]
+    fn test_save_appends_events_and_load_replays_them() {
+        with_temp_journal(|repo, journal_path| {
+            let first = Finance::new()
+                .with_product(&Product::new("prod1", "cat1"))
+                .with_log(log("prod1", 10.0));
+            repo.save_on(&first, date(2024, 1, 1)).unwrap();
+
+            let second = first
+                .clone()
+                .with_product(&Product::new("prod1", "cat2"))
+                .with_log(log("prod1", 20.0));
+            repo.save_on(&second, date(2024, 2, 1)).unwrap();
+
+            let journal = fs::read_to_string(journal_path).unwrap();
+            let types = journal
+                .lines()
+                .map(|line| ::json::parse(line).unwrap()["type"].to_string())
+                .collect::<Vec<String>>();
+
+            assert_eq!(
+                types,
+                vec!["snapshot", "product_recategorized", "log_added"]
+            );
+            assert_eq!(repo.load().unwrap(), second);
+        });
+    }
+
+    #[test]
+    fn test_save_removal_and_load() {
+        with_temp_journal(|repo, journal_path| {
+            let first = Finance::new().with_log(log("prod1", 10.0));
+            let second = first.clone().with_product(&Product::new("prod1", "cat1"));
+
+            repo.save_on(&second, date(2024, 1, 1)).unwrap();
+            repo.save_on(&first, date(2024, 2, 1)).unwrap();
+
+            let journal = fs::read_to_string(journal_path).unwrap();
+            assert_eq!(journal.lines().count(), 2);
+            assert_eq!(repo.load().unwrap(), first);
+            assert_eq!(repo.load_as_of(date(2024, 1, 1)).unwrap(), second);
+        });
+    }
+
+    #[test]
+    fn test_load_as_of() {
+        with_temp_journal(|repo, _| {
+            let first = Finance::new().with_log(log("prod1", 10.0));
+            let second = first.clone().with_log(log("prod2", 20.0));
+
+            repo.save_on(&first, date(2024, 1, 1)).unwrap();
+            repo.save_on(&second, date(2024, 3, 1)).unwrap();
+
+            assert_eq!(repo.load_as_of(date(2024, 2, 15)).unwrap(), first);
+            assert_eq!(repo.load_as_of(date(2024, 3, 1)).unwrap(), second);
+            assert!(repo.load_as_of(date(2023, 12, 31)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_compact_until() {
+        with_temp_journal(|repo, journal_path| {
+            let first = Finance::new().with_log(log("prod1", 10.0));
+            let second = first.clone().with_log(log("prod2", 20.0));
+            let third = second.clone().with_log(log("prod3", 30.0));
+
+            repo.save_on(&first, date(2024, 1, 1)).unwrap();
+            repo.save_on(&second, date(2024, 2, 1)).unwrap();
+            repo.save_on(&third, date(2024, 3, 1)).unwrap();
+
+            repo.compact_until(date(2024, 2, 1)).unwrap();
+
+            assert_eq!(fs::read_to_string(journal_path).unwrap().lines().count(), 2);
+            assert_eq!(repo.load().unwrap(), third);
+            assert_eq!(repo.load_as_of(date(2024, 2, 1)).unwrap(), second);
+            assert!(repo.load_as_of(date(2024, 1, 1)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_compact_until_without_history_keeps_journal() {
+        with_temp_journal(|repo, journal_path| {
+            let finance = Finance::new().with_log(log("prod1", 10.0));
+            repo.save_on(&finance, date(2024, 3, 1)).unwrap();
+            let journal = fs::read_to_string(&journal_path).unwrap();
+
+            assert!(repo.compact_until(date(2024, 2, 1)).is_err());
+            assert_eq!(fs::read_to_string(&journal_path).unwrap(), journal);
+            assert!(repo.load_as_of(date(2024, 2, 1)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_load_corrupted_journal() {
+        with_temp_journal(|repo, journal_path| {
+            fs::write(journal_path, "not a journal entry\n").unwrap();
+
+            assert!(matches!(
+                repo.load(),
+                Err(FinanceRepositoryLoadError::Corrupted(_))
+            ));
+        });
+    }
+
+    fn with_temp_journal<F>(func: F)
+    where
+        F: Fn(JournalFinanceRepository, std::path::PathBuf),
+    {
+        let dir = TempDir::new().expect("Error creating temp dir!");
+        let journal_path = dir.path().join("finance.jsonl");
+
+        func(
+            JournalFinanceRepository::new(journal_path.to_str().unwrap()),
+            journal_path,
+        )
+    }
+
+    fn log(product: &str, price: f64) -> FinanceLog {
+        FinanceLog::new(product, price, YearMonth::new(2024, Month::January))
+    }
+
+    fn date(year: i16, month: u8, day: u8) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+}
@@ -1,59 +1,154 @@
-use gregorian::{Month, Year};
+use gregorian::{Date, Month, Year, YearMonth};
 use serde::{Deserialize, Serialize};
 
-use crate::finance::{Finance, FinanceLog, Product};
+use crate::attachments::Attachment;
+use crate::envelopes::Envelope;
+use crate::finance::{
+    Account, AccountKind, Finance, FinanceLog, Goal, GoalContribution, LogSplit, Product, Transfer,
+};
+use crate::loans::Loan;
+use crate::recurring::{RecurrenceInterval, RecurringLog};
+use crate::reimbursements::{Reimbursable, ReimbursementStatus};
+use crate::shared::{ExpenseSplit, Settlement, SharedExpense};
 
 #[derive(Deserialize, Serialize)]
 pub struct JSONFinance {
     pub products: Vec<JSONProduct>,
     pub logs: Vec<JSONLog>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<JSONAccount>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transfers: Vec<JSONTransfer>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recurring: Vec<JSONRecurringLog>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<JSONGoal>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub loans: Vec<JSONLoan>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub people: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub settlements: Vec<JSONSettlement>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub envelopes: Vec<JSONEnvelope>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dismissed_subscriptions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tax_buckets: Vec<JSONTaxBucket>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub anomaly_thresholds: Vec<JSONAnomalyThreshold>,
 }
 
 impl JSONFinance {
     pub fn from_finance(finance: &Finance) -> Self {
         Self {
-            logs: finance
-                .logs
-                .iter()
-                .map(|log| JSONLog {
-                    product: log.product.to_owned(),
-                    price: log.price,
-                    year: log.year_month.year().to_number(),
-                    month: log.year_month.month().to_number(),
-                })
-                .collect(),
+            logs: finance.logs.iter().map(JSONLog::from_log).collect(),
             products: finance
                 .products()
+                .values()
+                .map(JSONProduct::from_product)
+                .collect(),
+            accounts: finance
+                .accounts()
+                .values()
+                .map(JSONAccount::from_account)
+                .collect(),
+            transfers: finance
+                .transfers
+                .iter()
+                .map(JSONTransfer::from_transfer)
+                .collect(),
+            recurring: finance
+                .recurring
                 .iter()
-                .map(|(product_id, product)| JSONProduct {
-                    product: product_id.to_owned(), // TODO Use product.name
-                    category: product.category.to_owned(),
+                .map(JSONRecurringLog::from_recurring)
+                .collect(),
+            goals: finance.goals().values().map(JSONGoal::from_goal).collect(),
+            loans: finance.loans().values().map(JSONLoan::from_loan).collect(),
+            people: finance.people().into_iter().collect(),
+            settlements: finance
+                .settlements
+                .iter()
+                .map(JSONSettlement::from_settlement)
+                .collect(),
+            envelopes: finance
+                .envelopes()
+                .values()
+                .map(JSONEnvelope::from_envelope)
+                .collect(),
+            dismissed_subscriptions: finance.dismissed_subscriptions().into_iter().collect(),
+            tax_buckets: finance
+                .tax_buckets()
+                .into_iter()
+                .map(|(category, bucket)| JSONTaxBucket { category, bucket })
+                .collect(),
+            anomaly_thresholds: finance
+                .anomaly_thresholds()
+                .into_iter()
+                .map(|(category, threshold)| JSONAnomalyThreshold {
+                    category,
+                    threshold,
                 })
                 .collect(),
         }
     }
 
-    pub fn to_finance(&self) -> Finance {
+    pub fn to_finance(&self) -> Result<Finance, String> {
         let mut finance = Finance::new();
         for json_product in &self.products {
-            finance = finance.with_product(&Product::new(
-                &json_product.product, // TODO Add product.id to json_product
-                &json_product.category,
-            ));
+            finance = finance.with_product(&json_product.to_product());
+        }
+
+        for json_account in &self.accounts {
+            finance = finance.with_account(&json_account.to_account());
         }
 
         for json_log in self.logs.iter() {
-            finance = finance.with_log(FinanceLog {
-                product: json_log.product.to_owned(),
-                price: json_log.price,
-                year_month: gregorian::YearMonth::new(
-                    Year::new(json_log.year),
-                    Month::new(json_log.month).unwrap(), // TODO Test unwrap
-                ),
-            })
+            finance = finance.with_log(json_log.to_log()?)
+        }
+
+        for json_transfer in &self.transfers {
+            finance = finance.with_transfer(json_transfer.to_transfer()?);
+        }
+
+        for json_recurring in &self.recurring {
+            finance = finance.with_recurring(json_recurring.to_recurring()?);
+        }
+
+        for json_goal in &self.goals {
+            finance = finance.with_goal(&json_goal.to_goal()?);
+        }
+
+        for json_loan in &self.loans {
+            finance = finance.with_loan(&json_loan.to_loan()?);
+        }
+
+        for person in &self.people {
+            finance = finance.with_person(person);
+        }
+
+        for json_settlement in &self.settlements {
+            finance = finance.with_settlement(json_settlement.to_settlement()?);
+        }
+
+        for json_envelope in &self.envelopes {
+            finance = finance.with_envelope(&json_envelope.to_envelope()?);
+        }
+
+        for name in &self.dismissed_subscriptions {
+            finance = finance.with_dismissed_subscription(name);
+        }
+
+        for json_tax_bucket in &self.tax_buckets {
+            finance = finance.with_tax_bucket(&json_tax_bucket.category, &json_tax_bucket.bucket);
         }
 
-        finance
+        for json_threshold in &self.anomaly_thresholds {
+            finance =
+                finance.with_anomaly_threshold(&json_threshold.category, json_threshold.threshold);
+        }
+
+        Ok(finance)
     }
 }
 
@@ -63,10 +158,540 @@ pub struct JSONProduct {
     pub category: String,
 }
 
+impl JSONProduct {
+    pub fn from_product(product: &Product) -> Self {
+        Self {
+            product: product.id().to_owned(), // TODO Use product.name
+            category: product.category.to_owned(),
+        }
+    }
+
+    pub fn to_product(&self) -> Product {
+        Product::new(
+            &self.product, // TODO Add product.id to json_product
+            &self.category,
+        )
+    }
+}
+
 #[derive(Deserialize, Serialize)]
 pub struct JSONLog {
     pub product: String,
     pub price: f64,
     pub year: i16,
     pub month: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub splits: Vec<JSONLogSplit>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared: Option<JSONSharedExpense>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<JSONAttachment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reimbursable: Option<JSONReimbursable>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installments: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tax_bucket: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONReimbursable {
+    pub status: JSONReimbursementStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reimbursed_by: Option<usize>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JSONReimbursementStatus {
+    Pending,
+    Submitted,
+    Reimbursed,
+}
+
+impl JSONReimbursable {
+    pub fn from_reimbursable(reimbursable: &Reimbursable) -> Self {
+        Self {
+            status: match reimbursable.status {
+                ReimbursementStatus::Pending => JSONReimbursementStatus::Pending,
+                ReimbursementStatus::Submitted => JSONReimbursementStatus::Submitted,
+                ReimbursementStatus::Reimbursed => JSONReimbursementStatus::Reimbursed,
+            },
+            reimbursed_by: reimbursable.reimbursed_by,
+        }
+    }
+
+    pub fn to_reimbursable(&self) -> Reimbursable {
+        let status = match self.status {
+            JSONReimbursementStatus::Pending => ReimbursementStatus::Pending,
+            JSONReimbursementStatus::Submitted => ReimbursementStatus::Submitted,
+            JSONReimbursementStatus::Reimbursed => ReimbursementStatus::Reimbursed,
+        };
+
+        Reimbursable {
+            status,
+            reimbursed_by: self.reimbursed_by,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONAttachment {
+    pub name: String,
+    pub file: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONLogSplit {
+    pub product: String,
+    pub price: f64,
+}
+
+impl JSONLog {
+    pub fn from_log(log: &FinanceLog) -> Self {
+        Self {
+            product: log.product.to_owned(),
+            price: log.price,
+            year: log.year_month.year().to_number(),
+            month: log.year_month.month().to_number(),
+            account: log.account.to_owned(),
+            splits: log
+                .splits
+                .iter()
+                .map(|split| JSONLogSplit {
+                    product: split.product.to_owned(),
+                    price: split.price,
+                })
+                .collect(),
+            tags: log.tags.iter().cloned().collect(),
+            payee: log.payee.to_owned(),
+            note: log.note.to_owned(),
+            location: log.location.to_owned(),
+            quantity: log.quantity,
+            unit: log.unit.to_owned(),
+            shared: log.shared.as_ref().map(JSONSharedExpense::from_shared),
+            attachments: log
+                .attachments
+                .iter()
+                .map(|attachment| JSONAttachment {
+                    name: attachment.name.to_owned(),
+                    file: attachment.file_name.to_owned(),
+                })
+                .collect(),
+            reimbursable: log
+                .reimbursable
+                .as_ref()
+                .map(JSONReimbursable::from_reimbursable),
+            installments: log.installments,
+            tax_bucket: log.tax_bucket.to_owned(),
+        }
+    }
+
+    pub fn to_log(&self) -> Result<FinanceLog, String> {
+        Ok(FinanceLog {
+            product: self.product.to_owned(),
+            price: self.price,
+            year_month: to_year_month(self.year, self.month)?,
+            account: self.account.to_owned(),
+            splits: self
+                .splits
+                .iter()
+                .map(|split| LogSplit::new(&split.product, split.price))
+                .collect(),
+            tags: self.tags.iter().cloned().collect(),
+            payee: self.payee.to_owned(),
+            note: self.note.to_owned(),
+            location: self.location.to_owned(),
+            quantity: self.quantity,
+            unit: self.unit.to_owned(),
+            shared: self.shared.as_ref().map(JSONSharedExpense::to_shared),
+            attachments: self
+                .attachments
+                .iter()
+                .map(
+                    |attachment| match Attachment::is_valid_file_name(&attachment.file) {
+                        true => Ok(Attachment {
+                            name: attachment.name.to_owned(),
+                            file_name: attachment.file.to_owned(),
+                        }),
+                        false => Err(format!("Invalid attachment file {}.", attachment.file)),
+                    },
+                )
+                .collect::<Result<_, String>>()?,
+            reimbursable: self
+                .reimbursable
+                .as_ref()
+                .map(JSONReimbursable::to_reimbursable),
+            installments: self.installments,
+            tax_bucket: self.tax_bucket.to_owned(),
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONSharedExpense {
+    pub paid_by: String,
+    pub split: JSONExpenseSplit,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JSONExpenseSplit {
+    Equal(Vec<String>),
+    Percentage(Vec<(String, f64)>),
+    Exact(Vec<(String, f64)>),
+}
+
+impl JSONSharedExpense {
+    pub fn from_shared(shared: &SharedExpense) -> Self {
+        Self {
+            paid_by: shared.paid_by.to_owned(),
+            split: match &shared.split {
+                ExpenseSplit::Equal(people) => JSONExpenseSplit::Equal(people.clone()),
+                ExpenseSplit::Percentage(shares) => JSONExpenseSplit::Percentage(shares.clone()),
+                ExpenseSplit::Exact(shares) => JSONExpenseSplit::Exact(shares.clone()),
+            },
+        }
+    }
+
+    pub fn to_shared(&self) -> SharedExpense {
+        let split = match &self.split {
+            JSONExpenseSplit::Equal(people) => ExpenseSplit::Equal(people.clone()),
+            JSONExpenseSplit::Percentage(shares) => ExpenseSplit::Percentage(shares.clone()),
+            JSONExpenseSplit::Exact(shares) => ExpenseSplit::Exact(shares.clone()),
+        };
+
+        SharedExpense::new(&self.paid_by, split)
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONAccount {
+    pub account: String,
+    pub kind: JSONAccountKind,
+    pub opening_balance: f64,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JSONAccountKind {
+    Checking,
+    CreditCard,
+    Cash,
+    Savings,
+}
+
+impl JSONAccount {
+    pub fn from_account(account: &Account) -> Self {
+        Self {
+            account: account.id().to_owned(),
+            kind: match account.kind {
+                AccountKind::Checking => JSONAccountKind::Checking,
+                AccountKind::CreditCard => JSONAccountKind::CreditCard,
+                AccountKind::Cash => JSONAccountKind::Cash,
+                AccountKind::Savings => JSONAccountKind::Savings,
+            },
+            opening_balance: account.opening_balance,
+        }
+    }
+
+    pub fn to_account(&self) -> Account {
+        let kind = match self.kind {
+            JSONAccountKind::Checking => AccountKind::Checking,
+            JSONAccountKind::CreditCard => AccountKind::CreditCard,
+            JSONAccountKind::Cash => AccountKind::Cash,
+            JSONAccountKind::Savings => AccountKind::Savings,
+        };
+
+        Account::new(&self.account, kind, self.opening_balance)
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONTransfer {
+    pub from: String,
+    pub to: String,
+    pub amount: f64,
+    pub year: i16,
+    pub month: u8,
+}
+
+impl JSONTransfer {
+    pub fn from_transfer(transfer: &Transfer) -> Self {
+        Self {
+            from: transfer.from.to_owned(),
+            to: transfer.to.to_owned(),
+            amount: transfer.amount,
+            year: transfer.year_month.year().to_number(),
+            month: transfer.year_month.month().to_number(),
+        }
+    }
+
+    pub fn to_transfer(&self) -> Result<Transfer, String> {
+        Ok(Transfer::new(
+            &self.from,
+            &self.to,
+            self.amount,
+            to_year_month(self.year, self.month)?,
+        ))
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONSettlement {
+    pub from: String,
+    pub to: String,
+    pub amount: f64,
+    pub year: i16,
+    pub month: u8,
+}
+
+impl JSONSettlement {
+    pub fn from_settlement(settlement: &Settlement) -> Self {
+        Self {
+            from: settlement.from.to_owned(),
+            to: settlement.to.to_owned(),
+            amount: settlement.amount,
+            year: settlement.year_month.year().to_number(),
+            month: settlement.year_month.month().to_number(),
+        }
+    }
+
+    pub fn to_settlement(&self) -> Result<Settlement, String> {
+        Ok(Settlement::new(
+            &self.from,
+            &self.to,
+            self.amount,
+            to_year_month(self.year, self.month)?,
+        ))
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONRecurringLog {
+    pub product: String,
+    pub price: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    pub interval: JSONRecurrenceInterval,
+    pub start: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    pub materialized: usize,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JSONRecurrenceInterval {
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl JSONRecurringLog {
+    pub fn from_recurring(recurring: &RecurringLog) -> Self {
+        Self {
+            product: recurring.product.to_owned(),
+            price: recurring.price,
+            account: recurring.account.to_owned(),
+            interval: match recurring.interval {
+                RecurrenceInterval::Weekly => JSONRecurrenceInterval::Weekly,
+                RecurrenceInterval::Monthly => JSONRecurrenceInterval::Monthly,
+                RecurrenceInterval::Yearly => JSONRecurrenceInterval::Yearly,
+            },
+            start: recurring.start.to_string(),
+            end: recurring.end.map(|end| end.to_string()),
+            materialized: recurring.materialized,
+        }
+    }
+
+    pub fn to_recurring(&self) -> Result<RecurringLog, String> {
+        let interval = match self.interval {
+            JSONRecurrenceInterval::Weekly => RecurrenceInterval::Weekly,
+            JSONRecurrenceInterval::Monthly => RecurrenceInterval::Monthly,
+            JSONRecurrenceInterval::Yearly => RecurrenceInterval::Yearly,
+        };
+
+        Ok(RecurringLog {
+            product: self.product.to_owned(),
+            price: self.price,
+            account: self.account.to_owned(),
+            interval,
+            start: parse_date(&self.start)?,
+            end: self.end.as_deref().map(parse_date).transpose()?,
+            materialized: self.materialized,
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONGoal {
+    pub goal: String,
+    pub target: f64,
+    /// As "YYYY-MM".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contributions: Vec<JSONGoalContribution>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JSONGoalContribution {
+    Log(usize),
+    Transfer(usize),
+}
+
+impl JSONGoal {
+    pub fn from_goal(goal: &Goal) -> Self {
+        Self {
+            goal: goal.id().to_owned(),
+            target: goal.target,
+            deadline: goal.deadline.map(|deadline| deadline.to_string()),
+            contributions: goal
+                .contributions
+                .iter()
+                .map(|contribution| match contribution {
+                    GoalContribution::Log(index) => JSONGoalContribution::Log(*index),
+                    GoalContribution::Transfer(index) => JSONGoalContribution::Transfer(*index),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_goal(&self) -> Result<Goal, String> {
+        let goal = Goal::new(
+            &self.goal,
+            self.target,
+            self.deadline.as_deref().map(parse_year_month).transpose()?,
+        );
+
+        Ok(self
+            .contributions
+            .iter()
+            .map(|contribution| match contribution {
+                JSONGoalContribution::Log(index) => GoalContribution::Log(*index),
+                JSONGoalContribution::Transfer(index) => GoalContribution::Transfer(*index),
+            })
+            .fold(goal, Goal::with_contribution))
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONLoan {
+    pub loan: String,
+    pub principal: f64,
+    pub annual_rate: f64,
+    pub term_months: u32,
+    /// As "YYYY-MM".
+    pub start: String,
+    pub payment_product: String,
+}
+
+impl JSONLoan {
+    pub fn from_loan(loan: &Loan) -> Self {
+        Self {
+            loan: loan.id().to_owned(),
+            principal: loan.principal,
+            annual_rate: loan.annual_rate,
+            term_months: loan.term_months,
+            start: loan.start.to_string(),
+            payment_product: loan.payment_product.to_owned(),
+        }
+    }
+
+    pub fn to_loan(&self) -> Result<Loan, String> {
+        Ok(Loan::new(
+            &self.loan,
+            self.principal,
+            self.annual_rate,
+            self.term_months,
+            parse_year_month(&self.start)?,
+            &self.payment_product,
+        ))
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONTaxBucket {
+    pub category: String,
+    pub bucket: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONAnomalyThreshold {
+    pub category: String,
+    pub threshold: f64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONEnvelope {
+    pub category: String,
+    pub rollover: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allocations: Vec<JSONAllocation>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct JSONAllocation {
+    /// As "YYYY-MM".
+    pub year_month: String,
+    pub amount: f64,
+}
+
+impl JSONEnvelope {
+    pub fn from_envelope(envelope: &Envelope) -> Self {
+        Self {
+            category: envelope.category().to_owned(),
+            rollover: envelope.rollover,
+            allocations: envelope
+                .allocations
+                .iter()
+                .map(|(year_month, amount)| JSONAllocation {
+                    year_month: year_month.to_string(),
+                    amount: *amount,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_envelope(&self) -> Result<Envelope, String> {
+        self.allocations.iter().try_fold(
+            Envelope::new(&self.category, self.rollover),
+            |envelope, allocation| {
+                Ok(envelope
+                    .with_allocation(parse_year_month(&allocation.year_month)?, allocation.amount))
+            },
+        )
+    }
+}
+
+fn to_year_month(year: i16, month: u8) -> Result<YearMonth, String> {
+    Month::new(month)
+        .map(|month| Year::new(year).with_month(month))
+        .map_err(|_| format!("Invalid month {month} of {year}."))
+}
+
+fn parse_year_month(year_month: &str) -> Result<YearMonth, String> {
+    parse_date(&format!("{year_month}-01"))
+        .map(|date| date.year_month())
+        .map_err(|_| format!("Invalid month {year_month}."))
+}
+
+fn parse_date(date: &str) -> Result<Date, String> {
+    date.parse().map_err(|_| format!("Invalid date {date}."))
 }
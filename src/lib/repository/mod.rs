@@ -1,15 +1,52 @@
+use std::collections::hash_map::DefaultHasher;
 use std::env;
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::io::Write;
+use std::path::Path;
 
+pub use self::encrypted::EncryptedFinanceRepository;
+pub use self::journal::JournalFinanceRepository;
 use self::json::JSONFinance;
 use crate::finance::Finance;
 
+mod encrypted;
+mod journal;
 mod json;
 
 pub trait FinanceRepository: Clone {
     fn load(&self) -> Result<Finance, FinanceRepositoryLoadError>;
     fn save(&self, finance: &Finance) -> Result<(), String>;
+
+    /// Identifies the current contents of the underlying file, so callers can
+    /// tell whether it changed since they loaded it. `None` if there is no
+    /// file yet.
+    fn fingerprint(&self) -> Option<FileFingerprint>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FileFingerprint {
+    content_hash: u64,
+}
+
+impl FileFingerprint {
+    pub fn of_file(path: &str) -> Option<Self> {
+        let content = fs::read(path).ok()?;
+
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+
+        Some(Self {
+            content_hash: hasher.finish(),
+        })
+    }
+
+    /// Whether both fingerprints describe the same contents. Only the
+    /// contents count: files touched without changing them still match, and
+    /// changes that keep the modification time, as some sync tools do, don't.
+    pub fn matches(&self, other: &FileFingerprint) -> bool {
+        self.content_hash == other.content_hash
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -33,6 +70,14 @@ pub struct EnvJSONFinanceRepository {
 }
 
 impl EnvJSONFinanceRepository {
+    /// A repository for the JSON file at `json_path`, for when the path
+    /// doesn't come from the environment.
+    pub fn new(json_path: &str) -> Self {
+        Self {
+            json_path: json_path.to_owned(),
+        }
+    }
+
     pub fn from_env() -> Result<Self, String> {
         let json_path = env::var("FINANCE_FILE_PATH")
             .map_err(|_| "Couldn't load FINANCE_FILE_PATH variable! Is is set?")?;
@@ -41,6 +86,113 @@ impl EnvJSONFinanceRepository {
     }
 }
 
+/// The repository selected by the `FINANCE_FILE_FORMAT` variable: `json`
+/// (the default) for a single snapshot file, `journal` for an append-only
+/// event journal, or `encrypted` for a passphrase-encrypted JSON file.
+#[derive(Clone, Debug)]
+pub enum EnvFinanceRepository {
+    JSON(EnvJSONFinanceRepository),
+    Journal(JournalFinanceRepository),
+    Encrypted(EncryptedFinanceRepository),
+}
+
+impl EnvFinanceRepository {
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_env_with_passphrase(None)
+    }
+
+    /// Whether the selected repository has to be unlocked with
+    /// `from_env_with_passphrase`.
+    pub fn requires_passphrase_from_env() -> bool {
+        env::var("FINANCE_FILE_FORMAT").is_ok_and(|file_format| file_format == "encrypted")
+    }
+
+    /// Whether the selected repository is encrypted and its file doesn't
+    /// exist yet, so it has to be made with `create_encrypted_from_env`.
+    pub fn requires_new_passphrase_from_env() -> bool {
+        Self::requires_passphrase_from_env()
+            && EnvJSONFinanceRepository::from_env()
+                .is_ok_and(|repo| !Path::new(&repo.json_path).exists())
+    }
+
+    /// Creates the encrypted file selected by the environment, holding an
+    /// empty `Finance` so it loads like any other.
+    pub fn create_encrypted_from_env(passphrase: &str, confirmation: &str) -> Result<Self, String> {
+        let file_path = EnvJSONFinanceRepository::from_env()?.json_path;
+
+        let repo = EncryptedFinanceRepository::create(&file_path, passphrase, confirmation)?;
+        repo.save(&Finance::new())?;
+
+        Ok(Self::Encrypted(repo))
+    }
+
+    pub fn from_env_with_passphrase(passphrase: Option<&str>) -> Result<Self, String> {
+        let file_format = env::var("FINANCE_FILE_FORMAT").unwrap_or("json".to_owned());
+        let file_path = || EnvJSONFinanceRepository::from_env().map(|repo| repo.json_path);
+
+        match (file_format.as_str(), passphrase) {
+            ("json", _) => Ok(Self::JSON(EnvJSONFinanceRepository::from_env()?)),
+            ("journal", _) => Ok(Self::Journal(JournalFinanceRepository::new(&file_path()?))),
+            ("encrypted", Some(passphrase)) => Ok(Self::Encrypted(
+                EncryptedFinanceRepository::unlock(&file_path()?, passphrase)?,
+            )),
+            ("encrypted", None) => Err("An encrypted Finance file needs a passphrase!".to_owned()),
+            _ => Err(format!(
+                "Unknown FINANCE_FILE_FORMAT {file_format}! Expected json, journal or encrypted."
+            )),
+        }
+    }
+
+    /// The repository `FINANCE_FILE_FORMAT` selects for the file at `path`,
+    /// instead of `FINANCE_FILE_PATH`. Encrypted files aren't supported,
+    /// since they need a passphrase.
+    pub fn unencrypted_from_env_at(path: &str) -> Result<Self, String> {
+        let file_format = env::var("FINANCE_FILE_FORMAT").unwrap_or("json".to_owned());
+
+        match file_format.as_str() {
+            "json" => Ok(Self::JSON(EnvJSONFinanceRepository::new(path))),
+            "journal" => Ok(Self::Journal(JournalFinanceRepository::new(path))),
+            "encrypted" => Err("Encrypted Finance files aren't supported here!".to_owned()),
+            _ => Err(format!(
+                "Unknown FINANCE_FILE_FORMAT {file_format}! Expected json, journal or encrypted."
+            )),
+        }
+    }
+
+    pub fn journal(&self) -> Option<&JournalFinanceRepository> {
+        match self {
+            Self::Journal(journal) => Some(journal),
+            _ => None,
+        }
+    }
+}
+
+impl FinanceRepository for EnvFinanceRepository {
+    fn load(&self) -> Result<Finance, FinanceRepositoryLoadError> {
+        match self {
+            Self::JSON(repo) => repo.load(),
+            Self::Journal(repo) => repo.load(),
+            Self::Encrypted(repo) => repo.load(),
+        }
+    }
+
+    fn save(&self, finance: &Finance) -> Result<(), String> {
+        match self {
+            Self::JSON(repo) => repo.save(finance),
+            Self::Journal(repo) => repo.save(finance),
+            Self::Encrypted(repo) => repo.save(finance),
+        }
+    }
+
+    fn fingerprint(&self) -> Option<FileFingerprint> {
+        match self {
+            Self::JSON(repo) => repo.fingerprint(),
+            Self::Journal(repo) => repo.fingerprint(),
+            Self::Encrypted(repo) => repo.fingerprint(),
+        }
+    }
+}
+
 impl FinanceRepository for EnvJSONFinanceRepository {
     fn load(&self) -> Result<Finance, FinanceRepositoryLoadError> {
         let json_path = &self.json_path;
@@ -57,7 +209,9 @@ impl FinanceRepository for EnvJSONFinanceRepository {
             )
         })?;
 
-        Ok(json_finance.to_finance())
+        json_finance
+            .to_finance()
+            .map_err(FinanceRepositoryLoadError::Corrupted)
     }
 
     fn save(&self, finance: &Finance) -> Result<(), String> {
@@ -76,6 +230,10 @@ impl FinanceRepository for EnvJSONFinanceRepository {
 
         Ok(())
     }
+
+    fn fingerprint(&self) -> Option<FileFingerprint> {
+        FileFingerprint::of_file(&self.json_path)
+    }
 }
 
 #[cfg(test)]
@@ -130,6 +288,24 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_loader_err_invalid_values() {
+        let invalid_month =
+            r#"{"logs": [{"product": "prod1", "price": 10, "year": 2021, "month": 13}]}"#;
+        let invalid_date = r#"{"recurring": [{"product": "prod1", "price": 10, "interval": "monthly", "start": "next monday", "materialized": 0}]}"#;
+
+        let invalid_attachment = r#"{"logs": [{"product": "prod1", "price": 10, "year": 2021, "month": 1, "attachments": [{"name": "passwd", "file": "../../etc/passwd"}]}]}"#;
+
+        for finance_json in [invalid_month, invalid_date, invalid_attachment] {
+            with_temp_finance_json_file(finance_json, |json_file_path| {
+                let repo = EnvJSONFinanceRepository::new(json_file_path.to_str().unwrap());
+                let load_err = repo.load().expect_err("Expected load to fail!");
+
+                assert!(matches!(load_err, FinanceRepositoryLoadError::Corrupted(_)));
+            });
+        }
+    }
+
     #[test]
     fn test_from_env_load_file_open_err() {
         set_finance_file_path("inexistent-file.json");
@@ -186,6 +362,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fingerprint_changes_with_contents() {
+        with_valid_temp_finance_json_file(|json_file_path| {
+            let path = json_file_path.to_str().unwrap();
+            let loaded = FileFingerprint::of_file(path).expect("Expected a fingerprint!");
+
+            assert!(loaded.matches(&FileFingerprint::of_file(path).unwrap()));
+
+            let modified = fs::metadata(path).unwrap().modified().unwrap();
+            fs::write(path, "changed contents").unwrap();
+            fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+
+            assert!(!loaded.matches(&FileFingerprint::of_file(path).unwrap()));
+        });
+
+        assert!(FileFingerprint::of_file("inexistent-file.json").is_none());
+    }
+
     fn set_finance_file_path(value: &str) {
         env::set_var("FINANCE_FILE_PATH", value);
     }
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use gregorian::YearMonth;
+
+use crate::finance::Price;
+
+/// Balances closer to zero than this are settled; it absorbs rounding.
+pub const SETTLED_THRESHOLD: Price = 0.005;
+
+/// A log paid by one person on behalf of several.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SharedExpense {
+    pub paid_by: String,
+    pub split: ExpenseSplit,
+}
+
+/// How a shared log's price is divided between people.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExpenseSplit {
+    Equal(Vec<String>),
+    /// Shares in percent of the price.
+    Percentage(Vec<(String, f64)>),
+    Exact(Vec<(String, Price)>),
+}
+
+impl SharedExpense {
+    pub fn new(paid_by: &str, split: ExpenseSplit) -> Self {
+        Self {
+            paid_by: paid_by.to_owned(),
+            split,
+        }
+    }
+
+    /// What each person owes of `price`, including whoever paid.
+    pub fn shares(&self, price: Price) -> Vec<(String, Price)> {
+        match &self.split {
+            ExpenseSplit::Equal(people) => people
+                .iter()
+                .map(|person| (person.clone(), price / people.len() as f64))
+                .collect(),
+            ExpenseSplit::Percentage(shares) => shares
+                .iter()
+                .map(|(person, percent)| (person.clone(), price * percent / 100.0))
+                .collect(),
+            ExpenseSplit::Exact(shares) => shares.clone(),
+        }
+    }
+}
+
+/// A payment from one person to another to settle what they owe. It's
+/// recorded on its own, apart from the logs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settlement {
+    pub from: String,
+    pub to: String,
+    pub amount: Price,
+    pub year_month: YearMonth,
+}
+
+impl Settlement {
+    pub fn new(from: &str, to: &str, amount: Price, year_month: YearMonth) -> Self {
+        Self {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            amount,
+            year_month,
+        }
+    }
+}
+
+/// The payments, dated `year_month`, that bring every balance to zero.
+/// `balances` are positive for people who are owed money.
+///
+/// The biggest debtor always pays the biggest creditor, which settles at
+/// least one of them with each payment, so there is at most one payment fewer
+/// than people with a balance.
+pub fn settle_up(balances: &BTreeMap<String, Price>, year_month: YearMonth) -> Vec<Settlement> {
+    let mut balances = balances
+        .iter()
+        .filter(|(_, balance)| balance.abs() > SETTLED_THRESHOLD)
+        .map(|(person, balance)| (person.clone(), *balance))
+        .collect::<Vec<(String, Price)>>();
+
+    let mut settlements = Vec::new();
+    loop {
+        let debtor = balances
+            .iter()
+            .enumerate()
+            .min_by(|(_, left), (_, right)| left.1.total_cmp(&right.1))
+            .map(|(index, _)| index);
+        let creditor = balances
+            .iter()
+            .enumerate()
+            .max_by(|(_, left), (_, right)| left.1.total_cmp(&right.1))
+            .map(|(index, _)| index);
+
+        let (Some(debtor), Some(creditor)) = (debtor, creditor) else {
+            break;
+        };
+
+        let amount = (-balances[debtor].1).min(balances[creditor].1);
+        if amount <= SETTLED_THRESHOLD {
+            break;
+        }
+
+        settlements.push(Settlement::new(
+            &balances[debtor].0,
+            &balances[creditor].0,
+            amount,
+            year_month,
+        ));
+        balances[debtor].1 += amount;
+        balances[creditor].1 -= amount;
+    }
+
+    settlements
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use gregorian::{Month, YearMonth};
+
+    use super::{settle_up, ExpenseSplit, SharedExpense};
+
+    #[test]
+    fn test_shares() {
+        let people = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+
+        assert_eq!(
+            SharedExpense::new("ana", ExpenseSplit::Equal(people(&["ana", "bob"]))).shares(30.0),
+            vec![("ana".to_owned(), 15.0), ("bob".to_owned(), 15.0)]
+        );
+        assert_eq!(
+            SharedExpense::new(
+                "ana",
+                ExpenseSplit::Percentage(vec![("ana".to_owned(), 25.0), ("bob".to_owned(), 75.0)])
+            )
+            .shares(40.0),
+            vec![("ana".to_owned(), 10.0), ("bob".to_owned(), 30.0)]
+        );
+    }
+
+    #[test]
+    fn test_settle_up() {
+        let year_month = YearMonth::new(2024, Month::May);
+        let balances = BTreeMap::from([
+            ("ana".to_owned(), 60.0),
+            ("bob".to_owned(), -20.0),
+            ("cat".to_owned(), -40.0),
+            ("dan".to_owned(), 0.0),
+        ]);
+
+        let payments = settle_up(&balances, year_month)
+            .into_iter()
+            .map(|settlement| (settlement.from, settlement.to, settlement.amount))
+            .collect::<Vec<(String, String, f64)>>();
+
+        assert_eq!(
+            payments,
+            vec![
+                ("cat".to_owned(), "ana".to_owned(), 40.0),
+                ("bob".to_owned(), "ana".to_owned(), 20.0)
+            ]
+        );
+    }
+}
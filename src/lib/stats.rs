@@ -1,11 +1,22 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-use gregorian::YearMonth;
+use itertools::Itertools;
 
-use super::finance::{Finance, Price};
+use gregorian::{Year, YearMonth};
+
+use super::anomalies::{Anomaly, AnomalySubject, DEFAULT_ANOMALY_THRESHOLD};
+use super::categories::{is_within_category, truncate_category, DEFAULT_CATEGORY_SEPARATOR};
+use super::envelopes::Envelope;
+use super::finance::{Finance, FinanceLog, Goal, GoalContribution, LogSplit, Price};
+use super::loans::{AmortizationEntry, Loan};
+use super::periods::{Period, PeriodGranularity};
+use super::reimbursements::{reimbursed_amounts, ReimbursementStatus};
+use super::subscriptions::{detect_subscriptions, Subscription};
+use super::taxes::{TaxItem, TaxSummary};
 
 pub struct FinanceStats {
     finance: Finance,
+    category_separator: String,
 }
 
 #[derive(Clone)]
@@ -14,6 +25,16 @@ pub struct GroupedTotals {
     pub unlabeled: Price,
 }
 
+impl GroupedTotals {
+    /// Adds `other`'s totals to these.
+    pub fn merge(&mut self, other: &GroupedTotals) {
+        for (label, total) in &other.labeled {
+            *self.labeled.entry(label.clone()).or_insert(0.0) += total;
+        }
+        self.unlabeled += other.unlabeled;
+    }
+}
+
 impl Default for GroupedTotals {
     fn default() -> Self {
         Self {
@@ -23,9 +44,91 @@ impl Default for GroupedTotals {
     }
 }
 
+/// What one unit of a product cost over time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitPriceReport {
+    pub unit: String,
+    pub months: Vec<UnitPriceMonth>,
+    pub min: Price,
+    pub max: Price,
+    pub average: Price,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitPriceMonth {
+    pub year_month: YearMonth,
+    pub unit_price: Price,
+    /// Change from the previous month in the report, in percent.
+    pub change: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoalProgress {
+    pub saved: Price,
+    pub remaining: Price,
+    /// Average saved per month, from the first contribution to now.
+    pub monthly_rate: Price,
+    /// When the target was or will be reached at `monthly_rate`, or `None`
+    /// if nothing is being saved.
+    pub projected_completion: Option<YearMonth>,
+}
+
+/// The payments recorded on a loan and what is still owed on it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoanReport {
+    pub payments: Vec<LoanPayment>,
+    pub remaining_balance: Price,
+    pub interest_by_year: BTreeMap<Year, Price>,
+}
+
+/// A recorded payment, split into interest and principal, next to the
+/// scheduled payment it was matched with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoanPayment {
+    pub year_month: YearMonth,
+    pub amount: Price,
+    pub principal: Price,
+    pub interest: Price,
+    pub balance: Price,
+    pub scheduled: Option<AmortizationEntry>,
+}
+
+/// Where the zero-based budget stands in a month.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvelopeBudget {
+    pub income: Price,
+    /// Income not yet allocated to any envelope, counting every month up to
+    /// this one.
+    pub available_to_budget: Price,
+    pub envelopes: Vec<EnvelopeMonth>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnvelopeMonth {
+    pub category: String,
+    pub carried_over: Price,
+    pub allocated: Price,
+    pub spent: Price,
+    pub available: Price,
+}
+
 impl FinanceStats {
     pub fn new(log: Finance) -> Self {
-        Self { finance: log }
+        Self {
+            finance: log,
+            category_separator: DEFAULT_CATEGORY_SEPARATOR.to_owned(),
+        }
+    }
+
+    pub fn with_category_separator(self, category_separator: &str) -> Self {
+        Self {
+            category_separator: category_separator.to_owned(),
+            ..self
+        }
+    }
+
+    pub fn category_separator(&self) -> &str {
+        &self.category_separator
     }
 
     pub fn product_totals_by_year_month(&self) -> HashMap<YearMonth, GroupedTotals> {
@@ -36,42 +139,782 @@ impl FinanceStats {
         self.group_logs_by_year_month_and_label(|product| self.finance.get_category_for(product))
     }
 
-    fn group_logs_by_year_month_and_label(
+    pub fn product_totals_by_period(
         &self,
-        label_fn: impl Fn(&str) -> Option<String>,
+        granularity: PeriodGranularity,
+    ) -> HashMap<Period, GroupedTotals> {
+        totals_by_period(self.product_totals_by_year_month(), granularity)
+    }
+
+    pub fn category_totals_by_period(
+        &self,
+        granularity: PeriodGranularity,
+    ) -> HashMap<Period, GroupedTotals> {
+        totals_by_period(self.category_totals_by_year_month(), granularity)
+    }
+
+    pub fn product_totals_in(&self, period: &Period) -> GroupedTotals {
+        totals_in(&self.product_totals_by_year_month(), period)
+    }
+
+    pub fn category_totals_in(&self, period: &Period) -> GroupedTotals {
+        totals_in(&self.category_totals_by_year_month(), period)
+    }
+
+    /// Category totals rolled up to the first `depth` levels of each
+    /// category path, so "Food:Groceries" counts as "Food" at depth 1.
+    pub fn category_totals_by_year_month_at_depth(
+        &self,
+        depth: usize,
     ) -> HashMap<YearMonth, GroupedTotals> {
-        let mut result = HashMap::<YearMonth, GroupedTotals>::new();
+        self.group_logs_by_year_month_and_label(|product| {
+            self.finance
+                .get_category_for(product)
+                .map(|category| truncate_category(&category, &self.category_separator, depth))
+        })
+    }
+
+    /// How far `goal` is from its target as of `current`, and when it will
+    /// be reached at the pace of its contributions so far. Contributions
+    /// pointing at missing logs or transfers are ignored.
+    pub fn goal_progress(&self, goal: &Goal, current: YearMonth) -> GoalProgress {
+        let contributions = goal
+            .contributions
+            .iter()
+            .filter_map(|contribution| match contribution {
+                GoalContribution::Log(index) => self
+                    .finance
+                    .logs
+                    .get(*index)
+                    .map(|log| (log.year_month, log.price)),
+                GoalContribution::Transfer(index) => self
+                    .finance
+                    .transfers
+                    .get(*index)
+                    .map(|transfer| (transfer.year_month, transfer.amount)),
+            })
+            .sorted_by_key(|(year_month, _)| *year_month)
+            .collect::<Vec<(YearMonth, Price)>>();
+
+        let saved = contributions
+            .iter()
+            .fold(0.0, |saved, (_, amount)| saved + amount);
+        let remaining = (goal.target - saved).max(0.0);
+
+        let monthly_rate = match contributions.first() {
+            Some((first, _)) => saved / (months_between(*first, current) + 1).max(1) as f64,
+            None => 0.0,
+        };
+
+        let projected_completion = match remaining {
+            0.0 => contributions
+                .iter()
+                .scan(0.0, |saved_so_far, (year_month, amount)| {
+                    *saved_so_far += amount;
+                    Some((*year_month, *saved_so_far))
+                })
+                .find(|(_, saved_so_far)| *saved_so_far >= goal.target)
+                .map(|(year_month, _)| year_month),
+            _ if monthly_rate > 0.0 => {
+                Some(current.add_months((remaining / monthly_rate).ceil() as i32))
+            }
+            _ => None,
+        };
+
+        GoalProgress {
+            saved,
+            remaining,
+            monthly_rate,
+            projected_completion,
+        }
+    }
+
+    /// Matches the logs of the loan's payment product, one payment per month,
+    /// against its amortization schedule. Interest is charged on the balance
+    /// actually left for every month since the previous payment, so extra or
+    /// missed payments change it.
+    pub fn loan_report(&self, loan: &Loan) -> LoanReport {
+        let schedule = loan.schedule();
+        let mut balance = loan.principal;
+        // Interest starts accruing a month before the first payment.
+        let mut paid_until = loan.start.add_months(-1);
+
+        let payments = self
+            .finance
+            .logs
+            .iter()
+            .flat_map(|log| {
+                log.lines()
+                    .into_iter()
+                    .filter(|line| line.product == loan.payment_product)
+                    .map(|line| (log.year_month, line.price))
+            })
+            .filter(|(year_month, _)| *year_month >= loan.start)
+            .fold(BTreeMap::new(), |mut months, (year_month, amount)| {
+                *months.entry(year_month).or_insert(0.0) += amount;
+                months
+            })
+            .into_iter()
+            .map(|(year_month, amount)| {
+                let months = months_between(paid_until, year_month) as u32;
+                let (principal, interest) = loan.split_payment(balance, amount, months);
+                balance = (balance - principal).max(0.0);
+                paid_until = year_month;
+
+                LoanPayment {
+                    year_month,
+                    amount,
+                    principal,
+                    interest,
+                    balance,
+                    scheduled: schedule
+                        .iter()
+                        .find(|entry| entry.year_month == year_month)
+                        .cloned(),
+                }
+            })
+            .collect::<Vec<LoanPayment>>();
+
+        let interest_by_year = payments.iter().fold(BTreeMap::new(), |mut years, payment| {
+            *years.entry(payment.year_month.year()).or_insert(0.0) += payment.interest;
+            years
+        });
+
+        LoanReport {
+            payments,
+            remaining_balance: balance,
+            interest_by_year,
+        }
+    }
+
+    /// What each person is owed from shared logs, net of the settlements
+    /// already recorded. Negative balances are owed by that person.
+    pub fn shared_balances(&self) -> BTreeMap<String, Price> {
+        let mut balances = self
+            .finance
+            .people()
+            .into_iter()
+            .map(|person| (person, 0.0))
+            .collect::<BTreeMap<String, Price>>();
 
         for log in &self.finance.logs {
+            let Some(shared) = &log.shared else {
+                continue;
+            };
+
+            *balances.entry(shared.paid_by.clone()).or_insert(0.0) += log.price;
+            for (person, share) in shared.shares(log.price) {
+                *balances.entry(person).or_insert(0.0) -= share;
+            }
+        }
+
+        for settlement in &self.finance.settlements {
+            *balances.entry(settlement.from.clone()).or_insert(0.0) += settlement.amount;
+            *balances.entry(settlement.to.clone()).or_insert(0.0) -= settlement.amount;
+        }
+
+        balances
+    }
+
+    /// Runs the envelopes from the first month with a log or allocation up
+    /// to `year_month`. Income is what logs with a negative price bring in,
+    /// except refunds on an envelope's categories, which refill the envelope.
+    /// Leftovers of envelopes that don't roll over go back to be budgeted the
+    /// next month.
+    pub fn envelope_budget(&self, year_month: YearMonth) -> EnvelopeBudget {
+        let envelopes = self
+            .finance
+            .envelopes()
+            .into_values()
+            .sorted_by(|left, right| left.category().cmp(right.category()))
+            .collect::<Vec<Envelope>>();
+
+        let first_month = self
+            .finance
+            .logs
+            .iter()
+            .map(|log| log.year_month)
+            .chain(
+                envelopes
+                    .iter()
+                    .flat_map(|envelope| envelope.allocations.keys().copied()),
+            )
+            .min()
+            .unwrap_or(year_month)
+            .min(year_month);
+
+        let spending_logs = self.spending_logs();
+        let mut available_to_budget = 0.0;
+        let mut carried_over = vec![0.0; envelopes.len()];
+        let mut month = first_month;
+
+        loop {
+            let mut income = 0.0;
+            let mut spent = vec![0.0; envelopes.len()];
+
+            for line in spending_logs
+                .iter()
+                .filter(|log| log.year_month == month)
+                .flat_map(|log| log.lines())
+            {
+                match self.envelope_for(&envelopes, &line.product) {
+                    Some(index) => spent[index] += line.price,
+                    None if line.price < 0.0 => income -= line.price,
+                    None => {}
+                }
+            }
+
+            let month_envelopes = envelopes
+                .iter()
+                .enumerate()
+                .map(|(index, envelope)| {
+                    let allocated = envelope.allocated_in(month);
+
+                    EnvelopeMonth {
+                        category: envelope.category().to_owned(),
+                        carried_over: carried_over[index],
+                        allocated,
+                        spent: spent[index],
+                        available: carried_over[index] + allocated - spent[index],
+                    }
+                })
+                .collect::<Vec<EnvelopeMonth>>();
+
+            available_to_budget += month_envelopes
+                .iter()
+                .fold(income, |available, envelope| available - envelope.allocated);
+
+            if month >= year_month {
+                return EnvelopeBudget {
+                    income,
+                    available_to_budget,
+                    envelopes: month_envelopes,
+                };
+            }
+
+            for (index, (envelope, month_envelope)) in
+                envelopes.iter().zip(&month_envelopes).enumerate()
+            {
+                carried_over[index] = match envelope.rollover {
+                    true => month_envelope.available,
+                    false => {
+                        available_to_budget += month_envelope.available;
+                        0.0
+                    }
+                };
+            }
+
+            month = month.next();
+        }
+    }
+
+    /// The envelope with the most specific category covering `product`.
+    fn envelope_for(&self, envelopes: &[Envelope], product: &str) -> Option<usize> {
+        let category = self.finance.get_category_for(product)?;
+
+        envelopes
+            .iter()
+            .enumerate()
+            .filter(|(_, envelope)| envelope.covers(&category, &self.category_separator))
+            .max_by_key(|(_, envelope)| envelope.category().len())
+            .map(|(index, _)| index)
+    }
+
+    /// The spending of `year` that counts towards each tax bucket. A line
+    /// goes to the bucket of its log or, failing that, to the one of its
+    /// category's closest ancestor with a bucket.
+    pub fn tax_summary(&self, year: Year) -> TaxSummary {
+        let tax_buckets = self.finance.tax_buckets();
+
+        self.spending_logs()
+            .into_iter()
+            .filter(|log| log.year_month.year() == year)
+            .flat_map(|log| log.lines().into_iter().map(move |line| (log.clone(), line)))
+            .fold(TaxSummary::new(year), |summary, (log, line)| {
+                let bucket = log.tax_bucket.clone().or_else(|| {
+                    self.finance
+                        .get_category_for(&line.product)
+                        .and_then(|category| self.tax_bucket_for(&tax_buckets, &category))
+                });
+
+                match bucket {
+                    Some(bucket) => summary.with_item(
+                        &bucket,
+                        TaxItem {
+                            log,
+                            product: line.product,
+                            amount: line.price,
+                        },
+                    ),
+                    None => summary,
+                }
+            })
+    }
+
+    fn tax_bucket_for(
+        &self,
+        tax_buckets: &HashMap<String, String>,
+        category: &str,
+    ) -> Option<String> {
+        tax_buckets
+            .iter()
+            .filter(|(parent, _)| is_within_category(category, parent, &self.category_separator))
+            .max_by_key(|(parent, _)| parent.len())
+            .map(|(_, bucket)| bucket.clone())
+    }
+
+    /// Months whose spending in a category, and logs whose product price, is
+    /// unusually high compared to the ones before, newest first.
+    pub fn anomalies(&self) -> Vec<Anomaly> {
+        self.category_anomalies()
+            .into_iter()
+            .chain(self.price_anomalies())
+            .sorted_by(|left, right| right.year_month.cmp(&left.year_month))
+            .collect()
+    }
+
+    /// Compares each month's spending in a category with the months before
+    /// it, counting months without spending as zero.
+    fn category_anomalies(&self) -> Vec<Anomaly> {
+        let totals = self.category_totals_by_year_month();
+        let (Some(first), Some(last)) = (totals.keys().min(), totals.keys().max()) else {
+            return Vec::new();
+        };
+
+        let months = std::iter::successors(Some(*first), |month| Some(month.next()))
+            .take_while(|month| month <= last)
+            .collect::<Vec<YearMonth>>();
+        let categories = totals
+            .values()
+            .flat_map(|totals| totals.labeled.keys().cloned())
+            .collect::<BTreeSet<String>>();
+
+        categories
+            .into_iter()
+            .flat_map(|category| {
+                let spent = months
+                    .iter()
+                    .map(|month| {
+                        totals
+                            .get(month)
+                            .and_then(|totals| totals.labeled.get(&category))
+                            .copied()
+                            .unwrap_or(0.0)
+                    })
+                    .collect::<Vec<Price>>();
+                let threshold = self.anomaly_threshold_for(&category);
+
+                months
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, month)| {
+                        Anomaly::detect(
+                            AnomalySubject::Category(category.clone()),
+                            *month,
+                            spent[index],
+                            &spent[..index],
+                            threshold,
+                        )
+                    })
+                    .collect::<Vec<Anomaly>>()
+            })
+            .collect()
+    }
+
+    /// Compares the price of each log that isn't split with the earlier logs
+    /// of its product.
+    fn price_anomalies(&self) -> Vec<Anomaly> {
+        let mut earlier_prices = HashMap::<&str, Vec<Price>>::new();
+        let mut anomalies = Vec::new();
+
+        for (index, log) in self
+            .finance
+            .logs
+            .iter()
+            .enumerate()
+            .filter(|(_, log)| log.price > 0.0 && !log.is_split())
+            .sorted_by_key(|(_, log)| log.year_month)
+        {
+            let history = earlier_prices.entry(&log.product).or_default();
+            let threshold = self
+                .finance
+                .get_category_for(&log.product)
+                .map_or(DEFAULT_ANOMALY_THRESHOLD, |category| {
+                    self.anomaly_threshold_for(&category)
+                });
+
+            anomalies.extend(Anomaly::detect(
+                AnomalySubject::Log {
+                    index,
+                    product: log.product.clone(),
+                },
+                log.year_month,
+                log.price,
+                history,
+                threshold,
+            ));
+            history.push(log.price);
+        }
+
+        anomalies
+    }
+
+    /// The threshold of the closest ancestor of `category` that has one.
+    fn anomaly_threshold_for(&self, category: &str) -> f64 {
+        self.finance
+            .anomaly_thresholds()
+            .into_iter()
+            .filter(|(parent, _)| is_within_category(category, parent, &self.category_separator))
+            .max_by_key(|(parent, _)| parent.len())
+            .map_or(DEFAULT_ANOMALY_THRESHOLD, |(_, threshold)| threshold)
+    }
+
+    /// Subscriptions detected in the logs, leaving out dismissed ones and
+    /// those whose product already has a recurring log.
+    pub fn subscriptions(&self) -> Vec<Subscription> {
+        let dismissed = self.finance.dismissed_subscriptions();
+        let tracked = self
+            .finance
+            .recurring
+            .iter()
+            .map(|recurring| recurring.product.as_str())
+            .collect::<BTreeSet<&str>>();
+
+        detect_subscriptions(&self.finance.logs)
+            .into_iter()
+            .filter(|subscription| !dismissed.contains(&subscription.name))
+            .filter(|subscription| !tracked.contains(subscription.product.as_str()))
+            .collect()
+    }
+
+    /// Reimbursable logs still pending from `from` to `to`, both included,
+    /// for an expense report.
+    pub fn pending_reimbursements(&self, from: YearMonth, to: YearMonth) -> Vec<FinanceLog> {
+        self.finance
+            .logs
+            .iter()
+            .filter(|log| (from..=to).contains(&log.year_month))
+            .filter(|log| {
+                log.reimbursable
+                    .as_ref()
+                    .is_some_and(|reimbursable| reimbursable.status == ReimbursementStatus::Pending)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Products with a quantity on any of their logs.
+    pub fn products_with_quantities(&self) -> BTreeSet<String> {
+        self.finance
+            .logs
+            .iter()
+            .filter(|log| log.quantity.is_some())
+            .map(|log| log.product.clone())
+            .collect()
+    }
+
+    /// Unit price of `product` in every month it was bought with a quantity,
+    /// weighting each log by its quantity. Only logs in the unit of the first
+    /// such log are counted, so litres and gallons don't mix.
+    pub fn unit_price_report(&self, product: &str) -> Option<UnitPriceReport> {
+        let quantity_logs = self
+            .finance
+            .logs
+            .iter()
+            .filter(|log| log.product == product && !log.is_split())
+            .filter_map(|log| Some((log, log.quantity?, log.unit.clone()?)))
+            .filter(|(_, quantity, _)| *quantity > 0.0)
+            .collect::<Vec<_>>();
+
+        let unit = quantity_logs.first()?.2.clone();
+
+        let mut totals_by_year_month = BTreeMap::<YearMonth, (Price, f64)>::new();
+        for (log, quantity, _) in quantity_logs
+            .iter()
+            .filter(|(_, _, log_unit)| *log_unit == unit)
+        {
+            let (total_price, total_quantity) =
+                totals_by_year_month.entry(log.year_month).or_default();
+            *total_price += log.price;
+            *total_quantity += quantity;
+        }
+
+        let mut months = Vec::<UnitPriceMonth>::new();
+        for (year_month, (total_price, total_quantity)) in totals_by_year_month {
+            let unit_price = total_price / total_quantity;
+            let change = months
+                .last()
+                .filter(|previous| previous.unit_price != 0.0)
+                .map(|previous| (unit_price - previous.unit_price) / previous.unit_price * 100.0);
+
+            months.push(UnitPriceMonth {
+                year_month,
+                unit_price,
+                change,
+            });
+        }
+
+        let unit_prices = months.iter().map(|month| month.unit_price);
+
+        Some(UnitPriceReport {
+            unit,
+            min: unit_prices.clone().fold(f64::INFINITY, f64::min),
+            max: unit_prices.clone().fold(f64::NEG_INFINITY, f64::max),
+            average: unit_prices.sum::<Price>() / months.len() as f64,
+            months,
+        })
+    }
+
+    /// Totals by payee. Logs without a payee are unlabeled.
+    pub fn payee_totals_by_year_month(&self) -> HashMap<YearMonth, GroupedTotals> {
+        let mut result = HashMap::<YearMonth, GroupedTotals>::new();
+
+        for log in &self.spending_logs() {
             let grouped_totals = result.entry(log.year_month).or_default();
-            let label = label_fn(&log.product);
 
-            if let Some(label) = label {
-                let current_total = grouped_totals
-                    .labeled
-                    .entry(label.clone())
-                    .or_insert(0.0)
-                    .to_owned();
+            match &log.payee {
+                Some(payee) => {
+                    *grouped_totals.labeled.entry(payee.clone()).or_insert(0.0) += log.price
+                }
+                None => grouped_totals.unlabeled += log.price,
+            }
+        }
+
+        result
+    }
 
-                let new_total = current_total + log.price;
+    /// Totals of the logs with each tag. A log counts fully towards every one
+    /// of its tags, so totals of different tags may overlap; untagged logs
+    /// are unlabeled.
+    pub fn tag_totals_by_year_month(&self) -> HashMap<YearMonth, GroupedTotals> {
+        let mut result = HashMap::<YearMonth, GroupedTotals>::new();
 
-                grouped_totals.labeled.insert(label.clone(), new_total);
-            } else {
+        for log in &self.spending_logs() {
+            let grouped_totals = result.entry(log.year_month).or_default();
+
+            if log.tags.is_empty() {
                 grouped_totals.unlabeled += log.price;
             }
+
+            for tag in &log.tags {
+                *grouped_totals.labeled.entry(tag.clone()).or_insert(0.0) += log.price;
+            }
+        }
+
+        result
+    }
+
+    /// Balance of every account at the end of each month, from the first to
+    /// the last month with logs or transfers on any account.
+    pub fn account_balances_by_year_month(&self) -> HashMap<YearMonth, GroupedTotals> {
+        let mut movements_by_year_month = HashMap::<YearMonth, Vec<(String, Price)>>::new();
+        for (year_month, account, amount) in self.account_movements() {
+            movements_by_year_month
+                .entry(year_month)
+                .or_default()
+                .push((account, amount));
+        }
+
+        let mut result = HashMap::new();
+        let (Some(first), Some(last)) = (
+            movements_by_year_month.keys().min().copied(),
+            movements_by_year_month.keys().max().copied(),
+        ) else {
+            return result;
+        };
+
+        let mut balances = self.opening_balances();
+        let mut year_month = first;
+        while year_month <= last {
+            for (account, amount) in movements_by_year_month
+                .get(&year_month)
+                .into_iter()
+                .flatten()
+            {
+                Self::add_to_balance(&mut balances, account, *amount);
+            }
+
+            result.insert(
+                year_month,
+                GroupedTotals {
+                    labeled: balances.clone(),
+                    unlabeled: 0.0,
+                },
+            );
+            year_month = year_month.next();
+        }
+
+        result
+    }
+
+    /// Balance of every account after all of its logs and transfers.
+    pub fn account_balances(&self) -> HashMap<String, Price> {
+        let mut balances = self.opening_balances();
+        for (_, account, amount) in self.account_movements() {
+            Self::add_to_balance(&mut balances, &account, amount);
+        }
+
+        balances
+    }
+
+    fn opening_balances(&self) -> HashMap<String, Price> {
+        self.finance
+            .accounts()
+            .into_iter()
+            .map(|(id, account)| (id, account.opening_balance))
+            .collect()
+    }
+
+    /// Every change to an account balance. Logs draw their price from their
+    /// account, and transfers move their amount between two accounts.
+    fn account_movements(&self) -> Vec<(YearMonth, String, Price)> {
+        let log_movements = self
+            .finance
+            .logs
+            .iter()
+            .flat_map(FinanceLog::installment_logs)
+            .filter_map(|log| {
+                log.account
+                    .as_ref()
+                    .map(|account| (log.year_month, account.to_owned(), -log.price))
+            });
+
+        let transfer_movements = self.finance.transfers.iter().flat_map(|transfer| {
+            [
+                (
+                    transfer.year_month,
+                    transfer.from.to_owned(),
+                    -transfer.amount,
+                ),
+                (transfer.year_month, transfer.to.to_owned(), transfer.amount),
+            ]
+        });
+
+        let accounts = self.finance.accounts();
+
+        log_movements
+            .chain(transfer_movements)
+            .filter(|(_, account, _)| accounts.contains_key(account))
+            .collect()
+    }
+
+    fn add_to_balance(balances: &mut HashMap<String, Price>, account: &str, amount: Price) {
+        if let Some(balance) = balances.get_mut(account) {
+            *balance += amount;
+        }
+    }
+
+    /// The logs as they count towards spending: without what reimbursements
+    /// paid back, on both the expense and the income side, and with
+    /// installment logs spread over their months. Fully reimbursed logs are
+    /// left out.
+    fn spending_logs(&self) -> Vec<FinanceLog> {
+        let reimbursed = reimbursed_amounts(&self.finance.logs);
+
+        self.finance
+            .logs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, log)| match reimbursed.get(&index) {
+                None => Some(log.clone()),
+                Some(amount) if (log.price - amount).abs() < f64::EPSILON => None,
+                Some(amount) => {
+                    let share = (log.price - amount) / log.price;
+
+                    Some(FinanceLog {
+                        price: log.price - amount,
+                        splits: log
+                            .splits
+                            .iter()
+                            .map(|split| LogSplit::new(&split.product, split.price * share))
+                            .collect(),
+                        ..log.clone()
+                    })
+                }
+            })
+            .flat_map(|log| log.installment_logs())
+            .collect()
+    }
+
+    fn group_logs_by_year_month_and_label(
+        &self,
+        label_fn: impl Fn(&str) -> Option<String>,
+    ) -> HashMap<YearMonth, GroupedTotals> {
+        let mut result = HashMap::<YearMonth, GroupedTotals>::new();
+
+        for log in &self.spending_logs() {
+            let grouped_totals = result.entry(log.year_month).or_default();
+
+            for line in log.lines() {
+                match label_fn(&line.product) {
+                    Some(label) => {
+                        *grouped_totals.labeled.entry(label).or_insert(0.0) += line.price
+                    }
+                    None => grouped_totals.unlabeled += line.price,
+                }
+            }
         }
 
         result
     }
 }
 
+/// Adds up monthly totals, like the ones of `product_totals_by_year_month`,
+/// into the periods of `granularity` they fall in.
+pub fn totals_by_period(
+    totals_by_year_month: HashMap<YearMonth, GroupedTotals>,
+    granularity: PeriodGranularity,
+) -> HashMap<Period, GroupedTotals> {
+    let mut result = HashMap::<Period, GroupedTotals>::new();
+    for (year_month, totals) in totals_by_year_month {
+        result
+            .entry(granularity.period_of(year_month))
+            .or_default()
+            .merge(&totals);
+    }
+
+    result
+}
+
+/// Adds up the monthly totals of the months in `period`.
+pub fn totals_in(
+    totals_by_year_month: &HashMap<YearMonth, GroupedTotals>,
+    period: &Period,
+) -> GroupedTotals {
+    totals_by_year_month
+        .iter()
+        .filter(|(year_month, _)| period.contains(**year_month))
+        .fold(GroupedTotals::default(), |mut result, (_, totals)| {
+            result.merge(totals);
+            result
+        })
+}
+
+pub(crate) fn months_between(from: YearMonth, to: YearMonth) -> i32 {
+    let months = |year_month: YearMonth| {
+        i32::from(year_month.year().to_number()) * 12 + i32::from(year_month.month().to_number())
+    };
+
+    months(to) - months(from)
+}
+
 #[cfg(test)]
 mod tests {
     use gregorian::{Month, YearMonth};
+    use itertools::Itertools;
 
-    use crate::finance::{FinanceLog, Product};
+    use crate::anomalies::AnomalySubject;
+    use crate::envelopes::Envelope;
+    use crate::finance::{
+        Account, AccountKind, FinanceLog, Goal, GoalContribution, LogSplit, Product, Transfer,
+    };
+    use crate::loans::Loan;
+    use crate::periods::{Period, PeriodGranularity};
+    use crate::recurring::{RecurrenceInterval, RecurringLog};
+    use crate::reimbursements::{Reimbursable, ReimbursementStatus};
+    use crate::shared::{ExpenseSplit, Settlement, SharedExpense};
 
-    use super::{Finance, FinanceStats};
+    use super::{EnvelopeMonth, Finance, FinanceStats};
 
     #[test]
     fn product_totals_by_year_month() {
@@ -165,4 +1008,684 @@ mod tests {
             30.0
         );
     }
+
+    #[test]
+    fn account_balances_by_year_month() {
+        let finance = Finance::new()
+            .with_account(&Account::new("checking", AccountKind::Checking, 100.0))
+            .with_account(&Account::new("cash", AccountKind::Cash, 20.0))
+            .with_log(
+                FinanceLog::new("prod1", 10.0, YearMonth::new(2022, Month::January))
+                    .with_account("checking"),
+            )
+            .with_log(
+                FinanceLog::new("salary", -50.0, YearMonth::new(2022, Month::March))
+                    .with_account("checking"),
+            )
+            .with_log(FinanceLog::new(
+                "prod2",
+                5.0,
+                YearMonth::new(2022, Month::March),
+            ));
+
+        let stats = FinanceStats::new(finance);
+        let balances = stats.account_balances_by_year_month();
+
+        assert_eq!(balances.len(), 3);
+
+        let balance_on = |year_month: YearMonth, account: &str| {
+            balances
+                .get(&year_month)
+                .unwrap()
+                .labeled
+                .get(account)
+                .copied()
+        };
+
+        assert_eq!(
+            balance_on(YearMonth::new(2022, Month::January), "checking"),
+            Some(90.0)
+        );
+        assert_eq!(
+            balance_on(YearMonth::new(2022, Month::February), "checking"),
+            Some(90.0)
+        );
+        assert_eq!(
+            balance_on(YearMonth::new(2022, Month::March), "checking"),
+            Some(140.0)
+        );
+        assert_eq!(
+            balance_on(YearMonth::new(2022, Month::March), "cash"),
+            Some(20.0)
+        );
+
+        assert_eq!(stats.account_balances().get("checking"), Some(&140.0));
+    }
+
+    #[test]
+    fn transfers_move_balances_but_not_totals() {
+        let finance = Finance::new()
+            .with_account(&Account::new("checking", AccountKind::Checking, 100.0))
+            .with_account(&Account::new("card", AccountKind::CreditCard, -30.0))
+            .with_transfer(Transfer::new(
+                "checking",
+                "card",
+                30.0,
+                YearMonth::new(2022, Month::April),
+            ));
+
+        let stats = FinanceStats::new(finance);
+        let balances = stats.account_balances();
+
+        assert_eq!(balances.get("checking"), Some(&70.0));
+        assert_eq!(balances.get("card"), Some(&0.0));
+        assert_eq!(
+            stats
+                .account_balances_by_year_month()
+                .get(&YearMonth::new(2022, Month::April))
+                .unwrap()
+                .labeled
+                .get("card"),
+            Some(&0.0)
+        );
+        assert!(stats.category_totals_by_year_month().is_empty());
+    }
+
+    #[test]
+    fn split_logs_count_per_line() {
+        let finance = Finance::new()
+            .with_product(&Product::new("bread", "groceries"))
+            .with_product(&Product::new("soap", "household"))
+            .with_product(&Product::new("kibble", "pets"))
+            .with_log(FinanceLog::split(
+                "supermarket",
+                vec![
+                    LogSplit::new("bread", 3.0),
+                    LogSplit::new("soap", 2.5),
+                    LogSplit::new("kibble", 12.0),
+                ],
+                YearMonth::new(2022, Month::May),
+            ));
+
+        assert_eq!(finance.logs[0].price, 17.5);
+
+        let stats = FinanceStats::new(finance);
+        let totals = stats.category_totals_by_year_month();
+        let may = totals.get(&YearMonth::new(2022, Month::May)).unwrap();
+
+        assert_eq!(may.labeled.get("groceries"), Some(&3.0));
+        assert_eq!(may.labeled.get("household"), Some(&2.5));
+        assert_eq!(may.labeled.get("pets"), Some(&12.0));
+        assert_eq!(may.unlabeled, 0.0);
+    }
+
+    #[test]
+    fn tag_totals_by_year_month() {
+        let finance = Finance::new()
+            .with_log(
+                FinanceLog::new("hotel", 200.0, YearMonth::new(2024, Month::July))
+                    .with_tag("vacation-2024")
+                    .with_tag("reimbursable"),
+            )
+            .with_log(
+                FinanceLog::new("museum", 20.0, YearMonth::new(2024, Month::July))
+                    .with_tag("vacation-2024"),
+            )
+            .with_log(FinanceLog::new(
+                "groceries",
+                50.0,
+                YearMonth::new(2024, Month::July),
+            ));
+
+        let totals = FinanceStats::new(finance).tag_totals_by_year_month();
+        let july = totals.get(&YearMonth::new(2024, Month::July)).unwrap();
+
+        assert_eq!(july.labeled.get("vacation-2024"), Some(&220.0));
+        assert_eq!(july.labeled.get("reimbursable"), Some(&200.0));
+        assert_eq!(july.unlabeled, 50.0);
+    }
+
+    #[test]
+    fn payee_totals_by_year_month() {
+        let year_month = YearMonth::new(2024, Month::August);
+        let finance = Finance::new()
+            .with_log(FinanceLog::new("bread", 3.0, year_month).with_payee("Bakery"))
+            .with_log(FinanceLog::new("cake", 12.0, year_month).with_payee("Bakery"))
+            .with_log(FinanceLog::new("bus", 2.0, year_month));
+
+        let totals = FinanceStats::new(finance).payee_totals_by_year_month();
+        let august = totals.get(&year_month).unwrap();
+
+        assert_eq!(august.labeled.get("Bakery"), Some(&15.0));
+        assert_eq!(august.unlabeled, 2.0);
+    }
+
+    #[test]
+    fn unit_price_report() {
+        let finance = Finance::new()
+            .with_log(
+                FinanceLog::new("fuel", 40.0, YearMonth::new(2024, Month::January))
+                    .with_quantity(20.0, "l"),
+            )
+            .with_log(
+                FinanceLog::new("fuel", 60.0, YearMonth::new(2024, Month::March))
+                    .with_quantity(25.0, "l"),
+            )
+            .with_log(
+                FinanceLog::new("fuel", 30.0, YearMonth::new(2024, Month::March))
+                    .with_quantity(5.0, "gal"),
+            )
+            .with_log(FinanceLog::new(
+                "fuel",
+                10.0,
+                YearMonth::new(2024, Month::April),
+            ));
+
+        let stats = FinanceStats::new(finance);
+        let report = stats.unit_price_report("fuel").unwrap();
+
+        assert_eq!(report.unit, "l");
+        assert_eq!(report.months.len(), 2);
+        assert_eq!(report.months[0].unit_price, 2.0);
+        assert_eq!(report.months[0].change, None);
+        assert_eq!(report.months[1].unit_price, 2.4);
+        assert!((report.months[1].change.unwrap() - 20.0).abs() < 1e-9);
+        assert_eq!(report.min, 2.0);
+        assert_eq!(report.max, 2.4);
+        assert!((report.average - 2.2).abs() < 1e-9);
+
+        assert!(stats.unit_price_report("bread").is_none());
+        assert_eq!(
+            stats
+                .products_with_quantities()
+                .into_iter()
+                .collect::<Vec<String>>(),
+            vec!["fuel"]
+        );
+    }
+
+    #[test]
+    fn category_totals_by_year_month_at_depth() {
+        let year_month = YearMonth::new(2024, Month::October);
+        let finance = Finance::new()
+            .with_product(&Product::new("sushi", "Food/Restaurants/Japanese"))
+            .with_product(&Product::new("bread", "Food/Groceries"))
+            .with_product(&Product::new("rent", "Home"))
+            .with_log(FinanceLog::new("sushi", 30.0, year_month))
+            .with_log(FinanceLog::new("bread", 3.0, year_month))
+            .with_log(FinanceLog::new("rent", 500.0, year_month));
+
+        let stats = FinanceStats::new(finance).with_category_separator("/");
+
+        let top_level = stats.category_totals_by_year_month_at_depth(1);
+        let october = top_level.get(&year_month).unwrap();
+        assert_eq!(october.labeled.get("Food"), Some(&33.0));
+        assert_eq!(october.labeled.get("Home"), Some(&500.0));
+
+        let second_level = stats.category_totals_by_year_month_at_depth(2);
+        let october = second_level.get(&year_month).unwrap();
+        assert_eq!(october.labeled.get("Food/Restaurants"), Some(&30.0));
+        assert_eq!(october.labeled.get("Food/Groceries"), Some(&3.0));
+    }
+
+    #[test]
+    fn goal_progress() {
+        let finance = Finance::new()
+            .with_log(FinanceLog::new(
+                "car fund",
+                100.0,
+                YearMonth::new(2024, Month::January),
+            ))
+            .with_transfer(Transfer::new(
+                "checking",
+                "savings",
+                200.0,
+                YearMonth::new(2024, Month::February),
+            ));
+
+        let goal = Goal::new("car", 1000.0, None)
+            .with_contribution(GoalContribution::Log(0))
+            .with_contribution(GoalContribution::Transfer(0))
+            .with_contribution(GoalContribution::Log(5));
+
+        let stats = FinanceStats::new(finance);
+        let progress = stats.goal_progress(&goal, YearMonth::new(2024, Month::March));
+
+        assert_eq!(progress.saved, 300.0);
+        assert_eq!(progress.remaining, 700.0);
+        assert_eq!(progress.monthly_rate, 100.0);
+        assert_eq!(
+            progress.projected_completion,
+            Some(YearMonth::new(2024, Month::October))
+        );
+
+        let mut reached_goal = goal;
+        reached_goal.target = 250.0;
+
+        let reached = stats.goal_progress(&reached_goal, YearMonth::new(2024, Month::March));
+        assert_eq!(reached.remaining, 0.0);
+        assert_eq!(
+            reached.projected_completion,
+            Some(YearMonth::new(2024, Month::February))
+        );
+    }
+
+    #[test]
+    fn loan_report() {
+        let loan = Loan::new(
+            "car",
+            1200.0,
+            12.0,
+            12,
+            YearMonth::new(2024, Month::November),
+            "car loan",
+        );
+
+        let finance = Finance::new()
+            .with_log(FinanceLog::new(
+                "car loan",
+                112.0,
+                YearMonth::new(2024, Month::December),
+            ))
+            .with_log(FinanceLog::new(
+                "car loan",
+                112.0,
+                YearMonth::new(2024, Month::November),
+            ))
+            .with_log(FinanceLog::new(
+                "car loan",
+                400.0,
+                YearMonth::new(2025, Month::January),
+            ))
+            .with_log(FinanceLog::new(
+                "car loan",
+                50.0,
+                YearMonth::new(2024, Month::October),
+            ))
+            .with_log(FinanceLog::new(
+                "groceries",
+                30.0,
+                YearMonth::new(2024, Month::December),
+            ));
+
+        let report = FinanceStats::new(finance).loan_report(&loan);
+
+        assert_eq!(report.payments.len(), 3);
+        assert_eq!(report.payments[0].interest, 12.0);
+        assert_eq!(report.payments[0].principal, 100.0);
+        assert_eq!(report.payments[0].balance, 1100.0);
+        assert_eq!(report.payments[1].interest, 11.0);
+        assert_eq!(
+            report.payments[2]
+                .scheduled
+                .as_ref()
+                .map(|entry| entry.year_month),
+            Some(YearMonth::new(2025, Month::January))
+        );
+        assert!((report.remaining_balance - 608.99).abs() < 1e-9);
+
+        let interest_by_year = report
+            .interest_by_year
+            .into_iter()
+            .map(|(year, interest)| (year.to_number(), interest))
+            .collect::<Vec<(i16, f64)>>();
+        assert_eq!(interest_by_year[0], (2024, 23.0));
+        assert_eq!(interest_by_year[1].0, 2025);
+        assert!((interest_by_year[1].1 - 9.99).abs() < 1e-9);
+    }
+
+    #[test]
+    fn loan_report_skipped_month() {
+        let loan = Loan::new(
+            "car",
+            1200.0,
+            12.0,
+            12,
+            YearMonth::new(2024, Month::November),
+            "car loan",
+        );
+
+        let finance = Finance::new()
+            .with_log(FinanceLog::new(
+                "car loan",
+                112.0,
+                YearMonth::new(2024, Month::November),
+            ))
+            .with_log(FinanceLog::new(
+                "car loan",
+                224.0,
+                YearMonth::new(2025, Month::January),
+            ));
+
+        let report = FinanceStats::new(finance).loan_report(&loan);
+
+        assert_eq!(report.payments.len(), 2);
+        assert!((report.payments[1].interest - 22.11).abs() < 1e-9);
+        assert_eq!(
+            report.payments[1]
+                .scheduled
+                .as_ref()
+                .map(|entry| entry.year_month),
+            Some(YearMonth::new(2025, Month::January))
+        );
+        assert!((report.remaining_balance - 898.11).abs() < 1e-9);
+    }
+
+    #[test]
+    fn loan_report_doubled_month() {
+        let loan = Loan::new(
+            "car",
+            1200.0,
+            12.0,
+            12,
+            YearMonth::new(2024, Month::November),
+            "car loan",
+        );
+
+        let finance = Finance::new()
+            .with_log(FinanceLog::new(
+                "car loan",
+                112.0,
+                YearMonth::new(2024, Month::November),
+            ))
+            .with_log(FinanceLog::new(
+                "car loan",
+                100.0,
+                YearMonth::new(2024, Month::November),
+            ))
+            .with_log(FinanceLog::new(
+                "car loan",
+                112.0,
+                YearMonth::new(2024, Month::December),
+            ));
+
+        let report = FinanceStats::new(finance).loan_report(&loan);
+
+        assert_eq!(report.payments.len(), 2);
+        assert_eq!(report.payments[0].amount, 212.0);
+        assert_eq!(report.payments[0].interest, 12.0);
+        assert_eq!(report.payments[0].balance, 1000.0);
+        assert_eq!(report.payments[1].interest, 10.0);
+        assert_eq!(
+            report.payments[1]
+                .scheduled
+                .as_ref()
+                .map(|entry| entry.year_month),
+            Some(YearMonth::new(2024, Month::December))
+        );
+    }
+
+    #[test]
+    fn shared_balances() {
+        let year_month = YearMonth::new(2024, Month::May);
+        let finance = Finance::new()
+            .with_person("ana")
+            .with_person("bob")
+            .with_person("cat")
+            .with_log(FinanceLog::new("groceries", 90.0, year_month).with_shared(
+                SharedExpense::new(
+                    "ana",
+                    ExpenseSplit::Equal(vec!["ana".to_owned(), "bob".to_owned(), "cat".to_owned()]),
+                ),
+            ))
+            .with_log(
+                FinanceLog::new("power", 50.0, year_month).with_shared(SharedExpense::new(
+                    "bob",
+                    ExpenseSplit::Exact(vec![("ana".to_owned(), 20.0), ("cat".to_owned(), 30.0)]),
+                )),
+            )
+            .with_log(FinanceLog::new("coffee", 3.0, year_month))
+            .with_settlement(Settlement::new("cat", "ana", 10.0, year_month));
+
+        let balances = FinanceStats::new(finance).shared_balances();
+
+        assert_eq!(
+            balances.into_iter().collect::<Vec<(String, f64)>>(),
+            vec![
+                ("ana".to_owned(), 30.0),
+                ("bob".to_owned(), 20.0),
+                ("cat".to_owned(), -50.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn envelope_budget() {
+        let april = YearMonth::new(2024, Month::April);
+        let may = YearMonth::new(2024, Month::May);
+
+        let finance = Finance::new()
+            .with_product(&Product::new("groceries", "Food:Groceries"))
+            .with_product(&Product::new("cinema", "Fun"))
+            .with_product(&Product::new("salary", "Income"))
+            .with_log(FinanceLog::new("salary", -1000.0, april))
+            .with_log(FinanceLog::new("groceries", 150.0, april))
+            .with_log(FinanceLog::new("cinema", 20.0, april))
+            .with_log(FinanceLog::new("salary", -1000.0, may))
+            .with_log(FinanceLog::new("groceries", 120.0, may))
+            .with_envelope(
+                &Envelope::new("Food", true)
+                    .with_allocation(april, 200.0)
+                    .with_allocation(may, 200.0),
+            )
+            .with_envelope(&Envelope::new("Fun", false).with_allocation(april, 50.0));
+
+        let stats = FinanceStats::new(finance);
+
+        let april_budget = stats.envelope_budget(april);
+        assert_eq!(april_budget.income, 1000.0);
+        assert_eq!(april_budget.available_to_budget, 750.0);
+
+        let may_budget = stats.envelope_budget(may);
+        assert_eq!(may_budget.income, 1000.0);
+        // 750 left from April, 30 back from Fun, 1000 of income, 200 to Food.
+        assert_eq!(may_budget.available_to_budget, 1580.0);
+        assert_eq!(
+            may_budget.envelopes[0],
+            EnvelopeMonth {
+                category: "Food".to_owned(),
+                carried_over: 50.0,
+                allocated: 200.0,
+                spent: 120.0,
+                available: 130.0,
+            }
+        );
+        assert_eq!(may_budget.envelopes[1].carried_over, 0.0);
+    }
+
+    #[test]
+    fn reimbursed_amounts_are_not_spending() {
+        let may = YearMonth::new(2024, Month::May);
+        let reimbursed = Reimbursable::new(ReimbursementStatus::Reimbursed).with_reimbursed_by(0);
+
+        let finance = Finance::new()
+            .with_log(FinanceLog::new("refund", -50.0, may))
+            .with_log(FinanceLog::new("hotel", 80.0, may).with_reimbursable(reimbursed))
+            .with_log(
+                FinanceLog::new("taxi", 20.0, may)
+                    .with_reimbursable(Reimbursable::new(ReimbursementStatus::Pending)),
+            );
+
+        let stats = FinanceStats::new(finance);
+        let totals = stats.product_totals_by_year_month();
+        let may_totals = totals.get(&may).unwrap();
+
+        assert_eq!(may_totals.labeled.get("refund"), None);
+        assert_eq!(may_totals.labeled.get("hotel"), Some(&30.0));
+        assert_eq!(may_totals.labeled.get("taxi"), Some(&20.0));
+
+        let pending = stats.pending_reimbursements(may, may);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].product, "taxi");
+        assert!(stats
+            .pending_reimbursements(may.next(), may.next())
+            .is_empty());
+    }
+
+    #[test]
+    fn dismissed_and_tracked_subscriptions_are_left_out() {
+        let january = YearMonth::new(2024, Month::January);
+        let finance = (0..3).fold(Finance::new(), |finance, month| {
+            finance
+                .with_log(FinanceLog::new("music", 10.0, january.add_months(month)))
+                .with_log(FinanceLog::new("video", 12.0, january.add_months(month)))
+                .with_log(FinanceLog::new("news", 5.0, january.add_months(month)))
+        });
+        let finance =
+            finance
+                .with_dismissed_subscription("news")
+                .with_recurring(RecurringLog::new(
+                    "video",
+                    12.0,
+                    RecurrenceInterval::Monthly,
+                    january.first_day(),
+                ));
+
+        let names = FinanceStats::new(finance)
+            .subscriptions()
+            .into_iter()
+            .map(|subscription| subscription.name)
+            .collect::<Vec<String>>();
+
+        assert_eq!(names, vec!["music"]);
+    }
+
+    #[test]
+    fn installments_are_spread_over_months() {
+        let january = YearMonth::new(2024, Month::January);
+        let finance = Finance::new()
+            .with_account(&Account::new("card", AccountKind::CreditCard, 0.0))
+            .with_log(
+                FinanceLog::new("laptop", 1000.0, january)
+                    .with_installments(3)
+                    .with_account("card"),
+            );
+        let stats = FinanceStats::new(finance);
+
+        let totals = stats.product_totals_by_year_month();
+        let laptop_in = |months: i32| {
+            totals
+                .get(&january.add_months(months))
+                .and_then(|totals| totals.labeled.get("laptop"))
+                .copied()
+        };
+
+        assert_eq!(laptop_in(0), Some(333.33));
+        assert_eq!(laptop_in(1), Some(333.33));
+        assert!((laptop_in(2).unwrap() - 333.34).abs() < 1e-9);
+        assert_eq!(laptop_in(3), None);
+
+        let balances = stats.account_balances_by_year_month();
+        assert_eq!(
+            balances.get(&january).unwrap().labeled.get("card"),
+            Some(&-333.33)
+        );
+    }
+
+    #[test]
+    fn tax_summary_uses_log_and_category_buckets() {
+        let march = YearMonth::new(2024, Month::March);
+        let finance = Finance::new()
+            .with_product(&Product::new("dentist", "Health:Dental"))
+            .with_product(&Product::new("pills", "Health"))
+            .with_product(&Product::new("desk", "Furniture"))
+            .with_tax_bucket("Health", "Medical")
+            .with_log(FinanceLog::new("dentist", 80.0, march))
+            .with_log(FinanceLog::new("pills", 15.0, march.add_months(-3)))
+            .with_log(FinanceLog::new("desk", 200.0, march).with_tax_bucket("Home office"))
+            .with_log(FinanceLog::new("pills", 10.0, march));
+
+        let summary = FinanceStats::new(finance).tax_summary(march.year());
+        let totals = summary
+            .buckets
+            .iter()
+            .map(|(bucket, summary)| (bucket.as_str(), summary.total, summary.items.len()))
+            .collect::<Vec<(&str, f64, usize)>>();
+
+        assert_eq!(
+            totals,
+            vec![("Home office", 200.0, 1), ("Medical", 90.0, 2)]
+        );
+    }
+
+    #[test]
+    fn anomalies_use_category_thresholds() {
+        let january = YearMonth::new(2024, Month::January);
+        let finance = Finance::new()
+            .with_product(&Product::new("groceries", "Food"))
+            .with_product(&Product::new("fuel", "Car"))
+            .with_anomaly_threshold("Car", 3.0);
+        let finance = [100.0, 110.0, 90.0, 200.0].into_iter().enumerate().fold(
+            finance,
+            |finance, (month, price)| {
+                let year_month = january.add_months(month as i32);
+                finance
+                    .with_log(FinanceLog::new("groceries", price, year_month))
+                    .with_log(FinanceLog::new("fuel", price, year_month))
+            },
+        );
+
+        let anomalies = FinanceStats::new(finance).anomalies();
+        let subjects = anomalies
+            .iter()
+            .map(|anomaly| anomaly.subject.clone())
+            .collect::<Vec<AnomalySubject>>();
+
+        assert_eq!(
+            subjects,
+            vec![
+                AnomalySubject::Category("Food".to_owned()),
+                AnomalySubject::Log {
+                    index: 6,
+                    product: "groceries".to_owned()
+                }
+            ]
+        );
+        assert!(anomalies
+            .iter()
+            .all(|anomaly| anomaly.year_month == january.add_months(3)));
+    }
+
+    #[test]
+    fn totals_by_period_and_in_range() {
+        let january = YearMonth::new(2024, Month::January);
+        let finance = Finance::new()
+            .with_product(&Product::new("rent", "Home"))
+            .with_product(&Product::new("power", "Home"));
+        let finance = (0..6).fold(finance, |finance, month| {
+            finance
+                .with_log(FinanceLog::new("rent", 500.0, january.add_months(month)))
+                .with_log(FinanceLog::new("power", 50.0, january.add_months(month)))
+        });
+        let stats = FinanceStats::new(finance);
+
+        let quarters = stats.category_totals_by_period(PeriodGranularity::Quarter);
+        assert_eq!(quarters.len(), 2);
+        assert_eq!(
+            quarters[&PeriodGranularity::Quarter.period_of(january)].labeled["Home"],
+            1650.0
+        );
+
+        let fiscal_years = stats.product_totals_by_period(PeriodGranularity::FiscalYear {
+            start_month: Month::April,
+        });
+        let totals = fiscal_years
+            .iter()
+            .map(|(period, totals)| (period.to_string(), totals.labeled["rent"]))
+            .sorted_by(|left, right| left.0.cmp(&right.0))
+            .collect::<Vec<(String, f64)>>();
+        assert_eq!(
+            totals,
+            vec![
+                ("FY 2023/24".to_owned(), 1500.0),
+                ("FY 2024/25".to_owned(), 1500.0)
+            ]
+        );
+
+        let range = Period::Range {
+            from: january.next(),
+            to: january.add_months(2),
+        };
+        assert_eq!(stats.product_totals_in(&range).labeled["power"], 100.0);
+    }
 }
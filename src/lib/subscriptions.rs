@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use gregorian::YearMonth;
+use itertools::Itertools;
+
+use crate::finance::{FinanceLog, Price};
+use crate::recurring::{RecurrenceInterval, RecurringLog};
+use crate::stats::months_between;
+
+/// How many charges it takes before a series of them counts as regular.
+pub const MIN_SUBSCRIPTION_CHARGES: usize = 3;
+
+/// How far, as a fraction of the typical charge, a charge may be from it and
+/// still belong to the same subscription. It leaves room for price changes.
+pub const SUBSCRIPTION_AMOUNT_TOLERANCE: f64 = 0.2;
+
+/// Charges from the same payee, or of the same product when they have no
+/// payee, that come at a regular interval with similar amounts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Subscription {
+    /// The payee, or the product of logs without one.
+    pub name: String,
+    /// Product of the latest charge.
+    pub product: String,
+    /// Account of the latest charge.
+    pub account: Option<String>,
+    pub interval_months: i32,
+    /// Amount of the latest charge.
+    pub amount: Price,
+    pub charges: usize,
+    pub last_charge: YearMonth,
+}
+
+impl Subscription {
+    pub fn annual_cost(&self) -> Price {
+        self.amount * 12.0 / f64::from(self.interval_months)
+    }
+
+    pub fn next_charge(&self) -> YearMonth {
+        self.last_charge.add_months(self.interval_months)
+    }
+
+    /// The recurring log that tracks the subscription from its next charge
+    /// on, if its interval is one recurring logs support.
+    pub fn to_recurring(&self) -> Option<RecurringLog> {
+        let interval = match self.interval_months {
+            1 => RecurrenceInterval::Monthly,
+            12 => RecurrenceInterval::Yearly,
+            _ => return None,
+        };
+
+        let recurring = RecurringLog::new(
+            &self.product,
+            self.amount,
+            interval,
+            self.next_charge().first_day(),
+        );
+
+        Some(match &self.account {
+            Some(account) => recurring.with_account(account),
+            None => recurring,
+        })
+    }
+}
+
+/// The subscriptions found in `logs`, most expensive per year first.
+///
+/// Charges in the same month are added together. A series is regular if it
+/// has at least `MIN_SUBSCRIPTION_CHARGES` months with charges, the same
+/// number of months, up to a year, between each of them, and every charge
+/// within `SUBSCRIPTION_AMOUNT_TOLERANCE` of their median.
+pub fn detect_subscriptions(logs: &[FinanceLog]) -> Vec<Subscription> {
+    let mut series = BTreeMap::<String, Vec<&FinanceLog>>::new();
+    for log in logs.iter().filter(|log| log.price > 0.0) {
+        let name = log.payee.clone().unwrap_or(log.product.clone());
+        series.entry(name).or_default().push(log);
+    }
+
+    series
+        .into_iter()
+        .filter_map(|(name, logs)| detect_subscription(name, &logs))
+        .sorted_by(|left, right| right.annual_cost().total_cmp(&left.annual_cost()))
+        .collect()
+}
+
+fn detect_subscription(name: String, logs: &[&FinanceLog]) -> Option<Subscription> {
+    let mut charges = BTreeMap::<YearMonth, Price>::new();
+    for log in logs {
+        *charges.entry(log.year_month).or_insert(0.0) += log.price;
+    }
+
+    if charges.len() < MIN_SUBSCRIPTION_CHARGES {
+        return None;
+    }
+
+    let intervals = charges
+        .keys()
+        .tuple_windows()
+        .map(|(from, to)| months_between(*from, *to))
+        .collect::<Vec<i32>>();
+    let interval_months = intervals[0];
+    if interval_months > 12
+        || intervals
+            .iter()
+            .any(|interval| *interval != interval_months)
+    {
+        return None;
+    }
+
+    let amounts = charges
+        .values()
+        .copied()
+        .sorted_by(|left, right| left.total_cmp(right))
+        .collect::<Vec<Price>>();
+    let median = amounts[amounts.len() / 2];
+    if amounts
+        .iter()
+        .any(|amount| (amount - median).abs() > median * SUBSCRIPTION_AMOUNT_TOLERANCE)
+    {
+        return None;
+    }
+
+    let (last_charge, amount) = charges.last_key_value()?;
+    let latest = logs.iter().max_by_key(|log| log.year_month)?;
+
+    Some(Subscription {
+        name,
+        product: latest.product.clone(),
+        account: latest.account.clone(),
+        interval_months,
+        amount: *amount,
+        charges: charges.len(),
+        last_charge: *last_charge,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+
+    use super::detect_subscriptions;
+    use crate::finance::FinanceLog;
+    use crate::recurring::RecurrenceInterval;
+
+    #[test]
+    fn test_detect_subscriptions() {
+        let january = YearMonth::new(2024, Month::January);
+        let monthly = |months: i32, price: f64| {
+            FinanceLog::new("streaming", price, january.add_months(months)).with_payee("Flix")
+        };
+
+        let logs = vec![
+            monthly(0, 10.0),
+            FinanceLog::new("groceries", 60.0, january),
+            monthly(1, 10.0),
+            FinanceLog::new("groceries", 200.0, january.next()),
+            monthly(2, 11.0),
+            FinanceLog::new("groceries", 45.0, january.add_months(2)),
+            FinanceLog::new("domain", 15.0, january),
+            FinanceLog::new("domain", 15.0, january.add_months(12)),
+            FinanceLog::new("domain", 16.0, january.add_months(24)),
+            FinanceLog::new("gift", 30.0, january),
+            FinanceLog::new("gift", 30.0, january.add_months(2)),
+            FinanceLog::new("gift", 30.0, january.add_months(7)),
+        ];
+
+        let subscriptions = detect_subscriptions(&logs);
+        let names = subscriptions
+            .iter()
+            .map(|subscription| subscription.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["Flix", "domain"]);
+
+        let flix = &subscriptions[0];
+        assert_eq!(flix.product, "streaming");
+        assert_eq!(flix.interval_months, 1);
+        assert_eq!(flix.annual_cost(), 132.0);
+        assert_eq!(flix.next_charge(), YearMonth::new(2024, Month::April));
+
+        let domain = &subscriptions[1];
+        assert_eq!(domain.annual_cost(), 16.0);
+        assert_eq!(domain.next_charge(), YearMonth::new(2027, Month::January));
+        assert_eq!(
+            domain.to_recurring().map(|recurring| recurring.interval),
+            Some(RecurrenceInterval::Yearly)
+        );
+    }
+}
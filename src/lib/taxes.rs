@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use gregorian::Year;
+
+use crate::export::csv_line;
+use crate::finance::{FinanceLog, Price};
+
+/// What counts towards each tax bucket in a year, like deductible medical
+/// expenses or donations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaxSummary {
+    pub year: Year,
+    pub buckets: BTreeMap<String, TaxBucketSummary>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TaxBucketSummary {
+    pub total: Price,
+    pub items: Vec<TaxItem>,
+}
+
+/// A line of a log that counts towards a bucket: the whole log, or one of
+/// its splits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaxItem {
+    pub log: FinanceLog,
+    pub product: String,
+    pub amount: Price,
+}
+
+impl TaxSummary {
+    pub fn new(year: Year) -> Self {
+        Self {
+            year,
+            buckets: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_item(mut self, bucket: &str, item: TaxItem) -> Self {
+        let summary = self.buckets.entry(bucket.to_owned()).or_default();
+        summary.total += item.amount;
+        summary.items.push(item);
+
+        self
+    }
+
+    /// Every item as CSV, bucket by bucket, with a header row.
+    pub fn to_csv(&self) -> String {
+        let rows = self.buckets.iter().flat_map(|(bucket, summary)| {
+            summary.items.iter().map(move |item| {
+                csv_line(&[
+                    bucket.to_owned(),
+                    item.log.year_month.to_string(),
+                    item.product.to_owned(),
+                    item.log.payee.to_owned().unwrap_or_default(),
+                    format!("{:.2}", item.amount),
+                    item.log.note.to_owned().unwrap_or_default(),
+                ])
+            })
+        });
+
+        Some(csv_line(&[
+            "Bucket", "Month", "Product", "Payee", "Amount", "Note",
+        ]))
+        .into_iter()
+        .chain(rows)
+        .map(|row| row + "\n")
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, Year, YearMonth};
+
+    use super::{TaxItem, TaxSummary};
+    use crate::finance::FinanceLog;
+
+    #[test]
+    fn test_to_csv() {
+        let year_month = YearMonth::new(2024, Month::March);
+        let item = |product: &str, amount: f64| TaxItem {
+            log: FinanceLog::new(product, amount, year_month).with_payee("Dr. Who, MD"),
+            product: product.to_owned(),
+            amount,
+        };
+
+        let summary = TaxSummary::new(Year::new(2024))
+            .with_item("Medical", item("dentist", 80.0))
+            .with_item("Donations", item("charity", 20.0))
+            .with_item("Medical", item("glasses", 120.0));
+
+        assert_eq!(summary.buckets["Medical"].total, 200.0);
+        assert_eq!(
+            summary.to_csv(),
+            "Bucket,Month,Product,Payee,Amount,Note\n\
+             Donations,2024-03,charity,\"Dr. Who, MD\",20.00,\n\
+             Medical,2024-03,dentist,\"Dr. Who, MD\",80.00,\n\
+             Medical,2024-03,glasses,\"Dr. Who, MD\",120.00,\n"
+        );
+    }
+}
@@ -1,48 +1,224 @@
+use std::env;
+
+use autosave::{AutosavePolicy, AutosaveTrigger};
 use cursive::{
+    event::Event,
     views::{Dialog, TextView},
     Cursive,
 };
-use neo_budget::repository::{
-    EnvJSONFinanceRepository, FinanceRepository, FinanceRepositoryLoadError,
-};
+use gregorian::Month;
+use neo_budget::categories::DEFAULT_CATEGORY_SEPARATOR;
+use neo_budget::repository::{EnvFinanceRepository, FinanceRepository, FinanceRepositoryLoadError};
+use views::budget_line::update_budget_line;
 use views::main_menu::main_menu_view;
+use views::passphrase::passphrase_view;
 
-use crate::siv::FinanceApp;
+use crate::siv::{
+    autosave_finance_app, redo_finance_app, undo_finance_app, watch_finance_file, FinanceApp,
+};
 
+mod autosave;
+mod cli;
 mod siv;
 mod views;
 
 fn main() -> Result<(), String> {
-    let finance_repo = EnvJSONFinanceRepository::from_env()?;
-    let finance = finance_repo.load();
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    if let Some(command_result) = cli::run_command(&args) {
+        return command_result;
+    }
+
+    let autosave = AutosavePolicy::from_env()?;
+    let fiscal_year_start = fiscal_year_start_from_env()?;
 
     let mut siv = cursive::default();
     set_back_button(&mut siv);
+    set_quit_key(&mut siv);
+    set_autosave_timer(&mut siv, autosave);
+    set_file_watcher(&mut siv, watch_from_env());
+
+    if EnvFinanceRepository::requires_passphrase_from_env() {
+        siv.add_layer(passphrase_view(move |siv, finance_repo| {
+            start_finance_app(siv, finance_repo, autosave, fiscal_year_start)
+        }));
+    } else {
+        start_finance_app(
+            &mut siv,
+            EnvFinanceRepository::from_env()?,
+            autosave,
+            fiscal_year_start,
+        );
+    }
 
-    match finance {
+    siv.run();
+
+    Ok(())
+}
+
+fn start_finance_app(
+    siv: &mut Cursive,
+    finance_repo: EnvFinanceRepository,
+    autosave: AutosavePolicy,
+    fiscal_year_start: Month,
+) {
+    match finance_repo.load() {
         Ok(finance) => {
-            let finance_app = FinanceApp::new(finance_repo, finance);
+            let finance_app =
+                FinanceApp::new(finance_repo, finance.clone(), autosave, fiscal_year_start);
             siv.set_user_data(finance_app);
+            set_history_keys(siv);
 
             siv.add_layer(main_menu_view());
+            update_budget_line(siv, &finance);
         }
 
         Err(error) => siv.add_layer(finance_load_error_view(error)),
     }
-
-    siv.run();
-
-    Ok(())
 }
 
 fn set_back_button(siv: &mut Cursive) {
     siv.set_global_callback('q', |siv| {
         if siv.pop_layer().is_none() {
-            siv.quit();
+            quit(siv);
         }
     });
 }
 
+/// Makes Ctrl-C quit like `q` does, autosaving first. Cursive's own binding
+/// quits right away.
+fn set_quit_key(siv: &mut Cursive) {
+    siv.clear_global_callbacks(Event::CtrlChar('c'));
+    siv.set_on_pre_event(Event::CtrlChar('c'), quit);
+}
+
+fn set_history_keys(siv: &mut Cursive) {
+    siv.set_global_callback('u', undo_finance_app);
+    siv.set_global_callback('r', redo_finance_app);
+}
+
+fn set_autosave_timer(siv: &mut Cursive, autosave: AutosavePolicy) {
+    if let AutosavePolicy::Interval(_) = autosave {
+        siv.set_fps(1);
+        siv.add_global_callback(Event::Refresh, |siv| {
+            // Failures are already reported in the status line.
+            let _ = autosave_finance_app(siv, AutosaveTrigger::Tick);
+        });
+    }
+}
+
+pub fn category_separator_from_env() -> String {
+    env::var("FINANCE_CATEGORY_SEPARATOR")
+        .ok()
+        .filter(|separator| !separator.is_empty())
+        .unwrap_or(DEFAULT_CATEGORY_SEPARATOR.to_owned())
+}
+
+/// The month fiscal years start on, from 1 to 12. January if unset.
+fn fiscal_year_start_from_env() -> Result<Month, String> {
+    match env::var("FINANCE_FISCAL_YEAR_START") {
+        Ok(value) if !value.trim().is_empty() => value
+            .trim()
+            .parse::<u8>()
+            .ok()
+            .and_then(|month| Month::new(month).ok())
+            .ok_or(format!(
+                "Invalid FINANCE_FISCAL_YEAR_START value \"{value}\"! Expected a month from 1 to 12."
+            )),
+        _ => Ok(Month::January),
+    }
+}
+
+fn watch_from_env() -> bool {
+    env::var("FINANCE_WATCH").is_ok_and(|watch| watch == "true")
+}
+
+fn set_file_watcher(siv: &mut Cursive, watch: bool) {
+    if watch {
+        siv.set_fps(1);
+        siv.add_global_callback(Event::Refresh, watch_finance_file);
+    }
+}
+
+fn quit(siv: &mut Cursive) {
+    match autosave_finance_app(siv, AutosaveTrigger::Quit) {
+        Ok(_) => siv.quit(),
+
+        Err(error) => siv.add_layer(
+            Dialog::around(TextView::new(format!("Autosave failed: {error}")))
+                .button("Quit anyway", |siv| siv.quit())
+                .button("Back", |siv| {
+                    siv.pop_layer();
+                }),
+        ),
+    }
+}
+
 fn finance_load_error_view(error: FinanceRepositoryLoadError) -> Dialog {
     Dialog::around(TextView::new(String::from(error)))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use cursive::{event::Event, Cursive};
+    use gregorian::Month;
+    use neo_budget::finance::{Finance, Product};
+    use neo_budget::repository::{
+        EnvFinanceRepository, EnvJSONFinanceRepository, FinanceRepository,
+    };
+    use tempfile::TempDir;
+
+    use super::{set_quit_key, start_finance_app};
+    use crate::autosave::AutosavePolicy;
+    use crate::siv::{get_finance_app, set_finance_app};
+
+    #[test]
+    fn test_ctrl_c_autosaves() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("finance.json");
+        let finance_repo = EnvJSONFinanceRepository::new(path.to_str().unwrap());
+        finance_repo.save(&Finance::new()).unwrap();
+
+        let mut siv = Cursive::new();
+        set_quit_key(&mut siv);
+        start_finance_app(
+            &mut siv,
+            EnvFinanceRepository::JSON(finance_repo.clone()),
+            AutosavePolicy::OnQuit,
+            Month::January,
+        );
+
+        let app = get_finance_app(&mut siv);
+        let finance = app.finance().with_product(&Product::new("prod1", "cat1"));
+        set_finance_app(&mut siv, app.with_edit("Added prod1", finance.clone()));
+
+        siv.on_event(Event::CtrlChar('c'));
+
+        assert!(!siv.is_running());
+        assert_eq!(finance_repo.load(), Ok(finance));
+    }
+
+    #[test]
+    fn test_create_encrypted_then_start() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("finance.json.enc");
+        env::set_var("FINANCE_FILE_PATH", path.to_str().unwrap());
+        env::set_var("FINANCE_FILE_FORMAT", "encrypted");
+
+        assert!(EnvFinanceRepository::requires_new_passphrase_from_env());
+        let finance_repo = EnvFinanceRepository::create_encrypted_from_env("secret", "secret")
+            .expect("Didn't expect create to fail!");
+
+        let mut siv = Cursive::new();
+        start_finance_app(
+            &mut siv,
+            finance_repo,
+            AutosavePolicy::Disabled,
+            Month::January,
+        );
+
+        assert_eq!(get_finance_app(&mut siv).finance(), Finance::new());
+        assert!(!EnvFinanceRepository::requires_new_passphrase_from_env());
+    }
+}
@@ -1,35 +1,245 @@
+use std::time::Instant;
+
 use cursive::Cursive;
+use gregorian::Month;
 use neo_budget::finance::Finance;
-use neo_budget::repository::{EnvJSONFinanceRepository, FinanceRepository};
+use neo_budget::history::History;
+use neo_budget::merge::{merge, MergeConflict, MergeSide};
+use neo_budget::repository::{EnvFinanceRepository, FileFingerprint, FinanceRepository};
+
+use crate::autosave::{AutosavePolicy, AutosaveTrigger};
+use crate::views::budget_line::update_budget_line;
+use crate::views::external_changes::show_external_changes_dialog;
+use crate::views::merge_conflicts::merge_conflicts_view;
+use crate::views::status_line::set_status;
 
 #[derive(Clone)]
 pub struct FinanceApp<T: FinanceRepository> {
     finance: Finance,
     finance_repo: T,
+    history: History<Finance>,
+    autosave: AutosavePolicy,
+    fiscal_year_start: Month,
+    unsaved_changes: bool,
+    last_save: Instant,
+    /// The finance as last loaded from or saved to the data file.
+    stored_finance: Finance,
+    stored_fingerprint: Option<FileFingerprint>,
+    ignored_fingerprint: Option<FileFingerprint>,
+}
+
+pub enum SaveError {
+    ExternallyModified,
+    Failed(String),
 }
 
 impl<T: FinanceRepository> FinanceApp<T> {
-    pub fn new(finance_repo: T, finance: Finance) -> Self {
+    pub fn new(
+        finance_repo: T,
+        finance: Finance,
+        autosave: AutosavePolicy,
+        fiscal_year_start: Month,
+    ) -> Self {
         Self {
+            stored_fingerprint: finance_repo.fingerprint(),
+            ignored_fingerprint: None,
+            stored_finance: finance.clone(),
             finance_repo,
             finance,
+            history: History::new(),
+            autosave,
+            fiscal_year_start,
+            unsaved_changes: false,
+            last_save: Instant::now(),
         }
     }
 
     pub fn with_finance(self, finance: Finance) -> Self {
-        Self { finance, ..self }
+        Self {
+            finance,
+            unsaved_changes: true,
+            ..self
+        }
+    }
+
+    /// Replaces the finance with `finance`, recording the change as an
+    /// undoable step labeled `description`.
+    pub fn with_edit(self, description: &str, finance: Finance) -> Self {
+        let history =
+            self.history
+                .clone()
+                .with_step(description, self.finance.clone(), finance.clone());
+
+        Self {
+            history,
+            ..self.with_finance(finance)
+        }
+    }
+
+    /// Reverts the last edit, returning the updated app and the description
+    /// of the undone edit, or `None` if there is nothing to undo.
+    pub fn undo(self) -> Option<(Self, String)> {
+        let (history, step) = self.history.clone().undo()?;
+
+        Some((
+            Self {
+                history,
+                ..self.with_finance(step.before)
+            },
+            step.description,
+        ))
+    }
+
+    /// Reapplies the last undone edit, returning the updated app and the
+    /// description of the redone edit, or `None` if there is nothing to redo.
+    pub fn redo(self) -> Option<(Self, String)> {
+        let (history, step) = self.history.clone().redo()?;
+
+        Some((
+            Self {
+                history,
+                ..self.with_finance(step.after)
+            },
+            step.description,
+        ))
     }
 
     pub fn finance(&self) -> Finance {
         self.finance.clone()
     }
 
+    /// The month fiscal years start on, from `FINANCE_FISCAL_YEAR_START`.
+    pub fn fiscal_year_start(&self) -> Month {
+        self.fiscal_year_start
+    }
+
     pub fn finance_repo(&self) -> T {
         self.finance_repo.clone()
     }
+
+    pub fn history(&self) -> History<Finance> {
+        self.history.clone()
+    }
+
+    /// Saves the finance, unless the data file changed since it was loaded
+    /// or last saved.
+    pub fn save(&self) -> Result<Self, SaveError> {
+        if self.is_stored_file_modified() {
+            return Err(SaveError::ExternallyModified);
+        }
+
+        self.overwrite().map_err(SaveError::Failed)
+    }
+
+    /// Saves the finance, replacing any changes made to the data file since it
+    /// was loaded.
+    pub fn overwrite(&self) -> Result<Self, String> {
+        self.finance_repo.save(&self.finance)?;
+
+        Ok(Self {
+            unsaved_changes: false,
+            last_save: Instant::now(),
+            stored_finance: self.finance.clone(),
+            stored_fingerprint: self.finance_repo.fingerprint(),
+            ..self.clone()
+        })
+    }
+
+    /// Replaces the finance with the contents of the data file, as an
+    /// undoable edit.
+    pub fn reload(self) -> Result<Self, String> {
+        let stored_finance = self.finance_repo.load()?;
+
+        Ok(Self {
+            unsaved_changes: false,
+            stored_fingerprint: self.finance_repo.fingerprint(),
+            stored_finance: stored_finance.clone(),
+            ..self.with_edit("Reloaded the data file", stored_finance)
+        })
+    }
+
+    /// Three-way merges the changes made since the data file was loaded with
+    /// the changes made to the file itself, as an undoable edit. `resolve`
+    /// picks the winner of every conflict. The result still has to be saved.
+    pub fn merge_stored_changes(
+        self,
+        resolve: impl Fn(&MergeConflict) -> MergeSide,
+    ) -> Result<(Self, Vec<MergeConflict>), String> {
+        let stored_finance = self.finance_repo.load()?;
+        let result = merge(
+            &self.stored_finance,
+            &self.finance,
+            &stored_finance,
+            resolve,
+        );
+
+        Ok((
+            Self {
+                stored_fingerprint: self.finance_repo.fingerprint(),
+                stored_finance,
+                ..self.with_edit("Merged changes from the data file", result.finance)
+            },
+            result.conflicts,
+        ))
+    }
+
+    /// Runs `rewrite`, which changes the data file without changing the
+    /// finance it stores, like compacting a journal. Refuses while the file
+    /// has changes that weren't loaded, so they are still reported.
+    pub fn rewriting_stored_file(
+        self,
+        rewrite: impl FnOnce() -> Result<(), String>,
+    ) -> Result<Self, String> {
+        if self.is_stored_file_modified() {
+            return Err("The data file changed since it was loaded!".to_owned());
+        }
+
+        rewrite()?;
+
+        Ok(Self {
+            stored_fingerprint: self.finance_repo.fingerprint(),
+            ..self
+        })
+    }
+
+    /// Stops reporting the current external changes to the data file. Saving
+    /// still refuses to overwrite them.
+    pub fn ignoring_stored_changes(self) -> Self {
+        Self {
+            ignored_fingerprint: self.finance_repo.fingerprint(),
+            ..self
+        }
+    }
+
+    pub fn has_unreported_stored_changes(&self) -> bool {
+        self.is_stored_file_modified() && !self.are_stored_changes_ignored()
+    }
+
+    pub fn has_ignored_stored_changes(&self) -> bool {
+        self.is_stored_file_modified() && self.are_stored_changes_ignored()
+    }
+
+    fn are_stored_changes_ignored(&self) -> bool {
+        match (self.ignored_fingerprint, self.finance_repo.fingerprint()) {
+            (Some(ignored), Some(current)) => ignored.matches(&current),
+            _ => false,
+        }
+    }
+
+    fn is_stored_file_modified(&self) -> bool {
+        match (self.stored_fingerprint, self.finance_repo.fingerprint()) {
+            (Some(stored), Some(current)) => !stored.matches(&current),
+            (None, Some(_)) => true,
+            (_, None) => false,
+        }
+    }
+
+    fn should_autosave(&self, trigger: AutosaveTrigger) -> bool {
+        self.unsaved_changes && self.autosave.should_save(trigger, self.last_save.elapsed())
+    }
 }
 
-type ChosenFinanceRepository = EnvJSONFinanceRepository;
+type ChosenFinanceRepository = EnvFinanceRepository;
 
 pub fn update_finance_app(
     siv: &mut cursive::Cursive,
@@ -52,5 +262,168 @@ pub fn get_finance_app(siv: &mut cursive::Cursive) -> FinanceApp<ChosenFinanceRe
 }
 
 pub fn set_finance_app(siv: &mut cursive::Cursive, app: FinanceApp<ChosenFinanceRepository>) {
+    update_budget_line(siv, &app.finance());
     siv.set_user_data(app);
+
+    // Failures are already reported in the status line.
+    let _ = autosave_finance_app(siv, AutosaveTrigger::Change);
+}
+
+/// Saves the current finance through its repository, reporting the outcome in
+/// the status line.
+pub fn save_finance_app(siv: &mut cursive::Cursive) -> Result<(), String> {
+    let saved_app = get_finance_app(siv).save();
+
+    match saved_app {
+        Ok(saved_app) => {
+            siv.set_user_data(saved_app);
+            set_status(siv, "All changes saved.");
+
+            Ok(())
+        }
+
+        Err(SaveError::ExternallyModified) => {
+            let error = "The data file changed on disk.".to_owned();
+            set_status(siv, &format!("Save failed: {error}"));
+            show_external_changes_dialog(siv);
+
+            Err(error)
+        }
+
+        Err(SaveError::Failed(error)) => {
+            set_status(siv, &format!("Save failed: {error}"));
+
+            Err(error)
+        }
+    }
+}
+
+pub fn overwrite_finance_app(siv: &mut cursive::Cursive) {
+    match get_finance_app(siv).overwrite() {
+        Ok(app) => {
+            siv.set_user_data(app);
+            set_status(siv, "Data file overwritten.");
+        }
+
+        Err(error) => set_status(siv, &format!("Save failed: {error}")),
+    }
+}
+
+pub fn reload_finance_app(siv: &mut cursive::Cursive) {
+    match get_finance_app(siv).reload() {
+        Ok(app) => {
+            update_budget_line(siv, &app.finance());
+            siv.set_user_data(app);
+            set_status(siv, "Data file reloaded.");
+        }
+
+        Err(error) => set_status(siv, &format!("Reload failed: {error}")),
+    }
+}
+
+/// Merges the data file's changes, asking which side wins any conflicts.
+pub fn merge_finance_app(siv: &mut cursive::Cursive) {
+    match get_finance_app(siv).merge_stored_changes(|_| MergeSide::Ours) {
+        Ok((_, conflicts)) if !conflicts.is_empty() => {
+            siv.add_layer(merge_conflicts_view(conflicts));
+        }
+
+        Ok((app, _)) => {
+            update_budget_line(siv, &app.finance());
+            siv.set_user_data(app);
+            let _ = save_finance_app(siv);
+        }
+
+        Err(error) => set_status(siv, &format!("Merge failed: {error}")),
+    }
+}
+
+/// Merges the data file's changes, resolving each conflict with the side
+/// chosen for it.
+pub fn resolve_merge_finance_app(
+    siv: &mut cursive::Cursive,
+    resolutions: Vec<(MergeConflict, MergeSide)>,
+) {
+    let merged_app = get_finance_app(siv).merge_stored_changes(|conflict| {
+        resolutions
+            .iter()
+            .find(|(resolved_conflict, _)| resolved_conflict == conflict)
+            .map_or(MergeSide::Ours, |(_, side)| *side)
+    });
+
+    match merged_app {
+        Ok((app, _)) => {
+            update_budget_line(siv, &app.finance());
+            siv.set_user_data(app);
+            let _ = save_finance_app(siv);
+        }
+
+        Err(error) => set_status(siv, &format!("Merge failed: {error}")),
+    }
+}
+
+pub fn ignore_stored_changes(siv: &mut cursive::Cursive) {
+    let app = get_finance_app(siv).ignoring_stored_changes();
+    siv.set_user_data(app);
+    set_status(siv, "The data file changed on disk. Saving will ask again.");
+}
+
+/// Offers to reload, overwrite or merge if the data file changed on disk and
+/// the change wasn't reported yet. Each change is only reported once.
+pub fn watch_finance_file(siv: &mut cursive::Cursive) {
+    let has_unreported_changes = siv
+        .user_data::<FinanceApp<ChosenFinanceRepository>>()
+        .is_some_and(|app| app.has_unreported_stored_changes());
+
+    if has_unreported_changes {
+        let app = get_finance_app(siv).ignoring_stored_changes();
+        siv.set_user_data(app);
+        show_external_changes_dialog(siv);
+    }
+}
+
+/// Saves the current finance if the autosave policy asks for it on `trigger`.
+/// Returns the error of a failed save, if one was attempted.
+pub fn autosave_finance_app(
+    siv: &mut cursive::Cursive,
+    trigger: AutosaveTrigger,
+) -> Result<(), String> {
+    let app = siv.user_data::<FinanceApp<ChosenFinanceRepository>>();
+    let should_save = app.as_ref().is_some_and(|app| app.should_autosave(trigger));
+    let is_paused = app.is_some_and(|app| app.has_ignored_stored_changes());
+
+    match (should_save, is_paused) {
+        (true, true) => {
+            let error = "The data file changed on disk.".to_owned();
+            set_status(siv, &format!("Autosave paused: {error}"));
+
+            Err(error)
+        }
+
+        (true, false) => save_finance_app(siv),
+
+        (false, _) => Ok(()),
+    }
+}
+
+pub fn undo_finance_app(siv: &mut cursive::Cursive) {
+    match get_finance_app(siv).undo() {
+        Some((app, description)) => {
+            set_finance_app(siv, app);
+            set_status(siv, &format!("Undone: {description}"));
+        }
+
+        None => set_status(siv, "Nothing to undo."),
+    }
+}
+
+pub fn redo_finance_app(siv: &mut cursive::Cursive) {
+    match get_finance_app(siv).redo() {
+        Some((app, description)) => {
+            set_finance_app(siv, app);
+            set_status(siv, &format!("Redone: {description}"));
+        }
+
+        None => set_status(siv, "Nothing to redo."),
+    }
 }
@@ -0,0 +1,162 @@
+use cursive::{
+    view::{Nameable, Scrollable},
+    views::{Dialog, EditView, LinearLayout, Panel, SelectView, TextView},
+    Cursive, With,
+};
+use itertools::Itertools;
+use neo_budget::finance::{Account, AccountKind, Finance};
+use neo_budget::stats::FinanceStats;
+
+use super::main_menu::year_month_totals_display;
+use super::show_logs::show_grouped_totals_view;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const ACCOUNTS_TEXT_NAME: &str = "accounts_text";
+const ACCOUNT_ID_INPUT_NAME: &str = "account_id";
+const ACCOUNT_KIND_INPUT_NAME: &str = "account_kind";
+const OPENING_BALANCE_INPUT_NAME: &str = "opening_balance";
+const ACCOUNT_INFO_TEXT_NAME: &str = "account_info_text";
+
+pub fn accounts_view(finance: &Finance) -> Dialog {
+    TextView::new(accounts_text(finance))
+        .with_name(ACCOUNTS_TEXT_NAME)
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Accounts"))
+        .wrap_with(Dialog::around)
+        .button("Monthly balances", |siv| {
+            let stats = FinanceStats::new(get_finance_app(siv).finance());
+            siv.add_layer(show_grouped_totals_view(year_month_totals_display(
+                stats.account_balances_by_year_month(),
+            )));
+        })
+        .button("Add account", |siv| {
+            siv.add_layer(add_account_view());
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn add_account_view() -> Dialog {
+    LinearLayout::vertical()
+        .child(TextView::new("Account name"))
+        .child(EditView::new().with_name(ACCOUNT_ID_INPUT_NAME))
+        .child(TextView::new("Kind"))
+        .child(
+            SelectView::<AccountKind>::new()
+                .popup()
+                .item("Checking", AccountKind::Checking)
+                .item("Credit card", AccountKind::CreditCard)
+                .item("Cash", AccountKind::Cash)
+                .item("Savings", AccountKind::Savings)
+                .with_name(ACCOUNT_KIND_INPUT_NAME),
+        )
+        .child(TextView::new("Opening balance"))
+        .child(EditView::new().with_name(OPENING_BALANCE_INPUT_NAME))
+        .child(TextView::empty().with_name(ACCOUNT_INFO_TEXT_NAME))
+        .wrap_with(Dialog::around)
+        .button("Ok", submit_account)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn submit_account(siv: &mut Cursive) {
+    let id = siv
+        .find_name::<EditView>(ACCOUNT_ID_INPUT_NAME)
+        .expect("Couldn't find account_id")
+        .get_content();
+
+    let kind = siv
+        .find_name::<SelectView<AccountKind>>(ACCOUNT_KIND_INPUT_NAME)
+        .expect("Couldn't find account_kind")
+        .selection()
+        .map_or(AccountKind::Checking, |kind| *kind);
+
+    let opening_balance = siv
+        .find_name::<EditView>(OPENING_BALANCE_INPUT_NAME)
+        .expect("Couldn't find opening_balance")
+        .get_content()
+        .trim()
+        .parse::<f64>();
+
+    let info = match (id.trim(), opening_balance) {
+        ("", _) => "Account name can't be empty!".to_owned(),
+
+        (id, Ok(opening_balance)) => {
+            let app = get_finance_app(siv);
+            let finance = app.finance();
+            let description = match finance.accounts().contains_key(id) {
+                true => format!("Updated account {id}"),
+                false => format!("Added account {id}"),
+            };
+
+            let finance = finance.with_account(&Account::new(id, kind, opening_balance));
+            set_finance_app(siv, app.with_edit(&description, finance));
+            reload_accounts_text(siv);
+
+            format!("{description}.")
+        }
+
+        (_, Err(_)) => "Invalid opening balance!".to_owned(),
+    };
+
+    siv.call_on_name(ACCOUNT_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+fn reload_accounts_text(siv: &mut Cursive) {
+    let finance = get_finance_app(siv).finance();
+
+    siv.call_on_name(ACCOUNTS_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(accounts_text(&finance))
+    });
+}
+
+fn accounts_text(finance: &Finance) -> String {
+    let balances = FinanceStats::new(finance.clone()).account_balances();
+    let lines = finance
+        .accounts()
+        .into_values()
+        .sorted_by(|a, b| a.id().cmp(b.id()))
+        .map(|account| {
+            format!(
+                "{} ({:?}): {:.2}",
+                account.id(),
+                account.kind,
+                balances.get(account.id()).copied().unwrap_or_default()
+            )
+        })
+        .collect::<Vec<String>>();
+
+    match lines.is_empty() {
+        true => "No accounts yet.".to_owned(),
+        false => lines.join("\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::finance::{Account, AccountKind, Finance, FinanceLog};
+
+    use super::accounts_text;
+
+    #[test]
+    fn test_accounts_text() {
+        let finance = Finance::new()
+            .with_account(&Account::new("wallet", AccountKind::Cash, 50.0))
+            .with_account(&Account::new("bank", AccountKind::Checking, 100.0))
+            .with_log(
+                FinanceLog::new("prod1", 20.0, YearMonth::new(2024, Month::May))
+                    .with_account("bank"),
+            );
+
+        assert_eq!(
+            accounts_text(&finance),
+            "bank (Checking): 80.00\nwallet (Cash): 50.00"
+        );
+        assert_eq!(accounts_text(&Finance::new()), "No accounts yet.");
+    }
+}
@@ -1,13 +1,15 @@
+use std::collections::BTreeSet;
+
 use cursive::{
     view::{Nameable, Resizable},
     views::{Canvas, Dialog, EditView, LinearLayout, TextView},
+    Cursive,
 };
 use gregorian::{Month, YearMonth};
 
 use crate::siv::{get_finance_app, set_finance_app};
 use neo_budget::finance::{FinanceLog, Price};
 
-
 pub fn add_log_view() -> Dialog {
     let layout = LinearLayout::new(cursive::direction::Orientation::Vertical)
         .child(TextView::new("Product name"))
@@ -21,6 +23,40 @@ pub fn add_log_view() -> Dialog {
                 .child(Canvas::new(()).fixed_width(2))
                 .child(EditView::new().with_name("month_input").fixed_width(3)),
         )
+        .child(TextView::new("Installments (optional)"))
+        .child(
+            EditView::new()
+                .with_name("add_log_installments")
+                .fixed_width(5),
+        )
+        .child(TextView::new("Account (optional)"))
+        .child(EditView::new().with_name("add_log_account").fixed_width(20))
+        .child(TextView::new("Quantity and unit (optional)"))
+        .child(
+            LinearLayout::new(cursive::direction::Orientation::Horizontal)
+                .child(EditView::new().with_name("add_log_quantity").fixed_width(8))
+                .child(Canvas::new(()).fixed_width(1))
+                .child(EditView::new().with_name("add_log_unit").fixed_width(6)),
+        )
+        .child(TextView::new("Payee (optional)"))
+        .child(EditView::new().with_name("add_log_payee").fixed_width(20))
+        .child(TextView::new("Location (optional)"))
+        .child(
+            EditView::new()
+                .with_name("add_log_location")
+                .fixed_width(20),
+        )
+        .child(TextView::new("Note (optional)"))
+        .child(EditView::new().with_name("add_log_note").fixed_width(40))
+        .child(TextView::new("Tags (comma separated, Enter completes)"))
+        .child(
+            EditView::new()
+                .on_edit(|siv, content, _| show_tag_suggestions(siv, content))
+                .on_submit(complete_tags_input)
+                .with_name("add_log_tags")
+                .fixed_width(20),
+        )
+        .child(TextView::empty().with_name("add_log_tag_suggestions"))
         .child(Canvas::new(()))
         .child(TextView::empty().with_name("add_log_result"));
 
@@ -50,6 +86,47 @@ pub fn add_log_view() -> Dialog {
                 .parse::<u8>()
                 .map(Month::new);
 
+            let account_input = siv
+                .find_name::<EditView>("add_log_account")
+                .expect("Couldn't find add_log_account")
+                .get_content();
+
+            let tags = parse_tags(
+                &siv.find_name::<EditView>("add_log_tags")
+                    .expect("Couldn't find add_log_tags")
+                    .get_content(),
+            );
+
+            let optional_input = |siv: &mut Cursive, name: &str| {
+                let content = siv
+                    .find_name::<EditView>(name)
+                    .expect("Couldn't find optional log input")
+                    .get_content();
+
+                Some(content.trim().to_owned()).filter(|content| !content.is_empty())
+            };
+
+            let payee = optional_input(siv, "add_log_payee");
+            let location = optional_input(siv, "add_log_location");
+            let note = optional_input(siv, "add_log_note");
+
+            let quantity = match (
+                optional_input(siv, "add_log_quantity").map(|quantity| quantity.parse::<f64>()),
+                optional_input(siv, "add_log_unit"),
+            ) {
+                (None, None) => Ok(None),
+                (Some(Ok(quantity)), Some(unit)) if quantity > 0.0 => Ok(Some((quantity, unit))),
+                _ => Err(()),
+            };
+
+            let installments = match optional_input(siv, "add_log_installments")
+                .map(|installments| installments.parse::<u32>())
+            {
+                None => Ok(None),
+                Some(Ok(installments)) if installments > 0 => Ok(Some(installments)),
+                _ => Err(()),
+            };
+
             let mut result_view = siv
                 .find_name::<TextView>("add_log_result")
                 .expect("Couldn't find add_log_result");
@@ -57,17 +134,49 @@ pub fn add_log_view() -> Dialog {
             let current_app = get_finance_app(siv);
             let current_log = current_app.finance();
 
-            match (price_input, year_input, month_input) {
-                (Ok(price), Ok(year), Ok(Ok(month))) => {
+            let account = match account_input.trim() {
+                "" => Ok(None),
+                account if current_log.accounts().contains_key(account) => {
+                    Ok(Some(account.to_owned()))
+                }
+                _ => Err(()),
+            };
+
+            match (
+                price_input,
+                year_input,
+                month_input,
+                account,
+                quantity,
+                installments,
+            ) {
+                (
+                    Ok(price),
+                    Ok(year),
+                    Ok(Ok(month)),
+                    Ok(account),
+                    Ok(quantity),
+                    Ok(installments),
+                ) => {
+                    let year_month = YearMonth::new(year, month);
+                    let log = FinanceLog {
+                        account,
+                        tags,
+                        payee,
+                        location,
+                        note,
+                        quantity: quantity.as_ref().map(|(quantity, _)| *quantity),
+                        unit: quantity.map(|(_, unit)| unit),
+                        installments,
+                        ..FinanceLog::new(&add_log_name, price, year_month)
+                    };
+
                     set_finance_app(
                         siv,
-                        current_app.with_finance(
+                        current_app.with_edit(
+                            &format!("Added log {add_log_name}: {price:.2} in {year_month}"),
                             // TODO Use real date
-                            current_log.with_log(FinanceLog::new(
-                                &add_log_name,
-                                price,
-                                YearMonth::new(year, month),
-                            )),
+                            current_log.with_log(log),
                         ),
                     );
                     result_view.set_content("Log added successfully!");
@@ -83,3 +192,85 @@ pub fn add_log_view() -> Dialog {
         })
         .h_align(cursive::align::HAlign::Center)
 }
+
+fn show_tag_suggestions(siv: &mut Cursive, content: &str) {
+    let suggestions = tag_suggestions(content, &get_finance_app(siv).finance().tags());
+    let text = match suggestions.is_empty() {
+        true => String::new(),
+        false => format!("Known tags: {}", suggestions.join(", ")),
+    };
+
+    siv.call_on_name("add_log_tag_suggestions", |view: &mut TextView| {
+        view.set_content(text)
+    });
+}
+
+fn complete_tags_input(siv: &mut Cursive, content: &str) {
+    let known_tags = get_finance_app(siv).finance().tags();
+
+    if let Some(suggestion) = tag_suggestions(content, &known_tags).first() {
+        let completed = complete_last_tag(content, suggestion);
+
+        siv.call_on_name("add_log_tags", |view: &mut EditView| {
+            view.set_content(completed)
+        });
+        show_tag_suggestions(siv, "");
+    }
+}
+
+fn parse_tags(content: &str) -> BTreeSet<String> {
+    content
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Known tags starting with the tag being typed, which is the text after the
+/// last comma.
+fn tag_suggestions(content: &str, known_tags: &BTreeSet<String>) -> Vec<String> {
+    let typed_tag = content.rsplit(',').next().unwrap_or_default().trim();
+    if typed_tag.is_empty() {
+        return Vec::new();
+    }
+
+    known_tags
+        .iter()
+        .filter(|tag| tag.starts_with(typed_tag) && tag.as_str() != typed_tag)
+        .cloned()
+        .collect()
+}
+
+fn complete_last_tag(content: &str, tag: &str) -> String {
+    match content.rsplit_once(',') {
+        Some((previous_tags, _)) => format!("{previous_tags}, {tag}, "),
+        None => format!("{tag}, "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::{complete_last_tag, parse_tags, tag_suggestions};
+
+    #[test]
+    fn test_tag_autocomplete() {
+        let known_tags = BTreeSet::from(["reimbursable".to_owned(), "vacation-2024".to_owned()]);
+
+        assert_eq!(
+            tag_suggestions("work, vac", &known_tags),
+            vec!["vacation-2024"]
+        );
+        assert!(tag_suggestions("work, ", &known_tags).is_empty());
+        assert_eq!(
+            complete_last_tag("work, vac", "vacation-2024"),
+            "work, vacation-2024, "
+        );
+        assert_eq!(
+            parse_tags("work, vacation-2024, "),
+            BTreeSet::from(["vacation-2024".to_owned(), "work".to_owned()])
+        );
+    }
+}
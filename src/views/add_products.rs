@@ -51,7 +51,17 @@ fn submit_button_action(siv: &mut Cursive) {
 
         match (product, category) {
             (Some(product), Some(category)) => {
-                app.with_finance(current_finance.with_product(&Product::new(&product, &category)))
+                let description = match current_finance.get_category_for(&product) {
+                    Some(old_category) => {
+                        format!("Moved product {product} from {old_category} to {category}")
+                    }
+                    None => format!("Added product {product} in {category}"),
+                };
+
+                app.with_edit(
+                    &description,
+                    current_finance.with_product(&Product::new(&product, &category)),
+                )
             }
 
             _ => {
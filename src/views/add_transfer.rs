@@ -0,0 +1,102 @@
+use cursive::{
+    view::{Nameable, Resizable},
+    views::{Canvas, Dialog, EditView, LinearLayout, SelectView, TextView},
+};
+use gregorian::{Month, YearMonth};
+use itertools::Itertools;
+
+use crate::siv::{get_finance_app, set_finance_app};
+use neo_budget::finance::{Finance, Price, Transfer};
+
+pub fn add_transfer_view(finance: &Finance) -> Dialog {
+    let layout = LinearLayout::new(cursive::direction::Orientation::Vertical)
+        .child(TextView::new("From account"))
+        .child(account_select(finance).with_name("transfer_from"))
+        .child(TextView::new("To account"))
+        .child(account_select(finance).with_name("transfer_to"))
+        .child(TextView::new("Amount"))
+        .child(EditView::new().with_name("transfer_amount").fixed_width(20))
+        .child(TextView::new("Year-Month"))
+        .child(
+            LinearLayout::new(cursive::direction::Orientation::Horizontal)
+                .child(EditView::new().with_name("transfer_year").fixed_width(5))
+                .child(Canvas::new(()).fixed_width(2))
+                .child(EditView::new().with_name("transfer_month").fixed_width(3)),
+        )
+        .child(Canvas::new(()))
+        .child(TextView::empty().with_name("add_transfer_result"));
+
+    Dialog::around(layout)
+        .button("Submit", |siv| {
+            let selected_account = |siv: &mut cursive::Cursive, name: &str| {
+                siv.find_name::<SelectView<String>>(name)
+                    .expect("Couldn't find account selection")
+                    .selection()
+                    .map(|account| account.to_string())
+            };
+
+            let from = selected_account(siv, "transfer_from");
+            let to = selected_account(siv, "transfer_to");
+
+            let amount_input = siv
+                .find_name::<EditView>("transfer_amount")
+                .expect("Couldn't find transfer_amount")
+                .get_content()
+                .parse::<Price>();
+
+            let year_input = siv
+                .find_name::<EditView>("transfer_year")
+                .expect("Couldn't find transfer_year")
+                .get_content()
+                .parse::<i16>();
+
+            let month_input = siv
+                .find_name::<EditView>("transfer_month")
+                .expect("Couldn't find transfer_month")
+                .get_content()
+                .parse::<u8>()
+                .map(Month::new);
+
+            let mut result_view = siv
+                .find_name::<TextView>("add_transfer_result")
+                .expect("Couldn't find add_transfer_result");
+
+            let current_app = get_finance_app(siv);
+
+            match (from, to, amount_input, year_input, month_input) {
+                (Some(from), Some(to), _, _, _) if from == to => {
+                    result_view.set_content("Pick two different accounts!");
+                }
+
+                (Some(from), Some(to), Ok(amount), Ok(year), Ok(Ok(month))) if amount > 0.0 => {
+                    let year_month = YearMonth::new(year, month);
+                    let finance = current_app
+                        .finance()
+                        .with_transfer(Transfer::new(&from, &to, amount, year_month));
+
+                    set_finance_app(
+                        siv,
+                        current_app.with_edit(
+                            &format!("Transferred {amount:.2} from {from} to {to} in {year_month}"),
+                            finance,
+                        ),
+                    );
+                    result_view.set_content("Transfer added successfully!");
+                }
+
+                _ => {
+                    result_view.set_content("Invalid input!");
+                }
+            }
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+        .h_align(cursive::align::HAlign::Center)
+}
+
+fn account_select(finance: &Finance) -> SelectView<String> {
+    SelectView::new()
+        .popup()
+        .with_all_str(finance.accounts().into_keys().sorted())
+}
@@ -0,0 +1,148 @@
+use cursive::{
+    view::{Nameable, Resizable, Scrollable},
+    views::{Dialog, EditView, LinearLayout, Panel, TextView},
+    Cursive, With,
+};
+use neo_budget::anomalies::{Anomaly, AnomalySubject};
+use neo_budget::finance::Finance;
+use neo_budget::stats::FinanceStats;
+
+use super::logs::log_text;
+use crate::category_separator_from_env;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const ALERTS_TEXT_NAME: &str = "alerts_text";
+const THRESHOLD_CATEGORY_INPUT_NAME: &str = "threshold_category";
+const THRESHOLD_INPUT_NAME: &str = "threshold";
+const THRESHOLD_INFO_TEXT_NAME: &str = "threshold_info_text";
+
+/// Months and logs with unusually high spending, newest first.
+pub fn alerts_view(finance: &Finance) -> Dialog {
+    TextView::new(alerts_text(finance))
+        .with_name(ALERTS_TEXT_NAME)
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Alerts"))
+        .wrap_with(Dialog::around)
+        .button("Set threshold", |siv| {
+            siv.add_layer(threshold_view());
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn alerts_text(finance: &Finance) -> String {
+    let anomalies = FinanceStats::new(finance.clone())
+        .with_category_separator(&category_separator_from_env())
+        .anomalies();
+
+    match anomalies.is_empty() {
+        true => "Nothing unusual.".to_owned(),
+        false => anomalies
+            .iter()
+            .map(|anomaly| anomaly_text(finance, anomaly))
+            .collect::<Vec<String>>()
+            .join("\n"),
+    }
+}
+
+/// The explanation of an anomaly, followed by the log it was found in, if
+/// any.
+fn anomaly_text(finance: &Finance, anomaly: &Anomaly) -> String {
+    let log = match &anomaly.subject {
+        AnomalySubject::Log { index, .. } => finance.logs.get(*index),
+        AnomalySubject::Category(_) => None,
+    };
+
+    match log {
+        Some(log) => format!("{}\n  {}", anomaly.explanation(), log_text(log)),
+        None => anomaly.explanation(),
+    }
+}
+
+fn threshold_view() -> Dialog {
+    LinearLayout::vertical()
+        .child(TextView::new("Category"))
+        .child(
+            EditView::new()
+                .with_name(THRESHOLD_CATEGORY_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::new("Times the usual amount"))
+        .child(
+            EditView::new()
+                .with_name(THRESHOLD_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::empty().with_name(THRESHOLD_INFO_TEXT_NAME))
+        .wrap_with(Dialog::around)
+        .button("Ok", submit_threshold)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn submit_threshold(siv: &mut Cursive) {
+    let input = |siv: &mut Cursive, name: &str| {
+        siv.find_name::<EditView>(name)
+            .expect("Couldn't find threshold input")
+            .get_content()
+            .trim()
+            .to_owned()
+    };
+
+    let category = input(siv, THRESHOLD_CATEGORY_INPUT_NAME);
+    let threshold = input(siv, THRESHOLD_INPUT_NAME).parse::<f64>();
+
+    let info = match (category.as_str(), threshold) {
+        (category, Ok(threshold)) if !category.is_empty() && threshold > 1.0 => {
+            let app = get_finance_app(siv);
+            let description = format!("Set anomaly threshold of {category} to {threshold}");
+            let finance = app.finance().with_anomaly_threshold(category, threshold);
+            set_finance_app(siv, app.with_edit(&description, finance.clone()));
+
+            siv.call_on_name(ALERTS_TEXT_NAME, |view: &mut TextView| {
+                view.set_content(alerts_text(&finance))
+            });
+
+            format!("{description}.")
+        }
+
+        _ => "Invalid input!".to_owned(),
+    };
+
+    siv.call_on_name(THRESHOLD_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::anomalies::{Anomaly, AnomalySubject};
+    use neo_budget::finance::{Finance, FinanceLog};
+
+    use super::anomaly_text;
+
+    #[test]
+    fn test_anomaly_text() {
+        let year_month = YearMonth::new(2024, Month::May);
+        let finance = Finance::new().with_log(FinanceLog::new("coffee", 6.0, year_month));
+        let anomaly = Anomaly {
+            subject: AnomalySubject::Log {
+                index: 0,
+                product: "coffee".to_owned(),
+            },
+            year_month,
+            amount: 6.0,
+            median: 3.0,
+            threshold: 1.5,
+        };
+
+        assert_eq!(
+            anomaly_text(&finance, &anomaly),
+            "Price of coffee in 2024-05 was 6.00, 2.0 times the usual 3.00 (threshold 1.5)\n  \
+             2024-05 coffee 6.00"
+        );
+    }
+}
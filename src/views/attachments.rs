@@ -0,0 +1,187 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use cursive::{
+    view::{Nameable, Resizable, Scrollable},
+    views::{Dialog, EditView, LinearLayout, Panel, SelectView, TextView},
+    Cursive, With,
+};
+use neo_budget::attachments::{Attachment, AttachmentStore};
+use neo_budget::finance::{Finance, FinanceLog};
+
+use super::logs::log_text;
+use super::status_line::set_status;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const ATTACHMENT_LOGS_NAME: &str = "attachment_logs";
+const LOG_ATTACHMENTS_NAME: &str = "log_attachments";
+const ATTACHMENT_PATH_INPUT_NAME: &str = "attachment_path";
+const ATTACHMENT_INFO_TEXT_NAME: &str = "attachment_info_text";
+
+/// Lists the logs, to show and add the attachments of one.
+pub fn attachments_view(finance: &Finance) -> Dialog {
+    let mut log_select = SelectView::<usize>::new().on_submit(|siv, index: &usize| {
+        let finance = get_finance_app(siv).finance();
+        if let Some(log) = finance.logs.get(*index) {
+            siv.add_layer(log_attachments_view(*index, log));
+        }
+    });
+    set_log_items(&mut log_select, finance);
+
+    log_select
+        .with_name(ATTACHMENT_LOGS_NAME)
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Attachments by log"))
+        .wrap_with(Dialog::around)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn set_log_items(log_select: &mut SelectView<usize>, finance: &Finance) {
+    log_select.clear();
+    for (index, log) in finance.logs.iter().enumerate().rev() {
+        log_select.add_item(attachment_log_text(log), index);
+    }
+}
+
+fn attachment_log_text(log: &FinanceLog) -> String {
+    match log.attachments.len() {
+        0 => log_text(log),
+        1 => format!("{} [1 attachment]", log_text(log)),
+        count => format!("{} [{count} attachments]", log_text(log)),
+    }
+}
+
+/// The attachments of the log at `index`. Selecting one opens it.
+fn log_attachments_view(index: usize, log: &FinanceLog) -> Dialog {
+    let mut attachment_select = SelectView::<Attachment>::new().on_submit(open_attachment);
+    for attachment in &log.attachments {
+        attachment_select.add_item(attachment.name.clone(), attachment.clone());
+    }
+
+    LinearLayout::vertical()
+        .child(attachment_select.with_name(LOG_ATTACHMENTS_NAME))
+        .child(TextView::new("\nFile to attach"))
+        .child(
+            EditView::new()
+                .on_submit(move |siv, path| attach_file(siv, index, path))
+                .with_name(ATTACHMENT_PATH_INPUT_NAME)
+                .fixed_width(40),
+        )
+        .child(TextView::empty().with_name(ATTACHMENT_INFO_TEXT_NAME))
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title(log_text(log)))
+        .wrap_with(Dialog::around)
+        .button("Attach", move |siv| {
+            let path = siv
+                .find_name::<EditView>(ATTACHMENT_PATH_INPUT_NAME)
+                .expect("Couldn't find attachment path")
+                .get_content();
+            attach_file(siv, index, &path);
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn attach_file(siv: &mut Cursive, index: usize, path: &str) {
+    let app = get_finance_app(siv);
+    let finance = app.finance();
+
+    let attached = AttachmentStore::for_attaching_from_env()
+        .and_then(|store| store.store(Path::new(path.trim())));
+    let info = match (attached, finance.logs.get(index)) {
+        (Ok(attachment), Some(log)) => {
+            let description = format!("Attached {} to {}", attachment.name, log.product);
+            let log = log.clone().with_attachment(attachment.clone());
+
+            set_finance_app(
+                siv,
+                app.with_edit(&description, finance.with_replaced_log(index, log)),
+            );
+            siv.call_on_name(
+                LOG_ATTACHMENTS_NAME,
+                |attachments: &mut SelectView<Attachment>| {
+                    attachments.add_item(attachment.name.clone(), attachment)
+                },
+            );
+            let finance = get_finance_app(siv).finance();
+            siv.call_on_name(ATTACHMENT_LOGS_NAME, |logs: &mut SelectView<usize>| {
+                set_log_items(logs, &finance)
+            });
+
+            format!("{description}.")
+        }
+
+        (Err(error), _) => error,
+        (_, None) => "The log no longer exists!".to_owned(),
+    };
+
+    siv.call_on_name(ATTACHMENT_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+fn open_attachment(siv: &mut Cursive, attachment: &Attachment) {
+    let opened = AttachmentStore::from_env().and_then(|store| match store.path_of(attachment) {
+        Some(path) if path.is_file() => open_file(&path),
+        None => Err(format!("{} has an invalid file name!", attachment.name)),
+        Some(_) => Err(format!(
+            "{} is missing from {}!",
+            attachment.name,
+            store.directory().display()
+        )),
+    });
+
+    match opened {
+        Ok(()) => set_status(siv, &format!("Opened {}.", attachment.name)),
+        Err(error) => {
+            siv.add_layer(Dialog::info(error));
+        }
+    }
+}
+
+/// Opens `path` with the system's default application. The opener gets no
+/// terminal, so it can't draw over the views.
+fn open_file(path: &Path) -> Result<(), String> {
+    let opener = match cfg!(target_os = "macos") {
+        true => "open",
+        // Unlike `cmd /C start`, this doesn't go through a shell.
+        false if cfg!(target_os = "windows") => "explorer",
+        false => "xdg-open",
+    };
+
+    Command::new(opener)
+        .arg(path)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|_| format!("Couldn't open {}!", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::attachments::Attachment;
+    use neo_budget::finance::FinanceLog;
+
+    use super::attachment_log_text;
+
+    #[test]
+    fn test_attachment_log_text() {
+        let log = FinanceLog::new("tv", 500.0, YearMonth::new(2024, Month::May));
+        let receipt = Attachment {
+            name: "receipt.pdf".to_owned(),
+            file_name: "abc.pdf".to_owned(),
+        };
+
+        assert_eq!(attachment_log_text(&log), "2024-05 tv 500.00");
+        assert_eq!(
+            attachment_log_text(&log.with_attachment(receipt)),
+            "2024-05 tv 500.00 [1 attachment]"
+        );
+    }
+}
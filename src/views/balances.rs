@@ -0,0 +1,372 @@
+use std::collections::BTreeSet;
+
+use cursive::{
+    view::{Nameable, Resizable, Scrollable},
+    views::{Dialog, EditView, LinearLayout, Panel, SelectView, TextView},
+    Cursive, With,
+};
+use gregorian::Date;
+use neo_budget::finance::{Finance, Price};
+use neo_budget::shared::{settle_up, ExpenseSplit, Settlement, SharedExpense, SETTLED_THRESHOLD};
+use neo_budget::stats::FinanceStats;
+
+use super::logs::log_text;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const BALANCES_TEXT_NAME: &str = "balances_text";
+const SETTLE_UP_SELECT_NAME: &str = "settle_up_select";
+const PERSON_INPUT_NAME: &str = "person_input";
+const SHARE_LOG_SELECT_NAME: &str = "share_log_select";
+const SHARE_PAID_BY_NAME: &str = "share_paid_by";
+const SHARE_SPLIT_INPUT_NAME: &str = "share_split";
+const SHARE_INFO_TEXT_NAME: &str = "share_info_text";
+
+/// Who owes whom from shared logs, and the payments that would settle it.
+/// Selecting a payment records it as a settlement.
+pub fn balances_view(finance: &Finance) -> Dialog {
+    let mut settle_up_select = SelectView::<Settlement>::new().on_submit(record_settlement);
+    set_settle_up_items(&mut settle_up_select, finance);
+
+    LinearLayout::vertical()
+        .child(TextView::new(balances_text(finance)).with_name(BALANCES_TEXT_NAME))
+        .child(TextView::new("\nSettle up (Enter: record payment)"))
+        .child(settle_up_select.with_name(SETTLE_UP_SELECT_NAME))
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Shared balances"))
+        .wrap_with(Dialog::around)
+        .button("Add person", |siv| {
+            siv.add_layer(add_person_view());
+        })
+        .button("Share log", |siv| {
+            let finance = get_finance_app(siv).finance();
+            match (finance.people().is_empty(), finance.logs.is_empty()) {
+                (true, _) => siv.add_layer(Dialog::info("Add a person first.")),
+                (_, true) => siv.add_layer(Dialog::info("Add a log first.")),
+                _ => siv.add_layer(share_log_view(&finance)),
+            }
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn record_settlement(siv: &mut Cursive, settlement: &Settlement) {
+    let app = get_finance_app(siv);
+    let description = format!(
+        "Recorded settlement of {:.2} from {} to {}",
+        settlement.amount, settlement.from, settlement.to
+    );
+    let finance = app.finance().with_settlement(settlement.clone());
+
+    set_finance_app(siv, app.with_edit(&description, finance));
+    reload_balances(siv);
+}
+
+fn add_person_view() -> Dialog {
+    EditView::new()
+        .on_submit(|siv, person| {
+            let person = person.trim();
+            if person.is_empty() {
+                return;
+            }
+
+            let app = get_finance_app(siv);
+            let finance = app.finance().with_person(person);
+            set_finance_app(
+                siv,
+                app.with_edit(&format!("Added person {person}"), finance),
+            );
+            siv.pop_layer();
+            reload_balances(siv);
+        })
+        .with_name(PERSON_INPUT_NAME)
+        .fixed_width(20)
+        .wrap_with(|view| Panel::new(view).title("Person name"))
+        .wrap_with(Dialog::around)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+/// Picks a log, who paid it and how to split it.
+fn share_log_view(finance: &Finance) -> Dialog {
+    let mut log_select = SelectView::<usize>::new();
+    for (index, log) in finance.logs.iter().enumerate().rev() {
+        log_select.add_item(log_text(log), index);
+    }
+
+    LinearLayout::vertical()
+        .child(TextView::new("Log"))
+        .child(
+            log_select
+                .with_name(SHARE_LOG_SELECT_NAME)
+                .scrollable()
+                .max_height(10),
+        )
+        .child(TextView::new("Paid by"))
+        .child(
+            SelectView::<String>::new()
+                .popup()
+                .with_all_str(finance.people())
+                .with_name(SHARE_PAID_BY_NAME),
+        )
+        .child(TextView::new(
+            "Split (\"ana, bob\", \"ana 60%, bob 40%\" or \"ana 12.50, bob 7.50\")",
+        ))
+        .child(
+            EditView::new()
+                .with_name(SHARE_SPLIT_INPUT_NAME)
+                .fixed_width(40),
+        )
+        .child(TextView::empty().with_name(SHARE_INFO_TEXT_NAME))
+        .wrap_with(Dialog::around)
+        .button("Ok", submit_share)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn submit_share(siv: &mut Cursive) {
+    let selection = |siv: &mut Cursive, name: &str| {
+        siv.find_name::<SelectView<String>>(name)
+            .and_then(|select| select.selection())
+            .map(|selection| selection.to_string())
+    };
+
+    let index = siv
+        .find_name::<SelectView<usize>>(SHARE_LOG_SELECT_NAME)
+        .and_then(|logs| logs.selection())
+        .map(|index| *index);
+    let paid_by = selection(siv, SHARE_PAID_BY_NAME);
+    let split = siv
+        .find_name::<EditView>(SHARE_SPLIT_INPUT_NAME)
+        .expect("Couldn't find split input")
+        .get_content();
+
+    let app = get_finance_app(siv);
+    let finance = app.finance();
+
+    let info = match (index.and_then(|index| finance.logs.get(index)), paid_by) {
+        (Some(log), Some(paid_by)) => match parse_split(&split, log.price, &finance.people()) {
+            Ok(split) => {
+                let index = index.expect("A log was selected");
+                let description = format!("Shared log {} paid by {paid_by}", log.product);
+                let log = log.clone().with_shared(SharedExpense::new(&paid_by, split));
+
+                set_finance_app(
+                    siv,
+                    app.with_edit(&description, finance.with_replaced_log(index, log)),
+                );
+                reload_balances(siv);
+
+                format!("{description}.")
+            }
+            Err(error) => error,
+        },
+
+        _ => "Pick a log and who paid it!".to_owned(),
+    };
+
+    siv.call_on_name(SHARE_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+fn reload_balances(siv: &mut Cursive) {
+    let finance = get_finance_app(siv).finance();
+
+    siv.call_on_name(BALANCES_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(balances_text(&finance))
+    });
+    siv.call_on_name(
+        SETTLE_UP_SELECT_NAME,
+        |select: &mut SelectView<Settlement>| set_settle_up_items(select, &finance),
+    );
+}
+
+fn set_settle_up_items(select: &mut SelectView<Settlement>, finance: &Finance) {
+    let balances = FinanceStats::new(finance.clone()).shared_balances();
+
+    select.clear();
+    for settlement in settle_up(&balances, Date::today().year_month()) {
+        select.add_item(
+            format!(
+                "{} pays {} {:.2}",
+                settlement.from, settlement.to, settlement.amount
+            ),
+            settlement,
+        );
+    }
+}
+
+fn balances_text(finance: &Finance) -> String {
+    let balances = FinanceStats::new(finance.clone()).shared_balances();
+    let balance_lines = balances
+        .iter()
+        .map(|(person, balance)| match balance {
+            balance if *balance > SETTLED_THRESHOLD => format!("{person} is owed {balance:.2}"),
+            balance if *balance < -SETTLED_THRESHOLD => format!("{person} owes {:.2}", -balance),
+            _ => format!("{person} is settled"),
+        })
+        .collect::<Vec<String>>();
+
+    let settlement_lines = finance.settlements.iter().map(|settlement| {
+        format!(
+            "{}: {} paid {} {:.2}",
+            settlement.year_month, settlement.from, settlement.to, settlement.amount
+        )
+    });
+
+    match (balance_lines.is_empty(), finance.settlements.is_empty()) {
+        (true, _) => "No people yet.".to_owned(),
+        (false, true) => balance_lines.join("\n"),
+        (false, false) => balance_lines
+            .into_iter()
+            .chain(["".to_owned(), "Settlements:".to_owned()])
+            .chain(settlement_lines)
+            .collect::<Vec<String>>()
+            .join("\n"),
+    }
+}
+
+/// Parses a comma-separated split of `price`: names only for equal shares,
+/// names with percentages, or names with exact amounts.
+fn parse_split(
+    split: &str,
+    price: Price,
+    people: &BTreeSet<String>,
+) -> Result<ExpenseSplit, String> {
+    let entries = split
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.rsplit_once(' ') {
+            Some((person, share)) => (person.trim().to_owned(), Some(share.to_owned())),
+            None => (entry.to_owned(), None),
+        })
+        .collect::<Vec<(String, Option<String>)>>();
+
+    if entries.is_empty() {
+        return Err("Split can't be empty!".to_owned());
+    }
+
+    if let Some((person, _)) = entries.iter().find(|(person, _)| !people.contains(person)) {
+        return Err(format!("Unknown person {person}!"));
+    }
+
+    if entries.iter().all(|(_, share)| share.is_none()) {
+        return Ok(ExpenseSplit::Equal(
+            entries.into_iter().map(|(person, _)| person).collect(),
+        ));
+    }
+
+    let percentages = entries
+        .iter()
+        .map(|(person, share)| {
+            share
+                .as_deref()
+                .and_then(|share| share.strip_suffix('%'))
+                .and_then(|percent| percent.parse::<f64>().ok())
+                .map(|percent| (person.clone(), percent))
+        })
+        .collect::<Option<Vec<(String, f64)>>>();
+
+    if let Some(percentages) = percentages {
+        let total = total_of(&percentages);
+        return match (total - 100.0).abs() < 0.01 {
+            true => Ok(ExpenseSplit::Percentage(percentages)),
+            false => Err(format!("Percentages add up to {total:.2}%, not 100%!")),
+        };
+    }
+
+    let amounts = entries
+        .iter()
+        .map(|(person, share)| {
+            share
+                .as_deref()
+                .and_then(|amount| amount.parse::<Price>().ok())
+                .map(|amount| (person.clone(), amount))
+        })
+        .collect::<Option<Vec<(String, Price)>>>();
+
+    match amounts {
+        Some(amounts) if (total_of(&amounts) - price).abs() < 0.005 => {
+            Ok(ExpenseSplit::Exact(amounts))
+        }
+        Some(amounts) => Err(format!(
+            "Amounts add up to {:.2}, not {price:.2}!",
+            total_of(&amounts)
+        )),
+        None => Err("Invalid split!".to_owned()),
+    }
+}
+
+fn total_of(shares: &[(String, f64)]) -> f64 {
+    shares.iter().fold(0.0, |total, (_, share)| total + share)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use gregorian::{Month, YearMonth};
+    use neo_budget::finance::{Finance, FinanceLog};
+    use neo_budget::shared::{ExpenseSplit, Settlement, SharedExpense};
+
+    use super::{balances_text, parse_split};
+
+    #[test]
+    fn test_parse_split() {
+        let people = BTreeSet::from(["ana".to_owned(), "bob".to_owned()]);
+
+        assert_eq!(
+            parse_split("ana, bob", 10.0, &people),
+            Ok(ExpenseSplit::Equal(vec![
+                "ana".to_owned(),
+                "bob".to_owned()
+            ]))
+        );
+        assert_eq!(
+            parse_split("ana 60%, bob 40%", 10.0, &people),
+            Ok(ExpenseSplit::Percentage(vec![
+                ("ana".to_owned(), 60.0),
+                ("bob".to_owned(), 40.0)
+            ]))
+        );
+        assert_eq!(
+            parse_split("ana 2.50, bob 7.50", 10.0, &people),
+            Ok(ExpenseSplit::Exact(vec![
+                ("ana".to_owned(), 2.5),
+                ("bob".to_owned(), 7.5)
+            ]))
+        );
+        assert_eq!(
+            parse_split("ana 2.50, bob 5", 10.0, &people),
+            Err("Amounts add up to 7.50, not 10.00!".to_owned())
+        );
+        assert_eq!(
+            parse_split("ana, cat", 10.0, &people),
+            Err("Unknown person cat!".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_balances_text() {
+        let year_month = YearMonth::new(2024, Month::May);
+        let finance = Finance::new()
+            .with_person("ana")
+            .with_person("bob")
+            .with_log(FinanceLog::new("groceries", 40.0, year_month).with_shared(
+                SharedExpense::new(
+                    "ana",
+                    ExpenseSplit::Equal(vec!["ana".to_owned(), "bob".to_owned()]),
+                ),
+            ))
+            .with_settlement(Settlement::new("bob", "ana", 5.0, year_month));
+
+        assert_eq!(
+            balances_text(&finance),
+            "ana is owed 15.00\nbob owes 15.00\n\nSettlements:\n2024-05: bob paid ana 5.00"
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use cursive::{view::Nameable, views::NamedView, views::TextView, Cursive};
+use gregorian::{Date, YearMonth};
+use neo_budget::finance::Finance;
+use neo_budget::stats::FinanceStats;
+
+use crate::category_separator_from_env;
+
+const BUDGET_LINE_NAME: &str = "budget_line";
+
+/// Shows how much income is left to put in envelopes this month.
+pub fn budget_line_view() -> NamedView<TextView> {
+    TextView::empty().with_name(BUDGET_LINE_NAME)
+}
+
+pub fn update_budget_line(siv: &mut Cursive, finance: &Finance) {
+    let text = budget_line_text(finance, Date::today().year_month());
+
+    siv.call_on_name(BUDGET_LINE_NAME, |view: &mut TextView| {
+        view.set_content(text)
+    });
+}
+
+fn budget_line_text(finance: &Finance, current: YearMonth) -> String {
+    let budget = FinanceStats::new(finance.clone())
+        .with_category_separator(&category_separator_from_env())
+        .envelope_budget(current);
+
+    format!("Available to budget: {:.2}", budget.available_to_budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::envelopes::Envelope;
+    use neo_budget::finance::{Finance, FinanceLog};
+
+    use super::budget_line_text;
+
+    #[test]
+    fn test_budget_line_text() {
+        let may = YearMonth::new(2024, Month::May);
+        let finance = Finance::new()
+            .with_log(FinanceLog::new("salary", -500.0, may))
+            .with_envelope(&Envelope::new("Food", true).with_allocation(may, 200.0));
+
+        assert_eq!(
+            budget_line_text(&finance, may),
+            "Available to budget: 300.00"
+        );
+    }
+}
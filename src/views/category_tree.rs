@@ -0,0 +1,197 @@
+use std::collections::{BTreeSet, HashMap};
+
+use cursive::{
+    view::{Nameable, Scrollable},
+    views::{Dialog, LinearLayout, Panel, SelectView},
+    Cursive,
+};
+use gregorian::YearMonth;
+use itertools::Itertools;
+use neo_budget::categories::CategoryTree;
+use neo_budget::stats::FinanceStats;
+
+const CATEGORY_ROWS_NAME: &str = "category_rows";
+const CATEGORY_MONTHS_NAME: &str = "category_months";
+const OTHERS_LABEL: &str = "<others>";
+
+/// Category totals by month as a tree. Selecting a category expands or
+/// collapses its subcategories.
+pub fn category_tree_view(stats: &FinanceStats) -> Dialog {
+    let trees = stats
+        .category_totals_by_year_month()
+        .into_iter()
+        .map(|(year_month, totals)| {
+            let mut tree = CategoryTree::from_totals(&totals.labeled, stats.category_separator());
+            if totals.unlabeled > 0.0 {
+                tree.children.insert(
+                    OTHERS_LABEL.to_owned(),
+                    CategoryTree {
+                        total: totals.unlabeled,
+                        ..CategoryTree::default()
+                    },
+                );
+            }
+
+            (year_month, tree)
+        })
+        .collect::<HashMap<YearMonth, CategoryTree>>();
+
+    let mut month_select = SelectView::<YearMonth>::new();
+    for year_month in trees.keys().sorted() {
+        month_select.add_item(year_month.to_string(), *year_month);
+    }
+
+    let mut row_select = SelectView::<Vec<String>>::new();
+    if let Some(tree) = trees
+        .keys()
+        .min()
+        .and_then(|year_month| trees.get(year_month))
+    {
+        set_rows(&mut row_select, tree, &BTreeSet::new());
+    }
+
+    let trees_on_select = trees.clone();
+    month_select.set_on_select(move |siv, year_month| {
+        if let Some(tree) = trees_on_select.get(year_month) {
+            siv.call_on_name(CATEGORY_ROWS_NAME, |rows: &mut SelectView<Vec<String>>| {
+                set_rows(rows, tree, &BTreeSet::new())
+            });
+        }
+    });
+
+    row_select.set_on_submit(move |siv, path: &Vec<String>| toggle_row(siv, &trees, path));
+
+    Dialog::around(
+        LinearLayout::horizontal()
+            .child(Panel::new(month_select.with_name(CATEGORY_MONTHS_NAME)))
+            .child(Panel::new(
+                row_select.with_name(CATEGORY_ROWS_NAME).scrollable(),
+            )),
+    )
+    .title("Category totals (Enter: expand/collapse)")
+    .button("Back", |siv| {
+        siv.pop_layer();
+    })
+}
+
+fn toggle_row(siv: &mut Cursive, trees: &HashMap<YearMonth, CategoryTree>, path: &Vec<String>) {
+    let year_month = siv
+        .find_name::<SelectView<YearMonth>>(CATEGORY_MONTHS_NAME)
+        .and_then(|months| months.selection())
+        .map(|year_month| *year_month);
+
+    let Some(tree) = year_month.and_then(|year_month| trees.get(&year_month)) else {
+        return;
+    };
+
+    siv.call_on_name(CATEGORY_ROWS_NAME, |rows: &mut SelectView<Vec<String>>| {
+        let shown_paths = rows
+            .iter()
+            .map(|(_, path)| path.clone())
+            .collect::<Vec<Vec<String>>>();
+
+        // A row is expanded when its children are shown.
+        let mut expanded = shown_paths
+            .iter()
+            .filter_map(|path| path.split_last().map(|(_, parent)| parent.to_vec()))
+            .filter(|parent| !parent.is_empty())
+            .collect::<BTreeSet<Vec<String>>>();
+
+        if !expanded.remove(path) {
+            expanded.insert(path.clone());
+        }
+
+        let selected = rows.selected_id();
+        set_rows(rows, tree, &expanded);
+        if let Some(selected) = selected {
+            rows.set_selection(selected);
+        }
+    });
+}
+
+fn set_rows(
+    rows: &mut SelectView<Vec<String>>,
+    tree: &CategoryTree,
+    expanded: &BTreeSet<Vec<String>>,
+) {
+    rows.clear();
+    for (label, path) in tree_rows(tree, expanded) {
+        rows.add_item(label, path);
+    }
+}
+
+/// One row per category shown: top-level categories, and the subcategories
+/// of every expanded category. Each row is a label and the category path.
+fn tree_rows(tree: &CategoryTree, expanded: &BTreeSet<Vec<String>>) -> Vec<(String, Vec<String>)> {
+    let mut rows = Vec::new();
+    add_tree_rows(&mut rows, tree, &[], expanded);
+
+    rows
+}
+
+fn add_tree_rows(
+    rows: &mut Vec<(String, Vec<String>)>,
+    tree: &CategoryTree,
+    parent: &[String],
+    expanded: &BTreeSet<Vec<String>>,
+) {
+    for (name, child) in &tree.children {
+        let path = [parent, std::slice::from_ref(name)].concat();
+        let is_expanded = expanded.contains(&path);
+        let marker = match (child.children.is_empty(), is_expanded) {
+            (true, _) => " ",
+            (false, true) => "-",
+            (false, false) => "+",
+        };
+
+        let indent = "  ".repeat(parent.len());
+        rows.push((
+            format!("{indent}{marker} {name}: {:.2}", child.total),
+            path.clone(),
+        ));
+
+        if is_expanded {
+            add_tree_rows(rows, child, &path, expanded);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashMap};
+
+    use neo_budget::categories::CategoryTree;
+
+    use super::tree_rows;
+
+    #[test]
+    fn test_tree_rows() {
+        let totals = HashMap::from([
+            ("Food:Restaurants".to_owned(), 30.0),
+            ("Food:Groceries".to_owned(), 50.0),
+            ("Rent".to_owned(), 500.0),
+        ]);
+        let tree = CategoryTree::from_totals(&totals, ":");
+
+        let labels = |expanded: &BTreeSet<Vec<String>>| {
+            tree_rows(&tree, expanded)
+                .into_iter()
+                .map(|(label, _)| label)
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            labels(&BTreeSet::new()),
+            vec!["+ Food: 80.00", "  Rent: 500.00"]
+        );
+        assert_eq!(
+            labels(&BTreeSet::from([vec!["Food".to_owned()]])),
+            vec![
+                "- Food: 80.00",
+                "    Groceries: 50.00",
+                "    Restaurants: 30.00",
+                "  Rent: 500.00"
+            ]
+        );
+    }
+}
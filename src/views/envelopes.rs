@@ -0,0 +1,246 @@
+use cursive::{
+    view::{Nameable, Resizable, Scrollable},
+    views::{Checkbox, Dialog, EditView, LinearLayout, Panel, SelectView, TextView},
+    Cursive, With,
+};
+use gregorian::{Date, YearMonth};
+use itertools::Itertools;
+use neo_budget::envelopes::Envelope;
+use neo_budget::finance::{Finance, Price};
+use neo_budget::stats::FinanceStats;
+
+use super::goals::parse_year_month;
+use crate::category_separator_from_env;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const ENVELOPES_TEXT_NAME: &str = "envelopes_text";
+const ENVELOPE_CATEGORY_INPUT_NAME: &str = "envelope_category";
+const ENVELOPE_ROLLOVER_NAME: &str = "envelope_rollover";
+const ENVELOPE_INFO_TEXT_NAME: &str = "envelope_info_text";
+const ALLOCATION_ENVELOPE_NAME: &str = "allocation_envelope";
+const ALLOCATION_MONTH_INPUT_NAME: &str = "allocation_month";
+const ALLOCATION_AMOUNT_INPUT_NAME: &str = "allocation_amount";
+const ALLOCATION_INFO_TEXT_NAME: &str = "allocation_info_text";
+
+/// This month's envelopes: what each has left, and how much income is still
+/// to be budgeted.
+pub fn envelopes_view(finance: &Finance) -> Dialog {
+    TextView::new(envelopes_text(finance, Date::today().year_month()))
+        .with_name(ENVELOPES_TEXT_NAME)
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Envelopes"))
+        .wrap_with(Dialog::around)
+        .button("Add envelope", |siv| {
+            siv.add_layer(add_envelope_view());
+        })
+        .button("Allocate", |siv| {
+            let finance = get_finance_app(siv).finance();
+            match finance.envelopes().is_empty() {
+                true => siv.add_layer(Dialog::info("Add an envelope first.")),
+                false => siv.add_layer(allocate_view(&finance)),
+            }
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn add_envelope_view() -> Dialog {
+    LinearLayout::vertical()
+        .child(TextView::new("Category"))
+        .child(
+            EditView::new()
+                .with_name(ENVELOPE_CATEGORY_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(
+            LinearLayout::horizontal()
+                .child(Checkbox::new().with_name(ENVELOPE_ROLLOVER_NAME))
+                .child(TextView::new(" Roll leftovers over")),
+        )
+        .child(TextView::empty().with_name(ENVELOPE_INFO_TEXT_NAME))
+        .wrap_with(Dialog::around)
+        .button("Ok", submit_envelope)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn submit_envelope(siv: &mut Cursive) {
+    let category = siv
+        .find_name::<EditView>(ENVELOPE_CATEGORY_INPUT_NAME)
+        .expect("Couldn't find envelope category")
+        .get_content()
+        .trim()
+        .to_owned();
+    let rollover = siv
+        .find_name::<Checkbox>(ENVELOPE_ROLLOVER_NAME)
+        .expect("Couldn't find envelope rollover")
+        .is_checked();
+
+    let app = get_finance_app(siv);
+    let finance = app.finance();
+
+    let info = match category.as_str() {
+        "" => "Category can't be empty!".to_owned(),
+        category if finance.envelopes().contains_key(category) => {
+            "Envelope already exists!".to_owned()
+        }
+
+        category => {
+            let description = format!("Added envelope {category}");
+            set_finance_app(
+                siv,
+                app.with_edit(
+                    &description,
+                    finance.with_envelope(&Envelope::new(category, rollover)),
+                ),
+            );
+            reload_envelopes_text(siv);
+
+            format!("{description}.")
+        }
+    };
+
+    siv.call_on_name(ENVELOPE_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+fn allocate_view(finance: &Finance) -> Dialog {
+    LinearLayout::vertical()
+        .child(TextView::new("Envelope"))
+        .child(
+            SelectView::<String>::new()
+                .popup()
+                .with_all_str(finance.envelopes().into_keys().sorted())
+                .with_name(ALLOCATION_ENVELOPE_NAME),
+        )
+        .child(TextView::new("Month (YYYY-MM)"))
+        .child(
+            EditView::new()
+                .content(Date::today().year_month().to_string())
+                .with_name(ALLOCATION_MONTH_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::new("Amount"))
+        .child(
+            EditView::new()
+                .with_name(ALLOCATION_AMOUNT_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::empty().with_name(ALLOCATION_INFO_TEXT_NAME))
+        .wrap_with(Dialog::around)
+        .button("Ok", submit_allocation)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn submit_allocation(siv: &mut Cursive) {
+    let input = |siv: &mut Cursive, name: &str| {
+        siv.find_name::<EditView>(name)
+            .expect("Couldn't find allocation input")
+            .get_content()
+            .trim()
+            .to_owned()
+    };
+
+    let category = siv
+        .find_name::<SelectView<String>>(ALLOCATION_ENVELOPE_NAME)
+        .and_then(|envelopes| envelopes.selection())
+        .map(|category| category.to_string());
+    let year_month = parse_year_month(&input(siv, ALLOCATION_MONTH_INPUT_NAME));
+    let amount = input(siv, ALLOCATION_AMOUNT_INPUT_NAME).parse::<Price>();
+
+    let app = get_finance_app(siv);
+    let finance = app.finance();
+    let envelope = category.and_then(|category| finance.envelopes().get(&category).cloned());
+
+    let info = match (envelope, year_month, amount) {
+        (Some(envelope), Some(year_month), Ok(amount)) if amount >= 0.0 => {
+            let description = format!(
+                "Allocated {amount:.2} to {} in {year_month}",
+                envelope.category()
+            );
+            let envelope = envelope.with_allocation(year_month, amount);
+            set_finance_app(
+                siv,
+                app.with_edit(&description, finance.with_envelope(&envelope)),
+            );
+            reload_envelopes_text(siv);
+
+            format!("{description}.")
+        }
+
+        _ => "Invalid input!".to_owned(),
+    };
+
+    siv.call_on_name(ALLOCATION_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+fn reload_envelopes_text(siv: &mut Cursive) {
+    let finance = get_finance_app(siv).finance();
+
+    siv.call_on_name(ENVELOPES_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(envelopes_text(&finance, Date::today().year_month()))
+    });
+}
+
+fn envelopes_text(finance: &Finance, current: YearMonth) -> String {
+    let budget = FinanceStats::new(finance.clone())
+        .with_category_separator(&category_separator_from_env())
+        .envelope_budget(current);
+
+    let summary = format!(
+        "{current}: income {:.2}, available to budget {:.2}",
+        budget.income, budget.available_to_budget
+    );
+
+    let envelope_lines = budget.envelopes.iter().map(|envelope| {
+        format!(
+            "{}: {:.2} carried + {:.2} allocated - {:.2} spent = {:.2} left",
+            envelope.category,
+            envelope.carried_over,
+            envelope.allocated,
+            envelope.spent,
+            envelope.available
+        )
+    });
+
+    match budget.envelopes.is_empty() {
+        true => format!("{summary}\n\nNo envelopes yet."),
+        false => [summary, "".to_owned()]
+            .into_iter()
+            .chain(envelope_lines)
+            .collect::<Vec<String>>()
+            .join("\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::envelopes::Envelope;
+    use neo_budget::finance::{Finance, FinanceLog, Product};
+
+    use super::envelopes_text;
+
+    #[test]
+    fn test_envelopes_text() {
+        let may = YearMonth::new(2024, Month::May);
+        let finance = Finance::new()
+            .with_product(&Product::new("groceries", "Food"))
+            .with_log(FinanceLog::new("salary", -500.0, may))
+            .with_log(FinanceLog::new("groceries", 80.0, may))
+            .with_envelope(&Envelope::new("Food", true).with_allocation(may, 200.0));
+
+        assert_eq!(
+            envelopes_text(&finance, may),
+            "2024-05: income 500.00, available to budget 300.00\n\n\
+             Food: 0.00 carried + 200.00 allocated - 80.00 spent = 120.00 left"
+        );
+    }
+}
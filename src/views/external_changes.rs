@@ -0,0 +1,46 @@
+use cursive::{
+    view::Nameable,
+    views::{Dialog, TextView},
+    Cursive,
+};
+
+use crate::siv::{
+    ignore_stored_changes, merge_finance_app, overwrite_finance_app, reload_finance_app,
+};
+
+const EXTERNAL_CHANGES_DIALOG_NAME: &str = "external_changes_dialog";
+
+/// Shows the external changes dialog, unless it's already open.
+pub fn show_external_changes_dialog(siv: &mut Cursive) {
+    if siv
+        .find_name::<Dialog>(EXTERNAL_CHANGES_DIALOG_NAME)
+        .is_none()
+    {
+        siv.add_layer(external_changes_view().with_name(EXTERNAL_CHANGES_DIALOG_NAME));
+    }
+}
+
+fn external_changes_view() -> Dialog {
+    Dialog::around(TextView::new(
+        "The data file was changed outside NeoBudget since it was loaded.\n\
+         Reload discards your unsaved changes, Overwrite discards the file's changes \
+         and Merge keeps both.",
+    ))
+    .title("Data file changed")
+    .button("Reload", |siv| {
+        siv.pop_layer();
+        reload_finance_app(siv);
+    })
+    .button("Overwrite", |siv| {
+        siv.pop_layer();
+        overwrite_finance_app(siv);
+    })
+    .button("Merge", |siv| {
+        siv.pop_layer();
+        merge_finance_app(siv);
+    })
+    .button("Later", |siv| {
+        siv.pop_layer();
+        ignore_stored_changes(siv);
+    })
+}
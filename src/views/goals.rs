@@ -0,0 +1,265 @@
+use cursive::{
+    view::{Nameable, Resizable, Scrollable},
+    views::{Dialog, EditView, LinearLayout, Panel, SelectView, TextView},
+    Cursive, With,
+};
+use gregorian::{Date, YearMonth};
+use itertools::Itertools;
+use neo_budget::finance::{Finance, Goal, GoalContribution, Price};
+use neo_budget::stats::FinanceStats;
+
+use super::logs::log_text;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const GOALS_TEXT_NAME: &str = "goals_text";
+const GOAL_NAME_INPUT_NAME: &str = "goal_name";
+const GOAL_TARGET_INPUT_NAME: &str = "goal_target";
+const GOAL_DEADLINE_INPUT_NAME: &str = "goal_deadline";
+const GOAL_INFO_TEXT_NAME: &str = "goal_info_text";
+const CONTRIBUTION_GOAL_NAME: &str = "contribution_goal";
+
+pub fn goals_view(finance: &Finance) -> Dialog {
+    TextView::new(goals_text(finance, Date::today().year_month()))
+        .with_name(GOALS_TEXT_NAME)
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Savings goals"))
+        .wrap_with(Dialog::around)
+        .button("Add goal", |siv| {
+            siv.add_layer(add_goal_view());
+        })
+        .button("Contribute", |siv| {
+            let finance = get_finance_app(siv).finance();
+            match finance.goals().is_empty() {
+                true => siv.add_layer(Dialog::info("Add a goal first.")),
+                false => siv.add_layer(contribute_view(&finance)),
+            }
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn add_goal_view() -> Dialog {
+    LinearLayout::vertical()
+        .child(TextView::new("Goal name"))
+        .child(
+            EditView::new()
+                .with_name(GOAL_NAME_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::new("Target amount"))
+        .child(
+            EditView::new()
+                .with_name(GOAL_TARGET_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::new("Deadline (YYYY-MM, optional)"))
+        .child(
+            EditView::new()
+                .with_name(GOAL_DEADLINE_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::empty().with_name(GOAL_INFO_TEXT_NAME))
+        .wrap_with(Dialog::around)
+        .button("Ok", submit_goal)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn submit_goal(siv: &mut Cursive) {
+    let input = |siv: &mut Cursive, name: &str| {
+        siv.find_name::<EditView>(name)
+            .expect("Couldn't find goal input")
+            .get_content()
+            .trim()
+            .to_owned()
+    };
+
+    let name = input(siv, GOAL_NAME_INPUT_NAME);
+    let target = input(siv, GOAL_TARGET_INPUT_NAME).parse::<Price>();
+    let deadline = match input(siv, GOAL_DEADLINE_INPUT_NAME).as_str() {
+        "" => Ok(None),
+        deadline => parse_year_month(deadline).map(Some).ok_or(()),
+    };
+
+    let app = get_finance_app(siv);
+    let finance = app.finance();
+
+    let info = match (name.as_str(), target, deadline) {
+        ("", _, _) => "Goal name can't be empty!".to_owned(),
+        (name, _, _) if finance.goals().contains_key(name) => "Goal already exists!".to_owned(),
+
+        (name, Ok(target), Ok(deadline)) if target > 0.0 => {
+            let description = format!("Added goal {name}: {target:.2}");
+            set_finance_app(
+                siv,
+                app.with_edit(
+                    &description,
+                    finance.with_goal(&Goal::new(name, target, deadline)),
+                ),
+            );
+            reload_goals_text(siv);
+
+            format!("{description}.")
+        }
+
+        _ => "Invalid input!".to_owned(),
+    };
+
+    siv.call_on_name(GOAL_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+/// Picks a goal, then the log or transfer that contributed to it.
+fn contribute_view(finance: &Finance) -> Dialog {
+    let goal_select = SelectView::<String>::new()
+        .popup()
+        .with_all_str(finance.goals().into_keys().sorted())
+        .with_name(CONTRIBUTION_GOAL_NAME);
+
+    let mut contribution_select = SelectView::<GoalContribution>::new();
+    for (index, transfer) in finance.transfers.iter().enumerate().rev() {
+        contribution_select.add_item(
+            format!(
+                "Transfer {} {:.2} from {} to {}",
+                transfer.year_month, transfer.amount, transfer.from, transfer.to
+            ),
+            GoalContribution::Transfer(index),
+        );
+    }
+    for (index, log) in finance.logs.iter().enumerate().rev() {
+        contribution_select.add_item(
+            format!("Log {}", log_text(log)),
+            GoalContribution::Log(index),
+        );
+    }
+
+    contribution_select.set_on_submit(|siv, contribution: &GoalContribution| {
+        let goal_id = siv
+            .find_name::<SelectView<String>>(CONTRIBUTION_GOAL_NAME)
+            .and_then(|goals| goals.selection())
+            .map(|goal_id| goal_id.to_string());
+
+        let app = get_finance_app(siv);
+        let finance = app.finance();
+
+        if let Some(goal) = goal_id.and_then(|goal_id| finance.goals().get(&goal_id).cloned()) {
+            if goal.contributions.contains(contribution) {
+                siv.add_layer(Dialog::info("Already contributed to this goal."));
+                return;
+            }
+
+            let description = format!("Contributed to goal {}", goal.id());
+            let goal = goal.with_contribution(*contribution);
+            set_finance_app(siv, app.with_edit(&description, finance.with_goal(&goal)));
+            siv.pop_layer();
+            reload_goals_text(siv);
+        }
+    });
+
+    LinearLayout::vertical()
+        .child(TextView::new("Goal"))
+        .child(goal_select)
+        .child(TextView::new("Contribution"))
+        .child(contribution_select.scrollable().max_height(15))
+        .wrap_with(Dialog::around)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn reload_goals_text(siv: &mut Cursive) {
+    let finance = get_finance_app(siv).finance();
+
+    siv.call_on_name(GOALS_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(goals_text(&finance, Date::today().year_month()))
+    });
+}
+
+fn goals_text(finance: &Finance, current: YearMonth) -> String {
+    let stats = FinanceStats::new(finance.clone());
+    let lines = finance
+        .goals()
+        .into_values()
+        .sorted_by(|left, right| left.id().cmp(right.id()))
+        .map(|goal| {
+            let progress = stats.goal_progress(&goal, current);
+            let projection = match (progress.remaining, progress.projected_completion) {
+                (0.0, Some(completion)) => {
+                    format!("reached in {completion}")
+                }
+                (_, Some(completion)) => {
+                    format!("{:.2}/month, done by {completion}", progress.monthly_rate)
+                }
+                (_, None) => "no contributions yet".to_owned(),
+            };
+
+            let deadline = match (goal.deadline, progress.projected_completion) {
+                (Some(deadline), Some(completion)) if completion > deadline => {
+                    format!(", behind the {deadline} deadline")
+                }
+                (Some(deadline), _) => format!(", deadline {deadline}"),
+                (None, _) => String::new(),
+            };
+
+            format!(
+                "{}: {:.2} of {:.2}, {projection}{deadline}",
+                goal.id(),
+                progress.saved,
+                goal.target
+            )
+        })
+        .collect::<Vec<String>>();
+
+    match lines.is_empty() {
+        true => "No goals yet.".to_owned(),
+        false => lines.join("\n"),
+    }
+}
+
+pub fn parse_year_month(year_month: &str) -> Option<YearMonth> {
+    format!("{year_month}-01")
+        .parse::<Date>()
+        .ok()
+        .map(|date| date.year_month())
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::finance::{Finance, FinanceLog, Goal, GoalContribution};
+
+    use super::{goals_text, parse_year_month};
+
+    #[test]
+    fn test_goals_text() {
+        let finance = Finance::new()
+            .with_log(FinanceLog::new(
+                "car fund",
+                100.0,
+                YearMonth::new(2024, Month::January),
+            ))
+            .with_goal(
+                &Goal::new("car", 500.0, Some(YearMonth::new(2024, Month::March)))
+                    .with_contribution(GoalContribution::Log(0)),
+            )
+            .with_goal(&Goal::new("emergency", 1000.0, None));
+
+        assert_eq!(
+            goals_text(&finance, YearMonth::new(2024, Month::February)),
+            "car: 100.00 of 500.00, 50.00/month, done by 2024-10, behind the 2024-03 deadline\n\
+             emergency: 0.00 of 1000.00, no contributions yet"
+        );
+    }
+
+    #[test]
+    fn test_parse_year_month() {
+        assert_eq!(
+            parse_year_month("2025-06"),
+            Some(YearMonth::new(2025, Month::June))
+        );
+        assert_eq!(parse_year_month("June"), None);
+    }
+}
@@ -0,0 +1,79 @@
+use cursive::{
+    view::{Nameable, Scrollable},
+    views::{Dialog, Panel, TextView},
+    Cursive, With,
+};
+use neo_budget::finance::Finance;
+use neo_budget::history::History;
+
+use crate::siv::{get_finance_app, redo_finance_app, undo_finance_app};
+
+const HISTORY_TEXT_NAME: &str = "history_text";
+
+pub fn history_view(history: &History<Finance>) -> Dialog {
+    TextView::new(history_text(history))
+        .with_name(HISTORY_TEXT_NAME)
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Edits, newest first"))
+        .wrap_with(Dialog::around)
+        .button("Undo", |siv| {
+            undo_finance_app(siv);
+            reload_history_text(siv);
+        })
+        .button("Redo", |siv| {
+            redo_finance_app(siv);
+            reload_history_text(siv);
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn reload_history_text(siv: &mut Cursive) {
+    let history = get_finance_app(siv).history();
+
+    siv.call_on_name(HISTORY_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(history_text(&history))
+    });
+}
+
+fn history_text<T: Clone>(history: &History<T>) -> String {
+    let redo_lines = history
+        .redo_steps()
+        .into_iter()
+        .rev()
+        .map(|step| format!("(undone) {}", step.description));
+
+    let undo_lines = history
+        .undo_steps()
+        .into_iter()
+        .rev()
+        .map(|step| step.description);
+
+    let lines = redo_lines.chain(undo_lines).collect::<Vec<String>>();
+
+    match lines.is_empty() {
+        true => "No edits yet.".to_owned(),
+        false => lines.join("\n"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use neo_budget::history::History;
+
+    use super::history_text;
+
+    #[test]
+    fn test_history_text() {
+        let (history, _) = History::new()
+            .with_step("first", 0, 1)
+            .with_step("second", 1, 2)
+            .with_step("third", 2, 3)
+            .undo()
+            .unwrap();
+
+        assert_eq!(history_text(&history), "(undone) third\nsecond\nfirst");
+        assert_eq!(history_text(&History::<i32>::new()), "No edits yet.");
+    }
+}
@@ -0,0 +1,75 @@
+use cursive::{
+    view::{Nameable, Resizable},
+    views::{Dialog, EditView, LinearLayout, TextView},
+    Cursive,
+};
+use gregorian::Date;
+use neo_budget::repository::JournalFinanceRepository;
+use neo_budget::stats::FinanceStats;
+
+use super::show_logs::show_period_totals_view;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const DATE_INPUT_NAME: &str = "journal_date";
+const INFO_TEXT_NAME: &str = "journal_info_text";
+
+pub fn journal_view(journal: JournalFinanceRepository) -> Dialog {
+    let books_journal = journal.clone();
+
+    let layout = LinearLayout::vertical()
+        .child(TextView::new("Date (YYYY-MM-DD)"))
+        .child(
+            EditView::new()
+                .content(Date::today().to_string())
+                .with_name(DATE_INPUT_NAME)
+                .fixed_width(12),
+        )
+        .child(TextView::empty().with_name(INFO_TEXT_NAME));
+
+    Dialog::around(layout)
+        .title("Journal")
+        .button("Category totals as of date", move |siv| {
+            let books = date_input(siv).and_then(|date| books_journal.load_as_of(date));
+
+            match books {
+                Ok(books) => {
+                    let stats = FinanceStats::new(books);
+                    let fiscal_year_start = get_finance_app(siv).fiscal_year_start();
+                    siv.add_layer(show_period_totals_view(
+                        stats.category_totals_by_year_month(),
+                        fiscal_year_start,
+                    ));
+                }
+
+                Err(error) => set_info_text(siv, &error),
+            }
+        })
+        .button("Compact until date", move |siv| {
+            let compacted = date_input(siv).and_then(|date| {
+                get_finance_app(siv).rewriting_stored_file(|| journal.compact_until(date))
+            });
+
+            match compacted {
+                Ok(app) => {
+                    set_finance_app(siv, app);
+                    set_info_text(siv, "Journal compacted!");
+                }
+                Err(error) => set_info_text(siv, &error),
+            }
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn date_input(siv: &mut Cursive) -> Result<Date, String> {
+    siv.find_name::<EditView>(DATE_INPUT_NAME)
+        .expect("Couldn't find journal_date")
+        .get_content()
+        .parse::<Date>()
+        .map_err(|_| "Invalid date!".to_owned())
+}
+
+fn set_info_text(siv: &mut Cursive, text: &str) {
+    siv.call_on_name(INFO_TEXT_NAME, |view: &mut TextView| view.set_content(text));
+}
@@ -0,0 +1,229 @@
+use cursive::{
+    view::{Nameable, Resizable, Scrollable},
+    views::{Dialog, EditView, LinearLayout, Panel, SelectView, TextView},
+    Cursive, With,
+};
+use itertools::Itertools;
+use neo_budget::finance::{Finance, Price};
+use neo_budget::loans::Loan;
+use neo_budget::stats::{FinanceStats, LoanReport};
+
+use super::goals::parse_year_month;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const LOANS_SELECT_NAME: &str = "loans_select";
+const LOAN_NAME_INPUT_NAME: &str = "loan_name";
+const LOAN_PRINCIPAL_INPUT_NAME: &str = "loan_principal";
+const LOAN_RATE_INPUT_NAME: &str = "loan_rate";
+const LOAN_TERM_INPUT_NAME: &str = "loan_term";
+const LOAN_START_INPUT_NAME: &str = "loan_start";
+const LOAN_PRODUCT_INPUT_NAME: &str = "loan_product";
+const LOAN_INFO_TEXT_NAME: &str = "loan_info_text";
+
+/// Lists the loans with what is still owed, to show the payments of one.
+pub fn loans_view(finance: &Finance) -> Dialog {
+    let mut loans_select = SelectView::<String>::new().on_submit(|siv, loan_id: &String| {
+        let finance = get_finance_app(siv).finance();
+        if let Some(loan) = finance.loans().get(loan_id) {
+            siv.add_layer(loan_report_view(loan, &FinanceStats::new(finance.clone())));
+        }
+    });
+    set_loan_items(&mut loans_select, finance);
+
+    loans_select
+        .with_name(LOANS_SELECT_NAME)
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Loans"))
+        .wrap_with(Dialog::around)
+        .button("Add loan", |siv| {
+            siv.add_layer(add_loan_view());
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn set_loan_items(loans_select: &mut SelectView<String>, finance: &Finance) {
+    let stats = FinanceStats::new(finance.clone());
+
+    loans_select.clear();
+    for loan in finance
+        .loans()
+        .into_values()
+        .sorted_by(|left, right| left.id().cmp(right.id()))
+    {
+        let report = stats.loan_report(&loan);
+        loans_select.add_item(
+            format!(
+                "{}: {:.2} of {:.2} left",
+                loan.id(),
+                report.remaining_balance,
+                loan.principal
+            ),
+            loan.id().to_owned(),
+        );
+    }
+}
+
+fn loan_report_view(loan: &Loan, stats: &FinanceStats) -> Dialog {
+    TextView::new(loan_report_text(loan, &stats.loan_report(loan)))
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title(format!("Loan {}", loan.id())))
+        .wrap_with(Dialog::around)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn add_loan_view() -> Dialog {
+    let field = |label: &str, name: &str| {
+        LinearLayout::vertical()
+            .child(TextView::new(label))
+            .child(EditView::new().with_name(name).fixed_width(20))
+    };
+
+    LinearLayout::vertical()
+        .child(field("Loan name", LOAN_NAME_INPUT_NAME))
+        .child(field("Principal", LOAN_PRINCIPAL_INPUT_NAME))
+        .child(field("Yearly rate (%)", LOAN_RATE_INPUT_NAME))
+        .child(field("Term (months)", LOAN_TERM_INPUT_NAME))
+        .child(field("First payment (YYYY-MM)", LOAN_START_INPUT_NAME))
+        .child(field("Payment product", LOAN_PRODUCT_INPUT_NAME))
+        .child(TextView::empty().with_name(LOAN_INFO_TEXT_NAME))
+        .wrap_with(Dialog::around)
+        .button("Ok", submit_loan)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn submit_loan(siv: &mut Cursive) {
+    let input = |siv: &mut Cursive, name: &str| {
+        siv.find_name::<EditView>(name)
+            .expect("Couldn't find loan input")
+            .get_content()
+            .trim()
+            .to_owned()
+    };
+
+    let name = input(siv, LOAN_NAME_INPUT_NAME);
+    let principal = input(siv, LOAN_PRINCIPAL_INPUT_NAME).parse::<Price>();
+    let rate = input(siv, LOAN_RATE_INPUT_NAME).parse::<f64>();
+    let term = input(siv, LOAN_TERM_INPUT_NAME).parse::<u32>();
+    let start = parse_year_month(&input(siv, LOAN_START_INPUT_NAME));
+    let product = input(siv, LOAN_PRODUCT_INPUT_NAME);
+
+    let app = get_finance_app(siv);
+    let finance = app.finance();
+
+    let info = match (
+        name.as_str(),
+        principal,
+        rate,
+        term,
+        start,
+        product.as_str(),
+    ) {
+        ("", ..) => "Loan name can't be empty!".to_owned(),
+        (name, ..) if finance.loans().contains_key(name) => "Loan already exists!".to_owned(),
+        (.., "") => "Payment product can't be empty!".to_owned(),
+
+        (name, Ok(principal), Ok(rate), Ok(term), Some(start), product)
+            if principal > 0.0 && rate >= 0.0 && term > 0 =>
+        {
+            let loan = Loan::new(name, principal, rate, term, start, product);
+            let description = format!(
+                "Added loan {name}: {principal:.2}, {:.2}/month",
+                loan.monthly_payment()
+            );
+            let finance = finance.with_loan(&loan);
+            set_finance_app(siv, app.with_edit(&description, finance.clone()));
+            siv.call_on_name(LOANS_SELECT_NAME, |loans: &mut SelectView<String>| {
+                set_loan_items(loans, &finance)
+            });
+
+            format!("{description}.")
+        }
+
+        _ => "Invalid input!".to_owned(),
+    };
+
+    siv.call_on_name(LOAN_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+/// The recorded payments next to the scheduled ones, then the interest paid
+/// per year.
+fn loan_report_text(loan: &Loan, report: &LoanReport) -> String {
+    let payment_lines = report.payments.iter().map(|payment| {
+        let scheduled = match &payment.scheduled {
+            Some(entry) => format!(" (scheduled {}: {:.2})", entry.year_month, entry.payment),
+            None => " (past the schedule)".to_owned(),
+        };
+
+        format!(
+            "{}: paid {:.2}, interest {:.2}, owed {:.2}{scheduled}",
+            payment.year_month, payment.amount, payment.interest, payment.balance
+        )
+    });
+
+    let interest_lines = report
+        .interest_by_year
+        .iter()
+        .map(|(year, interest)| format!("Interest in {year}: {interest:.2}"));
+
+    let summary = format!(
+        "{:.2} at {}% over {} months, {:.2}/month. Owed {:.2}.",
+        loan.principal,
+        loan.annual_rate,
+        loan.term_months,
+        loan.monthly_payment(),
+        report.remaining_balance
+    );
+
+    [summary, "".to_owned()]
+        .into_iter()
+        .chain(payment_lines)
+        .chain(interest_lines)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::finance::{Finance, FinanceLog};
+    use neo_budget::loans::Loan;
+    use neo_budget::stats::FinanceStats;
+
+    use super::loan_report_text;
+
+    #[test]
+    fn test_loan_report_text() {
+        let loan = Loan::new(
+            "phone",
+            200.0,
+            0.0,
+            2,
+            YearMonth::new(2024, Month::May),
+            "phone",
+        );
+        let finance = Finance::new()
+            .with_log(FinanceLog::new(
+                "phone",
+                100.0,
+                YearMonth::new(2024, Month::May),
+            ))
+            .with_loan(&loan);
+
+        let report = FinanceStats::new(finance).loan_report(&loan);
+
+        assert_eq!(
+            loan_report_text(&loan, &report),
+            "200.00 at 0% over 2 months, 100.00/month. Owed 100.00.\n\n\
+             2024-05: paid 100.00, interest 0.00, owed 100.00 (scheduled 2024-05: 100.00)\n\
+             Interest in 2024: 0.00"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use cursive::{
+    view::Scrollable,
+    views::{Dialog, Panel, TextView},
+    With,
+};
+use neo_budget::finance::{Finance, FinanceLog};
+
+pub fn logs_view(finance: &Finance) -> Dialog {
+    let text = match finance.logs.is_empty() {
+        true => "No logs yet.".to_owned(),
+        false => finance
+            .logs
+            .iter()
+            .rev()
+            .map(log_text)
+            .collect::<Vec<String>>()
+            .join("\n"),
+    };
+
+    TextView::new(text)
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Logs, newest first"))
+        .wrap_with(Dialog::around)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+/// Describes a log on one line, with its optional details after the price.
+pub fn log_text(log: &FinanceLog) -> String {
+    let details = [
+        log.installments
+            .map(|installments| format!("in {installments} installments")),
+        log.quantity
+            .zip(log.unit.as_ref())
+            .map(|(quantity, unit)| format!("for {quantity} {unit}")),
+        log.payee.as_ref().map(|payee| format!("at {payee}")),
+        log.location
+            .as_ref()
+            .map(|location| format!("in {location}")),
+        log.account
+            .as_ref()
+            .map(|account| format!("from {account}")),
+        (!log.tags.is_empty()).then(|| {
+            log.tags
+                .iter()
+                .map(|tag| format!("#{tag}"))
+                .collect::<Vec<String>>()
+                .join(" ")
+        }),
+        log.note.as_ref().map(|note| format!("- {note}")),
+    ];
+
+    [format!(
+        "{} {} {:.2}",
+        log.year_month, log.product, log.price
+    )]
+    .into_iter()
+    .chain(details.into_iter().flatten())
+    .collect::<Vec<String>>()
+    .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::finance::FinanceLog;
+
+    use super::log_text;
+
+    #[test]
+    fn test_log_text() {
+        let year_month = YearMonth::new(2024, Month::September);
+        let log = FinanceLog {
+            location: Some("Lisbon".to_owned()),
+            note: Some("birthday cake".to_owned()),
+            ..FinanceLog::new("cake", 12.0, year_month)
+                .with_payee("Bakery")
+                .with_tag("family")
+        };
+
+        assert_eq!(
+            log_text(&log),
+            "2024-09 cake 12.00 at Bakery in Lisbon #family - birthday cake"
+        );
+        assert_eq!(
+            log_text(&FinanceLog::new("bus", 2.0, year_month)),
+            "2024-09 bus 2.00"
+        );
+    }
+}
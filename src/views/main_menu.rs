@@ -1,73 +1,221 @@
 use std::collections::HashMap;
 
 use cursive::view::IntoBoxedView;
-use cursive::views::{Panel, SelectView};
+use cursive::views::{LinearLayout, Panel, SelectView};
 use cursive::{View, With};
 use gregorian::YearMonth;
-use neo_budget::repository::FinanceRepository;
 use neo_budget::stats::{FinanceStats, GroupedTotals};
 
+use super::accounts::accounts_view;
 use super::add_logs::add_log_view;
 use super::add_products::add_products_view;
+use super::add_transfer::add_transfer_view;
+use super::alerts::alerts_view;
+use super::attachments::attachments_view;
+use super::balances::balances_view;
+use super::budget_line::budget_line_view;
+use super::category_tree::category_tree_view;
+use super::envelopes::envelopes_view;
+use super::goals::goals_view;
+use super::history::history_view;
+use super::journal::journal_view;
+use super::loans::loans_view;
+use super::logs::logs_view;
+use super::recurring::recurring_view;
+use super::reimbursements::reimbursements_view;
 use super::save::save_view;
-use super::show_logs::show_grouped_totals_view;
-use crate::siv::get_finance_app;
+use super::show_logs::show_period_totals_view;
+use super::split_logs::split_logs_view;
+use super::status_line::{set_status, status_line_view};
+use super::subscriptions::subscriptions_view;
+use super::taxes::taxes_view;
+use super::unit_prices::unit_prices_view;
+use crate::category_separator_from_env;
+use crate::siv::{get_finance_app, save_finance_app};
 
 enum MenuSelection {
     AddLog,
+    Logs,
+    SplitLogs,
+    Attachments,
     AddProducts,
+    AddTransfer,
+    Recurring,
+    Reimbursements,
+    Subscriptions,
+    Taxes,
+    Alerts,
     ViewProductTotals,
     ViewCategoryTotals,
+    ViewTagTotals,
+    ViewPayeeTotals,
+    UnitPrices,
+    Accounts,
+    Goals,
+    Envelopes,
+    Loans,
+    SharedBalances,
+    History,
+    Journal,
     Save,
 }
 
 pub fn main_menu_view() -> Box<dyn View> {
     SelectView::<MenuSelection>::new()
         .item("Add log", MenuSelection::AddLog)
+        .item("Logs", MenuSelection::Logs)
+        .item("Split logs", MenuSelection::SplitLogs)
+        .item("Attachments", MenuSelection::Attachments)
         .item("Add products", MenuSelection::AddProducts)
+        .item("Add transfer", MenuSelection::AddTransfer)
+        .item("Recurring logs", MenuSelection::Recurring)
+        .item("Reimbursements", MenuSelection::Reimbursements)
+        .item("Subscriptions", MenuSelection::Subscriptions)
+        .item("Taxes", MenuSelection::Taxes)
+        .item("Alerts", MenuSelection::Alerts)
         .item("Product totals", MenuSelection::ViewProductTotals)
         .item("Category totals", MenuSelection::ViewCategoryTotals)
+        .item("Tag totals", MenuSelection::ViewTagTotals)
+        .item("Payee totals", MenuSelection::ViewPayeeTotals)
+        .item("Unit prices", MenuSelection::UnitPrices)
+        .item("Accounts", MenuSelection::Accounts)
+        .item("Savings goals", MenuSelection::Goals)
+        .item("Envelopes", MenuSelection::Envelopes)
+        .item("Loans", MenuSelection::Loans)
+        .item("Shared balances", MenuSelection::SharedBalances)
+        .item("History (u: undo, r: redo)", MenuSelection::History)
+        .item("Journal", MenuSelection::Journal)
         .item("Save", MenuSelection::Save)
         .on_submit(|siv, selection| {
             let finance_app = get_finance_app(siv);
 
             let finance = finance_app.finance();
-            let finance_repo = finance_app.finance_repo();
 
-            let stats = FinanceStats::new(finance.clone());
+            let stats = FinanceStats::new(finance.clone())
+                .with_category_separator(&category_separator_from_env());
 
             match selection {
                 MenuSelection::AddLog => {
                     siv.add_layer(add_log_view());
                 }
 
+                MenuSelection::Logs => {
+                    siv.add_layer(logs_view(&finance));
+                }
+
+                MenuSelection::SplitLogs => {
+                    siv.add_layer(split_logs_view(&finance));
+                }
+
+                MenuSelection::Attachments => {
+                    siv.add_layer(attachments_view(&finance));
+                }
+
                 MenuSelection::AddProducts => {
                     siv.add_layer(add_products_view());
                 }
 
+                MenuSelection::AddTransfer => match finance.accounts().len() {
+                    0 | 1 => set_status(siv, "Add two accounts to transfer between them."),
+                    _ => siv.add_layer(add_transfer_view(&finance)),
+                },
+
+                MenuSelection::Recurring => {
+                    siv.add_layer(recurring_view(&finance));
+                }
+
+                MenuSelection::Reimbursements => {
+                    siv.add_layer(reimbursements_view(&finance));
+                }
+
+                MenuSelection::Subscriptions => {
+                    siv.add_layer(subscriptions_view(&finance));
+                }
+
+                MenuSelection::Taxes => {
+                    siv.add_layer(taxes_view(&finance));
+                }
+
+                MenuSelection::Alerts => {
+                    siv.add_layer(alerts_view(&finance));
+                }
+
                 MenuSelection::ViewProductTotals => {
-                    let labeled_logs =
-                        year_month_totals_display(stats.product_totals_by_year_month());
-                    siv.add_layer(show_grouped_totals_view(labeled_logs.clone()));
+                    siv.add_layer(show_period_totals_view(
+                        stats.product_totals_by_year_month(),
+                        finance_app.fiscal_year_start(),
+                    ));
                 }
 
                 MenuSelection::ViewCategoryTotals => {
-                    let labeled_logs =
-                        year_month_totals_display(stats.category_totals_by_year_month());
-                    siv.add_layer(show_grouped_totals_view(labeled_logs.clone()));
+                    siv.add_layer(category_tree_view(&stats));
+                }
+
+                MenuSelection::ViewTagTotals => {
+                    siv.add_layer(show_period_totals_view(
+                        stats.tag_totals_by_year_month(),
+                        finance_app.fiscal_year_start(),
+                    ));
                 }
 
+                MenuSelection::ViewPayeeTotals => {
+                    siv.add_layer(show_period_totals_view(
+                        stats.payee_totals_by_year_month(),
+                        finance_app.fiscal_year_start(),
+                    ));
+                }
+
+                MenuSelection::UnitPrices => {
+                    siv.add_layer(unit_prices_view(&finance));
+                }
+
+                MenuSelection::Accounts => {
+                    siv.add_layer(accounts_view(&finance));
+                }
+
+                MenuSelection::Goals => {
+                    siv.add_layer(goals_view(&finance));
+                }
+
+                MenuSelection::Envelopes => {
+                    siv.add_layer(envelopes_view(&finance));
+                }
+
+                MenuSelection::Loans => {
+                    siv.add_layer(loans_view(&finance));
+                }
+
+                MenuSelection::SharedBalances => {
+                    siv.add_layer(balances_view(&finance));
+                }
+
+                MenuSelection::History => {
+                    siv.add_layer(history_view(&finance_app.history()));
+                }
+
+                MenuSelection::Journal => match finance_app.finance_repo().journal() {
+                    Some(journal) => siv.add_layer(journal_view(journal.clone())),
+                    None => set_status(siv, "Set FINANCE_FILE_FORMAT=journal to use the journal."),
+                },
+
                 MenuSelection::Save => {
-                    finance_repo.save(&finance).unwrap(); // TODO handle error
-                    siv.add_layer(save_view());
+                    if save_finance_app(siv).is_ok() {
+                        siv.add_layer(save_view());
+                    }
                 }
             }
         })
         .wrap_with(|view| Panel::new(view).title("NeoBudget - Main Menu"))
+        .wrap_with(|view| {
+            LinearLayout::vertical()
+                .child(view)
+                .child(budget_line_view())
+                .child(status_line_view())
+        })
         .into_boxed_view()
 }
 
-fn year_month_totals_display(
+pub fn year_month_totals_display(
     labeled_totals_by_year_month: HashMap<YearMonth, GroupedTotals>,
 ) -> HashMap<String, GroupedTotals> {
     let mut labeled_logs = HashMap::new();
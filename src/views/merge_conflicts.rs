@@ -0,0 +1,48 @@
+use cursive::{
+    view::{Nameable, Scrollable},
+    views::{Dialog, LinearLayout, SelectView, TextView},
+    Cursive,
+};
+use neo_budget::merge::{MergeConflict, MergeSide};
+
+use crate::siv::resolve_merge_finance_app;
+
+pub fn merge_conflicts_view(conflicts: Vec<MergeConflict>) -> Dialog {
+    let mut layout = LinearLayout::vertical();
+    for (index, conflict) in conflicts.iter().enumerate() {
+        layout.add_child(TextView::new(conflict.to_string()));
+        layout.add_child(
+            SelectView::<MergeSide>::new()
+                .popup()
+                .item("Keep ours", MergeSide::Ours)
+                .item("Take theirs", MergeSide::Theirs)
+                .with_name(conflict_select_name(index)),
+        );
+    }
+
+    Dialog::around(layout.scrollable())
+        .title("Merge conflicts")
+        .button("Merge", move |siv| {
+            let resolutions = conflicts
+                .iter()
+                .enumerate()
+                .map(|(index, conflict)| (conflict.clone(), chosen_side(siv, index)))
+                .collect();
+
+            siv.pop_layer();
+            resolve_merge_finance_app(siv, resolutions);
+        })
+        .button("Cancel", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn chosen_side(siv: &mut Cursive, index: usize) -> MergeSide {
+    siv.find_name::<SelectView<MergeSide>>(&conflict_select_name(index))
+        .and_then(|select_view| select_view.selection())
+        .map_or(MergeSide::Ours, |side| *side)
+}
+
+fn conflict_select_name(index: usize) -> String {
+    format!("merge_conflict_{index}")
+}
@@ -1,5 +1,28 @@
+pub mod accounts;
 pub mod add_logs;
 pub mod add_products;
+pub mod add_transfer;
+pub mod alerts;
+pub mod attachments;
+pub mod balances;
+pub mod budget_line;
+pub mod category_tree;
+pub mod envelopes;
+pub mod external_changes;
+pub mod goals;
+pub mod history;
+pub mod journal;
+pub mod loans;
+pub mod logs;
 pub mod main_menu;
+pub mod merge_conflicts;
+pub mod passphrase;
+pub mod recurring;
+pub mod reimbursements;
 pub mod save;
 pub mod show_logs;
+pub mod split_logs;
+pub mod status_line;
+pub mod subscriptions;
+pub mod taxes;
+pub mod unit_prices;
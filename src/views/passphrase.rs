@@ -0,0 +1,73 @@
+use cursive::{
+    view::{Nameable, Resizable},
+    views::{Dialog, EditView, LinearLayout, TextView},
+    Cursive,
+};
+use neo_budget::repository::EnvFinanceRepository;
+
+const PASSPHRASE_INPUT_NAME: &str = "passphrase";
+const CONFIRMATION_INPUT_NAME: &str = "passphrase_confirmation";
+const INFO_TEXT_NAME: &str = "passphrase_info_text";
+
+/// Asks for the passphrase of the encrypted data file, calling `on_unlock`
+/// with the unlocked repository once it's right. If the file doesn't exist
+/// yet, the new passphrase is asked twice.
+pub fn passphrase_view(on_unlock: impl Fn(&mut Cursive, EnvFinanceRepository) + 'static) -> Dialog {
+    let creating = EnvFinanceRepository::requires_new_passphrase_from_env();
+
+    let secret_input = |name: &str| EditView::new().secret().with_name(name).fixed_width(30);
+
+    let mut layout = LinearLayout::vertical()
+        .child(TextView::new(match creating {
+            true => "New passphrase",
+            false => "Passphrase",
+        }))
+        .child(secret_input(PASSPHRASE_INPUT_NAME));
+    if creating {
+        layout = layout
+            .child(TextView::new("Repeat passphrase"))
+            .child(secret_input(CONFIRMATION_INPUT_NAME));
+    }
+    layout = layout.child(TextView::empty().with_name(INFO_TEXT_NAME));
+
+    Dialog::around(layout)
+        .title(match creating {
+            true => "Create encrypted NeoBudget file",
+            false => "Unlock NeoBudget",
+        })
+        .button(
+            match creating {
+                true => "Create",
+                false => "Unlock",
+            },
+            move |siv| {
+                let input = |siv: &mut Cursive, name: &str| {
+                    siv.find_name::<EditView>(name)
+                        .map(|input| input.get_content().to_string())
+                };
+
+                let passphrase =
+                    input(siv, PASSPHRASE_INPUT_NAME).expect("Couldn't find passphrase");
+                let finance_repo = match input(siv, CONFIRMATION_INPUT_NAME) {
+                    Some(confirmation) => {
+                        EnvFinanceRepository::create_encrypted_from_env(&passphrase, &confirmation)
+                    }
+                    None => EnvFinanceRepository::from_env_with_passphrase(Some(&passphrase)),
+                };
+
+                match finance_repo {
+                    Ok(finance_repo) => {
+                        siv.pop_layer();
+                        on_unlock(siv, finance_repo);
+                    }
+
+                    Err(error) => {
+                        siv.call_on_name(INFO_TEXT_NAME, |view: &mut TextView| {
+                            view.set_content(error)
+                        });
+                    }
+                }
+            },
+        )
+        .button("Quit", |siv| siv.quit())
+}
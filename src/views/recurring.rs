@@ -0,0 +1,255 @@
+use cursive::{
+    view::{Nameable, Resizable, Scrollable},
+    views::{Dialog, EditView, LinearLayout, Panel, SelectView, TextView},
+    Cursive, With,
+};
+use gregorian::Date;
+use itertools::Itertools;
+use neo_budget::finance::{Finance, Price};
+use neo_budget::recurring::{RecurrenceInterval, RecurringLog};
+
+use super::status_line::set_status;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const RECURRING_TEXT_NAME: &str = "recurring_text";
+const RECURRING_PRODUCT_INPUT_NAME: &str = "recurring_product";
+const RECURRING_PRICE_INPUT_NAME: &str = "recurring_price";
+const RECURRING_INTERVAL_INPUT_NAME: &str = "recurring_interval";
+const RECURRING_START_INPUT_NAME: &str = "recurring_start";
+const RECURRING_END_INPUT_NAME: &str = "recurring_end";
+const RECURRING_ACCOUNT_INPUT_NAME: &str = "recurring_account";
+const RECURRING_INFO_TEXT_NAME: &str = "recurring_info_text";
+
+pub fn recurring_view(finance: &Finance) -> Dialog {
+    TextView::new(recurring_text(finance, Date::today()))
+        .with_name(RECURRING_TEXT_NAME)
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Recurring logs"))
+        .wrap_with(Dialog::around)
+        .button("Add due logs", |siv| {
+            let app = get_finance_app(siv);
+            let finance = app.finance();
+            let materialized = finance.clone().materialize_due(Date::today());
+            let added_logs = materialized.logs.len() - finance.logs.len();
+
+            match added_logs {
+                0 => set_status(siv, "No recurring logs are due."),
+                _ => {
+                    set_finance_app(
+                        siv,
+                        app.with_edit(&format!("Added {added_logs} recurring logs"), materialized),
+                    );
+                    reload_recurring_text(siv);
+                }
+            }
+        })
+        .button("Add recurring", |siv| {
+            siv.add_layer(add_recurring_view());
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn add_recurring_view() -> Dialog {
+    LinearLayout::vertical()
+        .child(TextView::new("Product name"))
+        .child(
+            EditView::new()
+                .with_name(RECURRING_PRODUCT_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::new("Price"))
+        .child(
+            EditView::new()
+                .with_name(RECURRING_PRICE_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::new("Every"))
+        .child(
+            SelectView::<RecurrenceInterval>::new()
+                .popup()
+                .item("Month", RecurrenceInterval::Monthly)
+                .item("Week", RecurrenceInterval::Weekly)
+                .item("Year", RecurrenceInterval::Yearly)
+                .with_name(RECURRING_INTERVAL_INPUT_NAME),
+        )
+        .child(TextView::new("Start (YYYY-MM-DD)"))
+        .child(
+            EditView::new()
+                .with_name(RECURRING_START_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::new("End (optional)"))
+        .child(
+            EditView::new()
+                .with_name(RECURRING_END_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::new("Account (optional)"))
+        .child(
+            EditView::new()
+                .with_name(RECURRING_ACCOUNT_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::empty().with_name(RECURRING_INFO_TEXT_NAME))
+        .wrap_with(Dialog::around)
+        .button("Ok", submit_recurring)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn submit_recurring(siv: &mut Cursive) {
+    let input = |siv: &mut Cursive, name: &str| {
+        siv.find_name::<EditView>(name)
+            .expect("Couldn't find recurring input")
+            .get_content()
+            .trim()
+            .to_owned()
+    };
+
+    let product = input(siv, RECURRING_PRODUCT_INPUT_NAME);
+    let price = input(siv, RECURRING_PRICE_INPUT_NAME).parse::<Price>();
+    let start = input(siv, RECURRING_START_INPUT_NAME).parse::<Date>();
+    let end = match input(siv, RECURRING_END_INPUT_NAME).as_str() {
+        "" => Ok(None),
+        end => end.parse::<Date>().map(Some),
+    };
+    let account = input(siv, RECURRING_ACCOUNT_INPUT_NAME);
+
+    let interval = siv
+        .find_name::<SelectView<RecurrenceInterval>>(RECURRING_INTERVAL_INPUT_NAME)
+        .expect("Couldn't find recurring_interval")
+        .selection()
+        .map_or(RecurrenceInterval::Monthly, |interval| *interval);
+
+    let app = get_finance_app(siv);
+    let finance = app.finance();
+    let is_known_account = account.is_empty() || finance.accounts().contains_key(&account);
+
+    let info = match (product.as_str(), price, start, end) {
+        ("", _, _, _) => "Product name can't be empty!".to_owned(),
+        (_, _, _, _) if !is_known_account => "Unknown account!".to_owned(),
+
+        (_, Ok(price), Ok(start), Ok(end)) if end.is_none_or(|end| end >= start) => {
+            let recurring = RecurringLog {
+                end,
+                account: (!account.is_empty()).then_some(account),
+                ..RecurringLog::new(&product, price, interval, start)
+            };
+
+            let description = format!("Added recurring log {product}: {price:.2} from {start}");
+            set_finance_app(
+                siv,
+                app.with_edit(&description, finance.with_recurring(recurring)),
+            );
+            reload_recurring_text(siv);
+
+            format!("{description}.")
+        }
+
+        _ => "Invalid input!".to_owned(),
+    };
+
+    siv.call_on_name(RECURRING_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+fn reload_recurring_text(siv: &mut Cursive) {
+    let finance = get_finance_app(siv).finance();
+
+    siv.call_on_name(RECURRING_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(recurring_text(&finance, Date::today()))
+    });
+}
+
+/// Lists the occurrences due up to `today` that weren't added yet, then the
+/// next occurrence of every recurring log.
+fn recurring_text(finance: &Finance, today: Date) -> String {
+    let describe = |date: Date, recurring: &RecurringLog| {
+        format!("{date} {} {:.2}", recurring.product, recurring.price)
+    };
+
+    let overdue = finance
+        .recurring
+        .iter()
+        .flat_map(|recurring| {
+            recurring
+                .due_until(today)
+                .into_iter()
+                .map(move |date| (date, recurring))
+        })
+        .sorted_by_key(|(date, _)| *date)
+        .map(|(date, recurring)| describe(date, recurring))
+        .collect::<Vec<String>>();
+
+    let upcoming = finance
+        .recurring
+        .iter()
+        .filter_map(|recurring| {
+            let next_index = recurring.materialized + recurring.due_until(today).len();
+            recurring
+                .occurrence(next_index)
+                .map(|date| (date, recurring))
+        })
+        .sorted_by_key(|(date, _)| *date)
+        .map(|(date, recurring)| describe(date, recurring))
+        .collect::<Vec<String>>();
+
+    if finance.recurring.is_empty() {
+        return "No recurring logs yet.".to_owned();
+    }
+
+    let section = |title: &str, lines: Vec<String>| match lines.is_empty() {
+        true => format!("{title}\n(none)"),
+        false => format!("{title}\n{}", lines.join("\n")),
+    };
+
+    format!(
+        "{}\n\n{}",
+        section("Overdue", overdue),
+        section("Upcoming", upcoming)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::Date;
+    use neo_budget::finance::Finance;
+    use neo_budget::recurring::{RecurrenceInterval, RecurringLog};
+
+    use super::recurring_text;
+
+    #[test]
+    fn test_recurring_text() {
+        let finance = Finance::new()
+            .with_recurring(RecurringLog::new(
+                "rent",
+                500.0,
+                RecurrenceInterval::Monthly,
+                date(2024, 1, 1),
+            ))
+            .with_recurring(RecurringLog::new(
+                "insurance",
+                120.0,
+                RecurrenceInterval::Yearly,
+                date(2024, 3, 15),
+            ));
+
+        assert_eq!(
+            recurring_text(&finance, date(2024, 2, 10)),
+            "Overdue\n2024-01-01 rent 500.00\n2024-02-01 rent 500.00\n\n\
+             Upcoming\n2024-03-01 rent 500.00\n2024-03-15 insurance 120.00"
+        );
+
+        let materialized = finance.materialize_due(date(2024, 2, 10));
+        assert_eq!(materialized.logs.len(), 2);
+        assert!(recurring_text(&materialized, date(2024, 2, 10)).starts_with("Overdue\n(none)"));
+    }
+
+    fn date(year: i16, month: u8, day: u8) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+}
@@ -0,0 +1,265 @@
+use std::fs;
+
+use cursive::{
+    view::{Nameable, Resizable, Scrollable},
+    views::{Dialog, EditView, LinearLayout, Panel, SelectView, TextView},
+    Cursive, With,
+};
+use neo_budget::finance::{Finance, FinanceLog};
+use neo_budget::reimbursements::{
+    expense_report_csv, expense_report_markdown, Reimbursable, ReimbursementStatus,
+};
+use neo_budget::stats::FinanceStats;
+
+use super::goals::parse_year_month;
+use super::logs::log_text;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const REIMBURSEMENT_LOGS_NAME: &str = "reimbursement_logs";
+const REIMBURSEMENT_STATUS_NAME: &str = "reimbursement_status";
+const REIMBURSEMENT_INCOME_NAME: &str = "reimbursement_income";
+const REPORT_FROM_INPUT_NAME: &str = "report_from";
+const REPORT_TO_INPUT_NAME: &str = "report_to";
+const REPORT_FORMAT_NAME: &str = "report_format";
+const REPORT_PATH_INPUT_NAME: &str = "report_path";
+const REPORT_INFO_TEXT_NAME: &str = "report_info_text";
+
+#[derive(Clone, Copy)]
+enum ReportFormat {
+    Csv,
+    Markdown,
+}
+
+/// Lists the expenses with their reimbursement status, to change it.
+pub fn reimbursements_view(finance: &Finance) -> Dialog {
+    let mut log_select = SelectView::<usize>::new().on_submit(|siv, index: &usize| {
+        let finance = get_finance_app(siv).finance();
+        siv.add_layer(edit_reimbursement_view(&finance, *index));
+    });
+    set_log_items(&mut log_select, finance);
+
+    log_select
+        .with_name(REIMBURSEMENT_LOGS_NAME)
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Reimbursements"))
+        .wrap_with(Dialog::around)
+        .button("Expense report", |siv| {
+            siv.add_layer(expense_report_view());
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn set_log_items(log_select: &mut SelectView<usize>, finance: &Finance) {
+    log_select.clear();
+    for (index, log) in finance.logs.iter().enumerate().rev() {
+        if log.price > 0.0 {
+            log_select.add_item(reimbursement_text(log), index);
+        }
+    }
+}
+
+fn reimbursement_text(log: &FinanceLog) -> String {
+    let status = match &log.reimbursable {
+        Some(reimbursable) => match (reimbursable.status, reimbursable.reimbursed_by) {
+            (ReimbursementStatus::Pending, _) => " [pending]".to_owned(),
+            (ReimbursementStatus::Submitted, _) => " [submitted]".to_owned(),
+            (ReimbursementStatus::Reimbursed, Some(index)) => {
+                format!(" [reimbursed by log {}]", index + 1)
+            }
+            (ReimbursementStatus::Reimbursed, None) => " [reimbursed]".to_owned(),
+        },
+        None => String::new(),
+    };
+
+    format!("{}{status}", log_text(log))
+}
+
+/// Sets whether the log at `index` is reimbursable, its status, and the
+/// income that paid it back.
+fn edit_reimbursement_view(finance: &Finance, index: usize) -> Dialog {
+    let log = &finance.logs[index];
+    let current = log.reimbursable.as_ref();
+
+    let mut status_select = SelectView::<Option<ReimbursementStatus>>::new()
+        .popup()
+        .item("Not reimbursable", None)
+        .item("Pending", Some(ReimbursementStatus::Pending))
+        .item("Submitted", Some(ReimbursementStatus::Submitted))
+        .item("Reimbursed", Some(ReimbursementStatus::Reimbursed));
+    status_select.set_selection(match current.map(|reimbursable| reimbursable.status) {
+        None => 0,
+        Some(ReimbursementStatus::Pending) => 1,
+        Some(ReimbursementStatus::Submitted) => 2,
+        Some(ReimbursementStatus::Reimbursed) => 3,
+    });
+
+    let mut income_select = SelectView::<Option<usize>>::new()
+        .popup()
+        .item("(none)", None);
+    for (income_index, income) in finance.logs.iter().enumerate() {
+        if income.price < 0.0 {
+            income_select.add_item(log_text(income), Some(income_index));
+            if current.and_then(|reimbursable| reimbursable.reimbursed_by) == Some(income_index) {
+                income_select.set_selection(income_select.len() - 1);
+            }
+        }
+    }
+
+    LinearLayout::vertical()
+        .child(TextView::new("Status"))
+        .child(status_select.with_name(REIMBURSEMENT_STATUS_NAME))
+        .child(TextView::new("Reimbursed by"))
+        .child(income_select.with_name(REIMBURSEMENT_INCOME_NAME))
+        .wrap_with(|view| Panel::new(view).title(log_text(log)))
+        .wrap_with(Dialog::around)
+        .button("Save", move |siv| save_reimbursement(siv, index))
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn save_reimbursement(siv: &mut Cursive, index: usize) {
+    let status = siv
+        .find_name::<SelectView<Option<ReimbursementStatus>>>(REIMBURSEMENT_STATUS_NAME)
+        .and_then(|statuses| statuses.selection())
+        .and_then(|status| *status);
+    let income = siv
+        .find_name::<SelectView<Option<usize>>>(REIMBURSEMENT_INCOME_NAME)
+        .and_then(|incomes| incomes.selection())
+        .and_then(|income| *income);
+
+    let app = get_finance_app(siv);
+    let finance = app.finance();
+    let Some(log) = finance.logs.get(index).cloned() else {
+        return;
+    };
+
+    let reimbursable = status.map(|status| Reimbursable {
+        status,
+        reimbursed_by: income,
+    });
+    let description = match &reimbursable {
+        Some(_) => format!("Updated reimbursement of {}", log.product),
+        None => format!("Marked {} as not reimbursable", log.product),
+    };
+
+    let finance = finance.with_replaced_log(
+        index,
+        FinanceLog {
+            reimbursable,
+            ..log
+        },
+    );
+    set_finance_app(siv, app.with_edit(&description, finance.clone()));
+
+    siv.pop_layer();
+    siv.call_on_name(REIMBURSEMENT_LOGS_NAME, |logs: &mut SelectView<usize>| {
+        let selected = logs.selected_id();
+        set_log_items(logs, &finance);
+        if let Some(selected) = selected {
+            logs.set_selection(selected);
+        }
+    });
+}
+
+fn expense_report_view() -> Dialog {
+    let field = |label: &str, name: &str| {
+        LinearLayout::vertical()
+            .child(TextView::new(label))
+            .child(EditView::new().with_name(name).fixed_width(30))
+    };
+
+    LinearLayout::vertical()
+        .child(field("From (YYYY-MM)", REPORT_FROM_INPUT_NAME))
+        .child(field("To (YYYY-MM)", REPORT_TO_INPUT_NAME))
+        .child(TextView::new("Format"))
+        .child(
+            SelectView::<ReportFormat>::new()
+                .popup()
+                .item("CSV", ReportFormat::Csv)
+                .item("Markdown", ReportFormat::Markdown)
+                .with_name(REPORT_FORMAT_NAME),
+        )
+        .child(field("Save to file", REPORT_PATH_INPUT_NAME))
+        .child(TextView::empty().with_name(REPORT_INFO_TEXT_NAME))
+        .wrap_with(|view| Panel::new(view).title("Pending expenses report"))
+        .wrap_with(Dialog::around)
+        .button("Export", export_report)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn export_report(siv: &mut Cursive) {
+    let input = |siv: &mut Cursive, name: &str| {
+        siv.find_name::<EditView>(name)
+            .expect("Couldn't find report input")
+            .get_content()
+            .trim()
+            .to_owned()
+    };
+
+    let from = parse_year_month(&input(siv, REPORT_FROM_INPUT_NAME));
+    let to = parse_year_month(&input(siv, REPORT_TO_INPUT_NAME));
+    let path = input(siv, REPORT_PATH_INPUT_NAME);
+    let format = siv
+        .find_name::<SelectView<ReportFormat>>(REPORT_FORMAT_NAME)
+        .and_then(|formats| formats.selection())
+        .map_or(ReportFormat::Csv, |format| *format);
+
+    let stats = FinanceStats::new(get_finance_app(siv).finance());
+
+    let info = match (from, to, path.as_str()) {
+        (_, _, "") => "File can't be empty!".to_owned(),
+
+        (Some(from), Some(to), path) if from <= to => {
+            let logs = stats.pending_reimbursements(from, to);
+            let report = match format {
+                ReportFormat::Csv => expense_report_csv(&logs),
+                ReportFormat::Markdown => expense_report_markdown(&logs),
+            };
+
+            match fs::write(path, report) {
+                Ok(()) => format!("Wrote {} pending expenses to {path}.", logs.len()),
+                Err(_) => format!("Couldn't write to file {path}!"),
+            }
+        }
+
+        _ => "Invalid months!".to_owned(),
+    };
+
+    siv.call_on_name(REPORT_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::finance::FinanceLog;
+    use neo_budget::reimbursements::{Reimbursable, ReimbursementStatus};
+
+    use super::reimbursement_text;
+
+    #[test]
+    fn test_reimbursement_text() {
+        let log = FinanceLog::new("hotel", 80.0, YearMonth::new(2024, Month::May));
+
+        assert_eq!(reimbursement_text(&log), "2024-05 hotel 80.00");
+        assert_eq!(
+            reimbursement_text(
+                &log.clone()
+                    .with_reimbursable(Reimbursable::new(ReimbursementStatus::Pending))
+            ),
+            "2024-05 hotel 80.00 [pending]"
+        );
+        assert_eq!(
+            reimbursement_text(&log.with_reimbursable(
+                Reimbursable::new(ReimbursementStatus::Reimbursed).with_reimbursed_by(2)
+            )),
+            "2024-05 hotel 80.00 [reimbursed by log 3]"
+        );
+    }
+}
@@ -1,35 +1,165 @@
 use std::collections::HashMap;
 
 use cursive::{
-    view::Nameable,
-    views::{Dialog, LinearLayout, ListView, Panel, SelectView, TextView},
+    view::{Nameable, Resizable},
+    views::{Dialog, EditView, LinearLayout, ListView, Panel, SelectView, TextView},
+    Cursive, With,
 };
+use gregorian::{Month, YearMonth};
 use im::Vector;
 use itertools::Itertools;
-use neo_budget::stats::GroupedTotals;
+use neo_budget::periods::{Period, PeriodGranularity};
+use neo_budget::stats::{totals_by_period, totals_in, GroupedTotals};
 
-type LogCollection = HashMap<String, GroupedTotals>;
+use super::goals::parse_year_month;
 
-pub fn show_grouped_totals_view(log_collection: LogCollection) -> Dialog {
-    let mut log_list = ListView::new();
-    reload_logs_list(&mut log_list, log_collection.values().next().cloned());
+type LogCollection = HashMap<String, GroupedTotals>;
 
-    let (label_select_view, label_select_view_name) = build_select_view(log_collection);
+const LABEL_SELECT_VIEW_NAME: &str = "label_select";
+const LOG_LIST_VIEW_NAME: &str = "log_list";
+const RANGE_FROM_INPUT_NAME: &str = "range_from";
+const RANGE_TO_INPUT_NAME: &str = "range_to";
+const RANGE_INFO_TEXT_NAME: &str = "range_info_text";
 
-    Dialog::around(
-        LinearLayout::horizontal()
-            .child(Panel::new(label_select_view))
-            .child(Panel::new(log_list.with_name(label_select_view_name))),
-    )
-    .button("Back", |siv| {
+pub fn show_grouped_totals_view(log_collection: LogCollection) -> Dialog {
+    Dialog::around(grouped_totals_layout(log_collection)).button("Back", |siv| {
         siv.pop_layer();
     })
 }
 
-fn build_select_view(log_collection: HashMap<String, GroupedTotals>) -> (SelectView, String) {
-    const LOG_LIST_VIEW_NAME: &str = "log_list";
+/// Monthly totals that can be regrouped by quarter, year or fiscal year, or
+/// added up over a custom range of months.
+pub fn show_period_totals_view(
+    totals_by_year_month: HashMap<YearMonth, GroupedTotals>,
+    fiscal_year_start: Month,
+) -> Dialog {
+    let granularity_totals = totals_by_year_month.clone();
+    let granularity_select = SelectView::<PeriodGranularity>::new()
+        .popup()
+        .item("Month", PeriodGranularity::Month)
+        .item("Quarter", PeriodGranularity::Quarter)
+        .item("Year", PeriodGranularity::Year)
+        .item(
+            "Fiscal year",
+            PeriodGranularity::FiscalYear {
+                start_month: fiscal_year_start,
+            },
+        )
+        .on_submit(move |siv, granularity: &PeriodGranularity| {
+            show_log_collection(
+                siv,
+                period_totals_display(&granularity_totals, *granularity),
+            );
+        });
+
+    let log_collection = period_totals_display(&totals_by_year_month, PeriodGranularity::Month);
+
+    LinearLayout::vertical()
+        .child(
+            LinearLayout::horizontal()
+                .child(TextView::new("Group by "))
+                .child(granularity_select),
+        )
+        .child(grouped_totals_layout(log_collection))
+        .wrap_with(Dialog::around)
+        .button("Custom range", move |siv| {
+            siv.add_layer(custom_range_view(totals_by_year_month.clone()));
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+/// Labels each period's totals with the period.
+fn period_totals_display(
+    totals_by_year_month: &HashMap<YearMonth, GroupedTotals>,
+    granularity: PeriodGranularity,
+) -> LogCollection {
+    totals_by_period(totals_by_year_month.clone(), granularity)
+        .into_iter()
+        .map(|(period, totals)| (period.to_string(), totals))
+        .collect()
+}
+
+fn custom_range_view(totals_by_year_month: HashMap<YearMonth, GroupedTotals>) -> Dialog {
+    let input = |label: &str, name: &str| {
+        LinearLayout::vertical()
+            .child(TextView::new(label))
+            .child(EditView::new().with_name(name).fixed_width(20))
+    };
+
+    LinearLayout::vertical()
+        .child(input("From (YYYY-MM)", RANGE_FROM_INPUT_NAME))
+        .child(input("To (YYYY-MM)", RANGE_TO_INPUT_NAME))
+        .child(TextView::empty().with_name(RANGE_INFO_TEXT_NAME))
+        .wrap_with(Dialog::around)
+        .button("Ok", move |siv| {
+            let month_input = |siv: &mut Cursive, name: &str| {
+                siv.find_name::<EditView>(name)
+                    .and_then(|input| parse_year_month(input.get_content().trim()))
+            };
+
+            match (
+                month_input(siv, RANGE_FROM_INPUT_NAME),
+                month_input(siv, RANGE_TO_INPUT_NAME),
+            ) {
+                (Some(from), Some(to)) if from <= to => {
+                    let period = Period::Range { from, to };
+                    let totals = totals_in(&totals_by_year_month, &period);
+
+                    siv.pop_layer();
+                    show_log_collection(siv, HashMap::from([(period.to_string(), totals)]));
+                }
+
+                _ => {
+                    siv.call_on_name(RANGE_INFO_TEXT_NAME, |view: &mut TextView| {
+                        view.set_content("Invalid months!")
+                    });
+                }
+            }
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn grouped_totals_layout(log_collection: LogCollection) -> LinearLayout {
+    let mut log_list = ListView::new();
+    reload_logs_list(&mut log_list, first_totals(&log_collection));
 
     let mut label_select_view = SelectView::new();
+    set_label_items(&mut label_select_view, log_collection);
+
+    LinearLayout::horizontal()
+        .child(Panel::new(
+            label_select_view.with_name(LABEL_SELECT_VIEW_NAME),
+        ))
+        .child(Panel::new(log_list.with_name(LOG_LIST_VIEW_NAME)))
+}
+
+/// Replaces the labels and totals shown by the grouped totals view on top.
+fn show_log_collection(siv: &mut Cursive, log_collection: LogCollection) {
+    let first = first_totals(&log_collection);
+
+    siv.call_on_name(
+        LABEL_SELECT_VIEW_NAME,
+        |label_select_view: &mut SelectView| set_label_items(label_select_view, log_collection),
+    );
+    siv.call_on_name(LOG_LIST_VIEW_NAME, |log_list: &mut ListView| {
+        reload_logs_list(log_list, first)
+    });
+}
+
+fn first_totals(log_collection: &LogCollection) -> Option<GroupedTotals> {
+    log_collection
+        .keys()
+        .min()
+        .and_then(|label| log_collection.get(label))
+        .cloned()
+}
+
+fn set_label_items(label_select_view: &mut SelectView, log_collection: LogCollection) {
+    label_select_view.clear();
     for label in log_collection.keys().sorted() {
         label_select_view.add_item(label.clone(), label.clone());
     }
@@ -41,7 +171,6 @@ fn build_select_view(log_collection: HashMap<String, GroupedTotals>) -> (SelectV
 
         reload_logs_list(&mut log_list, log_collection.get(selected_label).cloned());
     });
-    (label_select_view, LOG_LIST_VIEW_NAME.to_owned())
 }
 
 fn reload_logs_list(log_list: &mut ListView, log_collection: Option<GroupedTotals>) {
@@ -81,10 +210,13 @@ fn add_item_to_log_list(log_list: &mut ListView, label: &str, total: f64) {
 mod test {
     use std::collections::HashMap;
 
+    use gregorian::{Month, YearMonth};
     use im::vector;
+    use itertools::Itertools;
+    use neo_budget::periods::PeriodGranularity;
     use neo_budget::stats::GroupedTotals;
 
-    use super::sorted_labeled_logs;
+    use super::{period_totals_display, sorted_labeled_logs};
 
     #[test]
     fn test_sorted_labeled_logs() {
@@ -108,4 +240,26 @@ mod test {
             ],
         );
     }
+
+    #[test]
+    fn test_period_totals_display() {
+        let totals = |total: f64| GroupedTotals {
+            labeled: HashMap::from([("rent".to_owned(), total)]),
+            unlabeled: 0.0,
+        };
+        let by_month = HashMap::from([
+            (YearMonth::new(2024, Month::March), totals(500.0)),
+            (YearMonth::new(2024, Month::April), totals(510.0)),
+            (YearMonth::new(2024, Month::May), totals(520.0)),
+        ]);
+
+        let display = period_totals_display(&by_month, PeriodGranularity::Quarter);
+        let rent = display
+            .iter()
+            .map(|(label, totals)| (label.as_str(), totals.labeled["rent"]))
+            .sorted_by(|left, right| left.0.cmp(right.0))
+            .collect::<Vec<(&str, f64)>>();
+
+        assert_eq!(rent, vec![("2024 Q1", 500.0), ("2024 Q2", 1030.0)]);
+    }
 }
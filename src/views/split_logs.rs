@@ -0,0 +1,265 @@
+use cursive::{
+    view::{Nameable, Resizable, Scrollable},
+    views::{Canvas, Dialog, EditView, LinearLayout, SelectView, TextView},
+    Cursive, With,
+};
+use gregorian::{Date, Month, YearMonth};
+use neo_budget::finance::{Finance, FinanceLog, LogSplit, Price};
+
+use super::logs::log_text;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const SPLIT_LINES_NAME: &str = "split_lines";
+const SPLIT_RESULT_NAME: &str = "split_result";
+
+/// Lists the split logs, to edit one or start a new one.
+pub fn split_logs_view(finance: &Finance) -> Dialog {
+    let mut select = SelectView::<Option<usize>>::new().item("(new split log)", None);
+    for (index, log) in finance.logs.iter().enumerate() {
+        if log.is_split() {
+            select.add_item(log_text(log), Some(index));
+        }
+    }
+
+    select
+        .on_submit(|siv, index| {
+            let log =
+                index.and_then(|index| get_finance_app(siv).finance().logs.get(index).cloned());
+            siv.pop_layer();
+            siv.add_layer(split_log_editor(*index, log));
+        })
+        .scrollable()
+        .wrap_with(Dialog::around)
+        .title("Split logs")
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+/// Edits the split log at `index` as a whole, or a new one if `index` is
+/// `None`.
+fn split_log_editor(index: Option<usize>, log: Option<FinanceLog>) -> Dialog {
+    let log = log.unwrap_or_else(|| FinanceLog::split("", Vec::new(), Date::today().year_month()));
+
+    let mut lines = LinearLayout::vertical();
+    for split in log
+        .splits
+        .iter()
+        .cloned()
+        .chain(Some(LogSplit::new("", 0.0)))
+    {
+        lines.add_child(split_line(lines.len(), &split));
+    }
+
+    let layout = LinearLayout::vertical()
+        .child(TextView::new("Name"))
+        .child(
+            EditView::new()
+                .content(log.product.clone())
+                .with_name("split_name")
+                .fixed_width(20),
+        )
+        .child(TextView::new("Year-Month"))
+        .child(
+            LinearLayout::horizontal()
+                .child(
+                    EditView::new()
+                        .content(log.year_month.year().to_string())
+                        .with_name("split_year")
+                        .fixed_width(5),
+                )
+                .child(Canvas::new(()).fixed_width(2))
+                .child(
+                    EditView::new()
+                        .content(log.year_month.month().to_number().to_string())
+                        .with_name("split_month")
+                        .fixed_width(3),
+                ),
+        )
+        .child(TextView::new("Account (optional)"))
+        .child(
+            EditView::new()
+                .content(log.account.clone().unwrap_or_default())
+                .with_name("split_account")
+                .fixed_width(20),
+        )
+        .child(TextView::new("Lines (product, price)"))
+        .child(lines.with_name(SPLIT_LINES_NAME))
+        .child(Canvas::new(()))
+        .child(TextView::empty().with_name(SPLIT_RESULT_NAME));
+
+    Dialog::around(layout.scrollable())
+        .title("Split log")
+        .button("Add line", |siv| {
+            siv.call_on_name(SPLIT_LINES_NAME, |lines: &mut LinearLayout| {
+                lines.add_child(split_line(lines.len(), &LogSplit::new("", 0.0)))
+            });
+        })
+        .button("Save", move |siv| save_split_log(siv, index))
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn split_line(line: usize, split: &LogSplit) -> LinearLayout {
+    let price = match split.product.is_empty() {
+        true => String::new(),
+        false => split.price.to_string(),
+    };
+
+    LinearLayout::horizontal()
+        .child(
+            EditView::new()
+                .content(split.product.clone())
+                .with_name(format!("split_product_{line}"))
+                .fixed_width(20),
+        )
+        .child(Canvas::new(()).fixed_width(1))
+        .child(
+            EditView::new()
+                .content(price)
+                .with_name(format!("split_price_{line}"))
+                .fixed_width(10),
+        )
+}
+
+fn save_split_log(siv: &mut Cursive, index: Option<usize>) {
+    let input = |siv: &mut Cursive, name: &str| {
+        siv.find_name::<EditView>(name)
+            .expect("Couldn't find split log input")
+            .get_content()
+            .trim()
+            .to_owned()
+    };
+
+    let line_count = siv
+        .call_on_name(SPLIT_LINES_NAME, |lines: &mut LinearLayout| lines.len())
+        .unwrap_or_default();
+
+    // Lines left without a product are ignored.
+    let splits = (0..line_count)
+        .map(|line| {
+            (
+                input(siv, &format!("split_product_{line}")),
+                input(siv, &format!("split_price_{line}")),
+            )
+        })
+        .filter(|(product, _)| !product.is_empty())
+        .map(|(product, price)| Ok(LogSplit::new(&product, price.parse::<Price>()?)))
+        .collect::<Result<Vec<LogSplit>, std::num::ParseFloatError>>();
+
+    let name = input(siv, "split_name");
+    let year = input(siv, "split_year").parse::<i16>();
+    let month = input(siv, "split_month").parse::<u8>().map(Month::new);
+    let account = input(siv, "split_account");
+
+    let app = get_finance_app(siv);
+    let finance = app.finance();
+
+    let result = match (name.as_str(), year, month, splits) {
+        ("", _, _, _) => "Name can't be empty!".to_owned(),
+        (_, _, _, Ok(splits)) if splits.len() < 2 => "Add at least two lines!".to_owned(),
+        _ if !account.is_empty() && !finance.accounts().contains_key(&account) => {
+            "Unknown account!".to_owned()
+        }
+
+        (name, Ok(year), Ok(Ok(month)), Ok(splits)) => {
+            let log = split_log_from(
+                index.and_then(|index| finance.logs.get(index).cloned()),
+                name,
+                splits,
+                YearMonth::new(year, month),
+                (!account.is_empty()).then_some(account),
+            );
+            let total = log.price;
+
+            let (description, finance) = match index {
+                Some(index) => (
+                    format!("Edited split log {name}: {total:.2}"),
+                    finance.with_replaced_log(index, log),
+                ),
+                None => (
+                    format!("Added split log {name}: {total:.2}"),
+                    finance.with_log(log),
+                ),
+            };
+
+            set_finance_app(siv, app.with_edit(&description, finance));
+            siv.pop_layer();
+
+            return;
+        }
+
+        _ => "Invalid input!".to_owned(),
+    };
+
+    siv.call_on_name(SPLIT_RESULT_NAME, |view: &mut TextView| {
+        view.set_content(result)
+    });
+}
+
+/// The split log saved by the editor. When editing `existing`, the fields
+/// the editor doesn't show, like tags or attachments, are kept.
+fn split_log_from(
+    existing: Option<FinanceLog>,
+    name: &str,
+    splits: Vec<LogSplit>,
+    year_month: YearMonth,
+    account: Option<String>,
+) -> FinanceLog {
+    let log = FinanceLog::split(name, splits, year_month);
+
+    match existing {
+        Some(existing) => FinanceLog {
+            product: log.product,
+            year_month,
+            splits: log.splits,
+            price: log.price,
+            account,
+            ..existing
+        },
+        None => FinanceLog { account, ..log },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::finance::{FinanceLog, LogSplit};
+    use neo_budget::reimbursements::{Reimbursable, ReimbursementStatus};
+
+    use super::split_log_from;
+
+    #[test]
+    fn test_split_log_from_keeps_other_fields() {
+        let reimbursable = Reimbursable {
+            status: ReimbursementStatus::Submitted,
+            reimbursed_by: None,
+        };
+        let existing = FinanceLog {
+            reimbursable: Some(reimbursable.clone()),
+            ..FinanceLog::split(
+                "groceries",
+                vec![LogSplit::new("bread", 2.0), LogSplit::new("milk", 1.0)],
+                YearMonth::new(2024, Month::May),
+            )
+            .with_tag("trip")
+        };
+
+        let log = split_log_from(
+            Some(existing),
+            "market",
+            vec![LogSplit::new("bread", 3.0), LogSplit::new("eggs", 4.0)],
+            YearMonth::new(2024, Month::June),
+            Some("cash".to_owned()),
+        );
+
+        assert_eq!(log.product, "market");
+        assert_eq!(log.price, 7.0);
+        assert_eq!(log.year_month, YearMonth::new(2024, Month::June));
+        assert_eq!(log.splits.len(), 2);
+        assert_eq!(log.account, Some("cash".to_owned()));
+        assert!(log.tags.contains("trip"));
+        assert_eq!(log.reimbursable, Some(reimbursable));
+    }
+}
@@ -0,0 +1,13 @@
+use cursive::{view::Nameable, views::NamedView, views::TextView, Cursive};
+
+const STATUS_LINE_NAME: &str = "status_line";
+
+pub fn status_line_view() -> NamedView<TextView> {
+    TextView::empty().with_name(STATUS_LINE_NAME)
+}
+
+pub fn set_status(siv: &mut Cursive, status: &str) {
+    siv.call_on_name(STATUS_LINE_NAME, |view: &mut TextView| {
+        view.set_content(status)
+    });
+}
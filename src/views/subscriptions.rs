@@ -0,0 +1,141 @@
+use cursive::{
+    view::{Nameable, Scrollable},
+    views::{Dialog, Panel, SelectView, TextView},
+    Cursive, With,
+};
+use neo_budget::finance::Finance;
+use neo_budget::stats::FinanceStats;
+use neo_budget::subscriptions::Subscription;
+
+use super::status_line::set_status;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const SUBSCRIPTIONS_NAME: &str = "subscriptions";
+
+/// Lists the subscriptions detected in the logs, to track or dismiss them.
+pub fn subscriptions_view(finance: &Finance) -> Dialog {
+    let mut subscription_select =
+        SelectView::<Subscription>::new().on_submit(|siv, subscription: &Subscription| {
+            siv.add_layer(subscription_actions_view(subscription));
+        });
+    set_subscription_items(&mut subscription_select, finance);
+
+    subscription_select
+        .with_name(SUBSCRIPTIONS_NAME)
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Subscriptions"))
+        .wrap_with(Dialog::around)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn set_subscription_items(subscription_select: &mut SelectView<Subscription>, finance: &Finance) {
+    subscription_select.clear();
+    for subscription in FinanceStats::new(finance.clone()).subscriptions() {
+        subscription_select.add_item(subscription_text(&subscription), subscription);
+    }
+}
+
+fn subscription_text(subscription: &Subscription) -> String {
+    let interval = match subscription.interval_months {
+        1 => "every month".to_owned(),
+        12 => "every year".to_owned(),
+        months => format!("every {months} months"),
+    };
+
+    format!(
+        "{}: {:.2} {interval}, {:.2} a year, next on {}",
+        subscription.name,
+        subscription.amount,
+        subscription.annual_cost(),
+        subscription.next_charge()
+    )
+}
+
+fn subscription_actions_view(subscription: &Subscription) -> Dialog {
+    let track = subscription.clone();
+    let dismiss = subscription.clone();
+
+    Dialog::around(TextView::new(subscription_text(subscription)))
+        .title(&subscription.name)
+        .button("Track as recurring", move |siv| {
+            track_subscription(siv, &track)
+        })
+        .button("Dismiss", move |siv| dismiss_subscription(siv, &dismiss))
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn track_subscription(siv: &mut Cursive, subscription: &Subscription) {
+    let Some(recurring) = subscription.to_recurring() else {
+        set_status(siv, "Only monthly or yearly subscriptions can be tracked.");
+        return;
+    };
+
+    let app = get_finance_app(siv);
+    let finance = app.finance().with_recurring(recurring);
+    set_finance_app(
+        siv,
+        app.with_edit(
+            &format!("Tracked subscription {}", subscription.name),
+            finance.clone(),
+        ),
+    );
+
+    siv.pop_layer();
+    reload_subscriptions(siv, &finance);
+}
+
+fn dismiss_subscription(siv: &mut Cursive, subscription: &Subscription) {
+    let app = get_finance_app(siv);
+    let finance = app
+        .finance()
+        .with_dismissed_subscription(&subscription.name);
+    set_finance_app(
+        siv,
+        app.with_edit(
+            &format!("Dismissed subscription {}", subscription.name),
+            finance.clone(),
+        ),
+    );
+
+    siv.pop_layer();
+    reload_subscriptions(siv, &finance);
+}
+
+fn reload_subscriptions(siv: &mut Cursive, finance: &Finance) {
+    siv.call_on_name(
+        SUBSCRIPTIONS_NAME,
+        |subscriptions: &mut SelectView<Subscription>| {
+            set_subscription_items(subscriptions, finance)
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::subscriptions::Subscription;
+
+    use super::subscription_text;
+
+    #[test]
+    fn test_subscription_text() {
+        let subscription = Subscription {
+            name: "Flix".to_owned(),
+            product: "streaming".to_owned(),
+            account: None,
+            interval_months: 3,
+            amount: 30.0,
+            charges: 4,
+            last_charge: YearMonth::new(2024, Month::May),
+        };
+
+        assert_eq!(
+            subscription_text(&subscription),
+            "Flix: 30.00 every 3 months, 120.00 a year, next on 2024-08"
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use std::fs;
+
+use cursive::{
+    view::{Nameable, Resizable, Scrollable},
+    views::{Dialog, EditView, LinearLayout, Panel, SelectView, TextView},
+    Cursive, With,
+};
+use gregorian::{Date, Year};
+use neo_budget::finance::{Finance, FinanceLog};
+use neo_budget::stats::FinanceStats;
+use neo_budget::taxes::TaxSummary;
+
+use super::logs::log_text;
+use crate::category_separator_from_env;
+use crate::siv::{get_finance_app, set_finance_app};
+
+const TAX_YEAR_INPUT_NAME: &str = "tax_year";
+const TAX_SUMMARY_TEXT_NAME: &str = "tax_summary_text";
+const TAX_CATEGORY_INPUT_NAME: &str = "tax_category";
+const TAX_BUCKET_INPUT_NAME: &str = "tax_bucket";
+const TAX_LOG_BUCKET_INPUT_NAME: &str = "tax_log_bucket";
+const TAX_EXPORT_PATH_INPUT_NAME: &str = "tax_export_path";
+const TAX_INFO_TEXT_NAME: &str = "tax_info_text";
+
+/// The tax summary of a year, with its buckets and the logs in them.
+pub fn taxes_view(finance: &Finance) -> Dialog {
+    let year = Date::today().year();
+
+    LinearLayout::vertical()
+        .child(TextView::new("Year"))
+        .child(
+            EditView::new()
+                .content(year.to_string())
+                .on_submit(|siv, _| reload_tax_summary(siv))
+                .with_name(TAX_YEAR_INPUT_NAME)
+                .fixed_width(6),
+        )
+        .child(
+            TextView::new(tax_summary_text(&tax_summary(finance, year)))
+                .with_name(TAX_SUMMARY_TEXT_NAME)
+                .scrollable(),
+        )
+        .wrap_with(|view| Panel::new(view).title("Taxes"))
+        .wrap_with(Dialog::around)
+        .button("Category bucket", |siv| {
+            siv.add_layer(category_bucket_view());
+        })
+        .button("Log bucket", |siv| {
+            let finance = get_finance_app(siv).finance();
+            siv.add_layer(log_bucket_list_view(&finance));
+        })
+        .button("Export CSV", |siv| match selected_year(siv) {
+            Some(year) => siv.add_layer(export_view(year)),
+            None => siv.add_layer(Dialog::info("Invalid year!")),
+        })
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn tax_summary(finance: &Finance, year: Year) -> TaxSummary {
+    FinanceStats::new(finance.clone())
+        .with_category_separator(&category_separator_from_env())
+        .tax_summary(year)
+}
+
+fn tax_summary_text(summary: &TaxSummary) -> String {
+    if summary.buckets.is_empty() {
+        return format!("Nothing counts towards a tax bucket in {}.", summary.year);
+    }
+
+    summary
+        .buckets
+        .iter()
+        .map(|(bucket, bucket_summary)| {
+            Some(format!("{bucket}: {:.2}", bucket_summary.total))
+                .into_iter()
+                .chain(bucket_summary.items.iter().map(|item| {
+                    format!(
+                        "  {} {} {:.2}",
+                        item.log.year_month, item.product, item.amount
+                    )
+                }))
+                .collect::<Vec<String>>()
+                .join("\n")
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+fn selected_year(siv: &mut Cursive) -> Option<Year> {
+    siv.find_name::<EditView>(TAX_YEAR_INPUT_NAME)
+        .and_then(|input| input.get_content().trim().parse::<i16>().ok())
+        .map(Year::new)
+}
+
+fn reload_tax_summary(siv: &mut Cursive) {
+    let Some(year) = selected_year(siv) else {
+        return;
+    };
+    let finance = get_finance_app(siv).finance();
+
+    siv.call_on_name(TAX_SUMMARY_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(tax_summary_text(&tax_summary(&finance, year)))
+    });
+}
+
+fn category_bucket_view() -> Dialog {
+    LinearLayout::vertical()
+        .child(TextView::new("Category"))
+        .child(
+            EditView::new()
+                .with_name(TAX_CATEGORY_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::new("Tax bucket"))
+        .child(
+            EditView::new()
+                .with_name(TAX_BUCKET_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .child(TextView::empty().with_name(TAX_INFO_TEXT_NAME))
+        .wrap_with(Dialog::around)
+        .button("Ok", submit_category_bucket)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn submit_category_bucket(siv: &mut Cursive) {
+    let input = |siv: &mut Cursive, name: &str| {
+        siv.find_name::<EditView>(name)
+            .expect("Couldn't find tax bucket input")
+            .get_content()
+            .trim()
+            .to_owned()
+    };
+
+    let category = input(siv, TAX_CATEGORY_INPUT_NAME);
+    let bucket = input(siv, TAX_BUCKET_INPUT_NAME);
+
+    let info = match (category.as_str(), bucket.as_str()) {
+        ("", _) | (_, "") => "Invalid input!".to_owned(),
+
+        (category, bucket) => {
+            let app = get_finance_app(siv);
+            let description = format!("Set tax bucket of {category} to {bucket}");
+            let finance = app.finance().with_tax_bucket(category, bucket);
+            set_finance_app(siv, app.with_edit(&description, finance));
+            reload_tax_summary(siv);
+
+            format!("{description}.")
+        }
+    };
+
+    siv.call_on_name(TAX_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+fn log_bucket_list_view(finance: &Finance) -> Dialog {
+    let mut log_select = SelectView::<usize>::new().on_submit(|siv, index: &usize| {
+        let finance = get_finance_app(siv).finance();
+        if let Some(log) = finance.logs.get(*index) {
+            siv.add_layer(log_bucket_view(log, *index));
+        }
+    });
+    for (index, log) in finance.logs.iter().enumerate().rev() {
+        let bucket = match &log.tax_bucket {
+            Some(bucket) => format!(" [{bucket}]"),
+            None => String::new(),
+        };
+        log_select.add_item(format!("{}{bucket}", log_text(log)), index);
+    }
+
+    log_select
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Logs, newest first"))
+        .wrap_with(Dialog::around)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn log_bucket_view(log: &FinanceLog, index: usize) -> Dialog {
+    LinearLayout::vertical()
+        .child(TextView::new("Tax bucket (empty for the category's)"))
+        .child(
+            EditView::new()
+                .content(log.tax_bucket.clone().unwrap_or_default())
+                .with_name(TAX_LOG_BUCKET_INPUT_NAME)
+                .fixed_width(20),
+        )
+        .wrap_with(|view| Panel::new(view).title(log_text(log)))
+        .wrap_with(Dialog::around)
+        .button("Ok", move |siv| submit_log_bucket(siv, index))
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn submit_log_bucket(siv: &mut Cursive, index: usize) {
+    let bucket = siv
+        .find_name::<EditView>(TAX_LOG_BUCKET_INPUT_NAME)
+        .expect("Couldn't find tax bucket input")
+        .get_content()
+        .trim()
+        .to_owned();
+
+    let app = get_finance_app(siv);
+    let finance = app.finance();
+    let Some(log) = finance.logs.get(index).cloned() else {
+        return;
+    };
+
+    let description = match bucket.as_str() {
+        "" => format!("Cleared tax bucket of {}", log.product),
+        bucket => format!("Set tax bucket of {} to {bucket}", log.product),
+    };
+    let log = FinanceLog {
+        tax_bucket: Some(bucket).filter(|bucket| !bucket.is_empty()),
+        ..log
+    };
+    set_finance_app(
+        siv,
+        app.with_edit(&description, finance.with_replaced_log(index, log)),
+    );
+
+    // Pops the bucket form and the now outdated log list.
+    siv.pop_layer();
+    siv.pop_layer();
+    reload_tax_summary(siv);
+}
+
+fn export_view(year: Year) -> Dialog {
+    LinearLayout::vertical()
+        .child(TextView::new("Save to file"))
+        .child(
+            EditView::new()
+                .content(format!("taxes-{year}.csv"))
+                .with_name(TAX_EXPORT_PATH_INPUT_NAME)
+                .fixed_width(30),
+        )
+        .child(TextView::empty().with_name(TAX_INFO_TEXT_NAME))
+        .wrap_with(|view| Panel::new(view).title(format!("Export {year}")))
+        .wrap_with(Dialog::around)
+        .button("Export", move |siv| export_tax_summary(siv, year))
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn export_tax_summary(siv: &mut Cursive, year: Year) {
+    let path = siv
+        .find_name::<EditView>(TAX_EXPORT_PATH_INPUT_NAME)
+        .expect("Couldn't find export path input")
+        .get_content()
+        .trim()
+        .to_owned();
+
+    let summary = tax_summary(&get_finance_app(siv).finance(), year);
+
+    let info = match path.as_str() {
+        "" => "File can't be empty!".to_owned(),
+        path => match fs::write(path, summary.to_csv()) {
+            Ok(()) => format!("Wrote the {year} tax summary to {path}."),
+            Err(_) => format!("Couldn't write to file {path}!"),
+        },
+    };
+
+    siv.call_on_name(TAX_INFO_TEXT_NAME, |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, Year, YearMonth};
+    use neo_budget::finance::FinanceLog;
+    use neo_budget::taxes::{TaxItem, TaxSummary};
+
+    use super::tax_summary_text;
+
+    #[test]
+    fn test_tax_summary_text() {
+        let year = Year::new(2024);
+        assert_eq!(
+            tax_summary_text(&TaxSummary::new(year)),
+            "Nothing counts towards a tax bucket in 2024."
+        );
+
+        let log = FinanceLog::new("dentist", 80.0, YearMonth::new(2024, Month::March));
+        let summary = TaxSummary::new(year).with_item(
+            "Medical",
+            TaxItem {
+                log,
+                product: "dentist".to_owned(),
+                amount: 80.0,
+            },
+        );
+
+        assert_eq!(
+            tax_summary_text(&summary),
+            "Medical: 80.00\n  2024-03 dentist 80.00"
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use cursive::{
+    view::Scrollable,
+    views::{Dialog, Panel, SelectView, TextView},
+    With,
+};
+use neo_budget::finance::Finance;
+use neo_budget::stats::{FinanceStats, UnitPriceReport};
+
+use crate::siv::get_finance_app;
+
+/// Lists the products bought with a quantity, to show the unit price report
+/// of one.
+pub fn unit_prices_view(finance: &Finance) -> Dialog {
+    SelectView::<String>::new()
+        .with_all_str(FinanceStats::new(finance.clone()).products_with_quantities())
+        .on_submit(|siv, product: &String| {
+            let stats = FinanceStats::new(get_finance_app(siv).finance());
+            if let Some(report) = stats.unit_price_report(product) {
+                siv.add_layer(unit_price_report_view(product, &report));
+            }
+        })
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title("Unit prices"))
+        .wrap_with(Dialog::around)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn unit_price_report_view(product: &str, report: &UnitPriceReport) -> Dialog {
+    TextView::new(unit_price_report_text(report))
+        .scrollable()
+        .wrap_with(|view| Panel::new(view).title(format!("{product} per {}", report.unit)))
+        .wrap_with(Dialog::around)
+        .button("Back", |siv| {
+            siv.pop_layer();
+        })
+}
+
+fn unit_price_report_text(report: &UnitPriceReport) -> String {
+    let month_lines = report.months.iter().map(|month| match month.change {
+        Some(change) => format!(
+            "{}: {:.2} ({change:+.1}%)",
+            month.year_month, month.unit_price
+        ),
+        None => format!("{}: {:.2}", month.year_month, month.unit_price),
+    });
+
+    let summary = format!(
+        "Min {:.2}, max {:.2}, average {:.2}",
+        report.min, report.max, report.average
+    );
+
+    month_lines
+        .chain(["".to_owned(), summary])
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use gregorian::{Month, YearMonth};
+    use neo_budget::finance::{Finance, FinanceLog};
+    use neo_budget::stats::FinanceStats;
+
+    use super::unit_price_report_text;
+
+    #[test]
+    fn test_unit_price_report_text() {
+        let finance = Finance::new()
+            .with_log(
+                FinanceLog::new("rice", 4.0, YearMonth::new(2024, Month::May))
+                    .with_quantity(2.0, "kg"),
+            )
+            .with_log(
+                FinanceLog::new("rice", 3.0, YearMonth::new(2024, Month::June))
+                    .with_quantity(2.0, "kg"),
+            );
+
+        let report = FinanceStats::new(finance)
+            .unit_price_report("rice")
+            .unwrap();
+
+        assert_eq!(
+            unit_price_report_text(&report),
+            "2024-05: 2.00\n2024-06: 1.50 (-25.0%)\n\nMin 1.50, max 2.00, average 1.75"
+        );
+    }
+}